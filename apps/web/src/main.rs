@@ -10,8 +10,11 @@
 
 use bevy::prelude::*;
 use bevy::log::LogPlugin;
-use bevy_egui::EguiPlugin;
-use wave_physics::WavePhysicsPlugin;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+use ez_core::registry::{register_simulations, ActiveSimulation, SimulationRegistry};
+use ez_renderer::antialiasing::{anti_aliasing_controls, AntiAliasing, AntiAliasingPlugin};
+use ez_renderer::viewport::SplitViewportPlugin;
+use ez_ui::widgets::about_simulation;
 
 fn main() {
     // ═══════════════════════════════════════════════════════════════════
@@ -26,37 +29,91 @@ fn main() {
     // Application
     // ═══════════════════════════════════════════════════════════════════
 
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "Entropy Zero - Scientific Simulation Platform".into(),
-                        canvas: Some("#canvas".into()),
-                        fit_canvas_to_parent: true,
-                        prevent_default_event_handling: true,
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(LogPlugin {
-                    // Log levels: TRACE < DEBUG < INFO < WARN < ERROR
-                    level: bevy::log::Level::DEBUG,
-                    // Filter noisy modules, keep our code verbose
-                    filter: "wgpu=error,wgpu_core=error,wgpu_hal=error,\
-                             naga=warn,bevy_render=info,bevy_ecs=warn,\
-                             entropy_zero=debug,classical_mechanics=debug,\
-                             wave_physics=debug,ez_core=debug,ez_physics=debug".to_string(),
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Entropy Zero - Scientific Simulation Platform".into(),
+                    canvas: Some("#canvas".into()),
+                    fit_canvas_to_parent: true,
+                    prevent_default_event_handling: true,
                     ..default()
                 }),
-        )
-        .add_plugins(EguiPlugin)
-        // Simulation plugins - comment out to switch between simulations
-        // .add_plugins(ClassicalMechanicsPlugin)
-        .add_plugins(WavePhysicsPlugin)
-        // Add debug startup message
-        .add_systems(Startup, log_startup_info)
-        .run();
+                ..default()
+            })
+            .set(LogPlugin {
+                // Log levels: TRACE < DEBUG < INFO < WARN < ERROR
+                level: bevy::log::Level::DEBUG,
+                // Filter noisy modules, keep our code verbose
+                filter: "wgpu=error,wgpu_core=error,wgpu_hal=error,\
+                         naga=warn,bevy_render=info,bevy_ecs=warn,\
+                         entropy_zero=debug,classical_mechanics=debug,\
+                         wave_physics=debug,ez_core=debug,ez_physics=debug".to_string(),
+                ..default()
+            }),
+    )
+    .add_plugins(EguiPlugin)
+    .add_plugins(AntiAliasingPlugin)
+    // Side-by-side comparisons, e.g. the ripple tank with and without slits
+    .add_plugins(SplitViewportPlugin)
+    // Add debug startup message
+    .add_systems(Startup, log_startup_info)
+    .add_systems(Update, render_simulation_menu);
+
+    // Simulations - the first one registered is active at startup
+    let mut simulations = wave_physics::all_simulations();
+    simulations.extend(classical_mechanics::all_simulations());
+    register_simulations(&mut app, simulations);
+
+    // F9 or `--capture-thumbnails` refreshes the gallery thumbnails.
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+    app.add_plugins(ez_renderer::thumbnail::ThumbnailCapturePlugin);
+
+    app.run();
+}
+
+/// Small window for switching between registered simulations.
+fn render_simulation_menu(
+    mut contexts: EguiContexts,
+    mut registry: ResMut<SimulationRegistry>,
+    mut next_state: ResMut<NextState<ActiveSimulation>>,
+    mut anti_aliasing: ResMut<AntiAliasing>,
+) {
+    let active_name = registry
+        .active()
+        .and_then(|id| registry.get(id))
+        .map_or("None", |sim| sim.name());
+
+    let mut selected = None;
+    egui::Window::new("Simulations")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .resizable(false)
+        .collapsible(true)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::ComboBox::from_id_source("active_simulation")
+                .selected_text(active_name)
+                .show_ui(ui, |ui| {
+                    for meta in registry.metadata() {
+                        let is_active = registry.active() == Some(meta.id);
+                        if ui.selectable_label(is_active, meta.name).clicked() && !is_active {
+                            selected = Some(meta.id);
+                        }
+                    }
+                });
+
+            if let Some(meta) = registry.metadata().iter().find(|meta| registry.active() == Some(meta.id)) {
+                about_simulation(ui, meta);
+            }
+
+            ui.separator();
+            anti_aliasing_controls(ui, &mut anti_aliasing);
+        });
+
+    if let Some(id) = selected {
+        info!("Switching simulation to {}", id);
+        registry.switch_to(id, &mut next_state);
+    }
 }
 
 /// Log startup information to browser console.
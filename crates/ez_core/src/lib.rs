@@ -6,21 +6,37 @@
 //! - The `Simulation` trait that all simulations must implement
 //! - `SimulationCategory` enum for scientific classification
 //! - Parameter definitions for UI generation
+//! - A registry for switching simulations at runtime
 //! - Common math utilities
+//! - SI-prefixed display formatting
+//! - Adaptive quality that scales work to hold a target frame rate
+//! - A startup benchmark sorting machines into performance tiers
+//! - Recording of scalar observables over a run, with CSV export
 
 pub mod math;
 pub mod parameters;
+pub mod performance;
+pub mod quality;
+pub mod recording;
+pub mod registry;
 pub mod taxonomy;
 pub mod traits;
+pub mod units;
 
-pub use parameters::{ParameterDef, ParameterValue};
+pub use parameters::{ParameterDef, ParameterPreset, ParameterValue};
+pub use registry::{register_simulations, SimulationRegistry};
 pub use taxonomy::SimulationCategory;
 pub use traits::Simulation;
 
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use crate::math::*;
-    pub use crate::parameters::{ParameterDef, ParameterValue};
+    pub use crate::parameters::{ParameterDef, ParameterPreset, ParameterValue};
+    pub use crate::performance::{PerformanceProfile, PerformanceProfilePlugin, PerformanceTier};
+    pub use crate::quality::{AdaptiveQuality, AdaptiveQualityPlugin};
+    pub use crate::recording::{DataRecorder, DataRecorderPlugin};
+    pub use crate::registry::*;
     pub use crate::taxonomy::*;
     pub use crate::traits::Simulation;
+    pub use crate::units::{format_si, format_with_unit, parse_si};
 }
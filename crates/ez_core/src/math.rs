@@ -36,6 +36,154 @@ pub fn map_range(value: f32, from_min: f32, from_max: f32, to_min: f32, to_max:
     to_min + normalized * (to_max - to_min)
 }
 
+/// In-place radix-2 fast Fourier transform.
+///
+/// `re` and `im` hold the real and imaginary parts and must have the same
+/// power-of-two length.
+pub fn fft_in_place(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    assert_eq!(n, im.len(), "fft: real and imaginary parts differ in length");
+    assert!(n.is_power_of_two(), "fft: length must be a power of two");
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Magnitude spectrum of a real signal, zero-padded to `size` samples.
+///
+/// The mean is removed and a Hann window applied before transforming.
+/// Returns the `size / 2` non-negative frequency bins; bin `k` corresponds
+/// to `k / (size * dt)` Hz for a sample interval `dt`.
+pub fn magnitude_spectrum(samples: &[f32], size: usize) -> Vec<f32> {
+    let samples = &samples[samples.len().saturating_sub(size)..];
+    let mean = samples.iter().sum::<f32>() / samples.len().max(1) as f32;
+
+    let mut re = vec![0.0; size];
+    let mut im = vec![0.0; size];
+    let last = samples.len().saturating_sub(1).max(1) as f32;
+    for (i, sample) in samples.iter().enumerate() {
+        let window = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / last).cos();
+        re[i] = (sample - mean) * window;
+    }
+
+    fft_in_place(&mut re, &mut im);
+    re.iter()
+        .zip(&im)
+        .take(size / 2)
+        .map(|(r, i)| (r * r + i * i).sqrt())
+        .collect()
+}
+
+pub use complex::Complex;
+
+/// Complex numbers for phasors and wavefunctions.
+pub mod complex {
+    use std::ops::{Add, Mul};
+
+    /// A complex number `re + i·im`.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub struct Complex {
+        pub re: f32,
+        pub im: f32,
+    }
+
+    impl Complex {
+        pub const ZERO: Self = Self::new(0.0, 0.0);
+        pub const ONE: Self = Self::new(1.0, 0.0);
+        pub const I: Self = Self::new(0.0, 1.0);
+
+        pub const fn new(re: f32, im: f32) -> Self {
+            Self { re, im }
+        }
+
+        /// Builds a complex number from modulus `r` and argument `theta`.
+        pub fn from_polar(r: f32, theta: f32) -> Self {
+            let (sin, cos) = theta.sin_cos();
+            Self::new(r * cos, r * sin)
+        }
+
+        /// `e^{iθ}`, the unit phasor at angle `theta`.
+        pub fn exp(theta: f32) -> Self {
+            Self::from_polar(1.0, theta)
+        }
+
+        /// Complex conjugate.
+        pub fn conj(self) -> Self {
+            Self::new(self.re, -self.im)
+        }
+
+        /// Modulus `|z|`.
+        pub fn abs(self) -> f32 {
+            self.re.hypot(self.im)
+        }
+
+        /// Squared modulus `|z|²`, e.g. a probability density.
+        pub fn norm_sqr(self) -> f32 {
+            self.re * self.re + self.im * self.im
+        }
+
+        /// Argument (phase angle) in `(-π, π]`.
+        pub fn arg(self) -> f32 {
+            self.im.atan2(self.re)
+        }
+    }
+
+    impl Add for Complex {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self {
+            Self::new(self.re + rhs.re, self.im + rhs.im)
+        }
+    }
+
+    impl Mul for Complex {
+        type Output = Self;
+
+        fn mul(self, rhs: Self) -> Self {
+            Self::new(self.re * rhs.re - self.im * rhs.im, self.re * rhs.im + self.im * rhs.re)
+        }
+    }
+
+    impl Mul<f32> for Complex {
+        type Output = Self;
+
+        fn mul(self, rhs: f32) -> Self {
+            Self::new(self.re * rhs, self.im * rhs)
+        }
+    }
+}
+
 /// Physical constants (SI units).
 pub mod constants {
     /// Speed of light in vacuum (m/s)
@@ -76,4 +224,38 @@ mod tests {
     fn test_map_range() {
         assert!((map_range(5.0, 0.0, 10.0, 0.0, 100.0) - 50.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_euler_identity() {
+        let z = Complex::exp(std::f32::consts::PI);
+        assert!((z.re + 1.0).abs() < 1e-6);
+        assert!(z.im.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_complex_modulus_and_product() {
+        let z = Complex::new(3.0, 4.0);
+        assert!((z.abs() - 5.0).abs() < 1e-6);
+        assert_eq!(z * z.conj(), Complex::new(25.0, 0.0));
+        let w = Complex::from_polar(2.0, 0.5) * Complex::from_polar(3.0, 0.25);
+        assert!((w.abs() - 6.0).abs() < 1e-5);
+        assert!((w.arg() - 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_spectrum_peak_at_signal_frequency() {
+        // 300 samples of a sine completing one cycle every 16 samples,
+        // zero-padded to 512: the peak lands in bin 512 / 16 = 32.
+        let samples: Vec<f32> = (0..300)
+            .map(|i| (2.0 * std::f32::consts::PI * i as f32 / 16.0).sin())
+            .collect();
+        let spectrum = magnitude_spectrum(&samples, 512);
+        let peak = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(bin, _)| bin);
+        assert_eq!(spectrum.len(), 256);
+        assert_eq!(peak, Some(32));
+    }
 }
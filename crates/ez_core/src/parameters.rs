@@ -28,6 +28,9 @@ pub enum ParameterDef {
         min: i32,
         max: i32,
         default: i32,
+        step: Option<i32>,
+        /// Spread the slider logarithmically, for ranges spanning decades.
+        logarithmic: bool,
     },
 
     /// Boolean toggle
@@ -38,6 +41,15 @@ pub enum ParameterDef {
         default: bool,
     },
 
+    /// 2D vector input
+    Vec2 {
+        id: &'static str,
+        name: &'static str,
+        description: &'static str,
+        default: [f32; 2],
+        unit: Option<&'static str>,
+    },
+
     /// 3D vector input
     Vec3 {
         id: &'static str,
@@ -72,6 +84,7 @@ impl ParameterDef {
             Self::Float { id, .. } => id,
             Self::Int { id, .. } => id,
             Self::Bool { id, .. } => id,
+            Self::Vec2 { id, .. } => id,
             Self::Vec3 { id, .. } => id,
             Self::Color { id, .. } => id,
             Self::Enum { id, .. } => id,
@@ -84,19 +97,72 @@ impl ParameterDef {
             Self::Float { name, .. } => name,
             Self::Int { name, .. } => name,
             Self::Bool { name, .. } => name,
+            Self::Vec2 { name, .. } => name,
             Self::Vec3 { name, .. } => name,
             Self::Color { name, .. } => name,
             Self::Enum { name, .. } => name,
         }
     }
+
+    /// Checks that the definition is self-consistent: a non-empty id, a
+    /// non-empty range containing the default, a positive step, and an
+    /// enum default that names one of the options.
+    pub fn validate(&self) -> Result<(), String> {
+        let id = self.id();
+        if id.is_empty() {
+            return Err(format!("parameter \"{}\" has an empty id", self.name()));
+        }
+
+        match *self {
+            Self::Float { min, max, default, step, .. } => {
+                check_range(id, min, max, default)?;
+                if step.is_some_and(|step| step <= 0.0) {
+                    return Err(format!("{id}: step must be positive"));
+                }
+            }
+            Self::Int { min, max, default, step, .. } => {
+                check_range(id, min, max, default)?;
+                if step.is_some_and(|step| step <= 0) {
+                    return Err(format!("{id}: step must be positive"));
+                }
+            }
+            Self::Enum { options, default_index, .. } => {
+                if default_index >= options.len() {
+                    return Err(format!(
+                        "{id}: default index {default_index} out of range for {} options",
+                        options.len()
+                    ));
+                }
+            }
+            Self::Bool { .. } | Self::Vec2 { .. } | Self::Vec3 { .. } | Self::Color { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(id: &str, min: T, max: T, default: T) -> Result<(), String> {
+    // Written as positive checks so NaN fails them.
+    let ordered = min < max;
+    let contains_default = min <= default && default <= max;
+    if !ordered {
+        return Err(format!("{id}: min {min} must be less than max {max}"));
+    }
+    if !contains_default {
+        return Err(format!("{id}: default {default} outside [{min}, {max}]"));
+    }
+    Ok(())
 }
 
+/// Named set of parameter values, keyed by parameter id.
+pub type ParameterPreset = (&'static str, Vec<(&'static str, ParameterValue)>);
+
 /// Runtime parameter value.
-#[derive(Debug, Clone, Reflect)]
+#[derive(Debug, Clone, PartialEq, Reflect)]
 pub enum ParameterValue {
     Float(f32),
     Int(i32),
     Bool(bool),
+    Vec2(Vec2),
     Vec3(Vec3),
     Color(Color),
     Enum(usize),
@@ -124,12 +190,33 @@ impl ParameterValue {
         }
     }
 
+    pub fn as_vec2(&self) -> Option<Vec2> {
+        match self {
+            Self::Vec2(v) => Some(*v),
+            _ => None,
+        }
+    }
+
     pub fn as_vec3(&self) -> Option<Vec3> {
         match self {
             Self::Vec3(v) => Some(*v),
             _ => None,
         }
     }
+
+    pub fn as_color(&self) -> Option<Color> {
+        match self {
+            Self::Color(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_enum(&self) -> Option<usize> {
+        match self {
+            Self::Enum(v) => Some(*v),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -149,5 +236,80 @@ mod tests {
             unit: Some("m/s²"),
         };
         assert_eq!(p.id(), "gravity");
+        assert_eq!(p.validate(), Ok(()));
+    }
+
+    fn float(id: &'static str, min: f32, max: f32, default: f32) -> ParameterDef {
+        ParameterDef::Float {
+            id,
+            name: "Float",
+            description: "",
+            min,
+            max,
+            default,
+            step: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_id() {
+        assert!(float("", 0.0, 1.0, 0.5).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_range() {
+        assert!(float("f", 1.0, 1.0, 1.0).validate().is_err());
+        assert!(float("f", 2.0, 1.0, 1.5).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_default_outside_range() {
+        assert!(float("f", 0.0, 1.0, 1.5).validate().is_err());
+        assert!(float("f", 0.0, 1.0, f32::NAN).validate().is_err());
+
+        let int = ParameterDef::Int {
+            id: "i",
+            name: "Int",
+            description: "",
+            min: 0,
+            max: 10,
+            default: -1,
+            step: None,
+            logarithmic: false,
+        };
+        assert!(int.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_nonpositive_step() {
+        let int = ParameterDef::Int {
+            id: "i",
+            name: "Int",
+            description: "",
+            min: 0,
+            max: 10,
+            default: 5,
+            step: Some(0),
+            logarithmic: false,
+        };
+        assert!(int.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_enum_default_past_options() {
+        let mut def = ParameterDef::Enum {
+            id: "e",
+            name: "Enum",
+            description: "",
+            options: &["a", "b"],
+            default_index: 2,
+        };
+        assert!(def.validate().is_err());
+
+        if let ParameterDef::Enum { default_index, .. } = &mut def {
+            *default_index = 1;
+        }
+        assert_eq!(def.validate(), Ok(()));
     }
 }
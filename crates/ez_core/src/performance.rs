@@ -0,0 +1,111 @@
+//! Hardware performance tiers for sizing simulations on first run.
+//!
+//! [`PerformanceProfilePlugin`] runs a short CPU benchmark when the app is
+//! built and stores the result in [`PerformanceProfile`]. Simulations pass
+//! its [`PerformanceTier`] to [`Simulation::recommended_defaults`] and
+//! apply the values before spawning, so a heavy default doesn't freeze a
+//! slow machine.
+//!
+//! On wasm32 the benchmark is timed with [`bevy::utils::Instant`], backed by
+//! `performance.now()`, since `std::time::Instant` panics in the browser.
+//!
+//! [`Simulation::recommended_defaults`]: crate::traits::Simulation::recommended_defaults
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::Instant;
+
+/// Particles stepped by the benchmark.
+const BENCHMARK_PARTICLES: usize = 10_000;
+/// How long the benchmark runs.
+const BENCHMARK_DURATION: Duration = Duration::from_millis(20);
+/// Particle updates per second at which a machine counts as medium or high.
+pub const MEDIUM_THROUGHPUT: f32 = 5.0e7;
+pub const HIGH_THROUGHPUT: f32 = 2.0e8;
+
+/// Plugin measuring the machine once into [`PerformanceProfile`].
+pub struct PerformanceProfilePlugin;
+
+impl Plugin for PerformanceProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let profile = PerformanceProfile::measure();
+        info!("Benchmark: {:.2e} particle updates/s, {:?} tier", profile.throughput, profile.tier);
+        app.insert_resource(profile);
+    }
+}
+
+/// Coarse speed class of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum PerformanceTier {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl PerformanceTier {
+    /// Tier for a measured throughput in particle updates per second.
+    pub fn from_throughput(throughput: f32) -> Self {
+        if throughput >= HIGH_THROUGHPUT {
+            Self::High
+        } else if throughput >= MEDIUM_THROUGHPUT {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// Result of the startup benchmark.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct PerformanceProfile {
+    /// Particle updates per second on one core.
+    pub throughput: f32,
+    pub tier: PerformanceTier,
+}
+
+impl PerformanceProfile {
+    /// Steps a block of falling, bouncing particles with semi-implicit Euler
+    /// for [`BENCHMARK_DURATION`] and counts the updates.
+    pub fn measure() -> Self {
+        const DT: f32 = 1.0 / 60.0;
+        let mut positions: Vec<Vec3> = (0..BENCHMARK_PARTICLES).map(|i| Vec3::new(i as f32, 10.0, 0.0)).collect();
+        let mut velocities = vec![Vec3::new(1.0, 0.0, -1.0); BENCHMARK_PARTICLES];
+
+        let started = Instant::now();
+        let mut steps = 0;
+        while started.elapsed() < BENCHMARK_DURATION {
+            for (position, velocity) in positions.iter_mut().zip(&mut velocities) {
+                velocity.y -= 9.8 * DT;
+                *position += *velocity * DT;
+                if position.y < 0.0 {
+                    position.y = -position.y;
+                    velocity.y = -velocity.y;
+                }
+            }
+            steps += 1;
+        }
+        std::hint::black_box(&positions);
+
+        let throughput = (steps * BENCHMARK_PARTICLES) as f32 / started.elapsed().as_secs_f32();
+        Self { throughput, tier: PerformanceTier::from_throughput(throughput) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_split_at_thresholds() {
+        assert_eq!(PerformanceTier::from_throughput(0.0), PerformanceTier::Low);
+        assert_eq!(PerformanceTier::from_throughput(MEDIUM_THROUGHPUT), PerformanceTier::Medium);
+        assert_eq!(PerformanceTier::from_throughput(HIGH_THROUGHPUT * 10.0), PerformanceTier::High);
+        assert!(PerformanceTier::Low < PerformanceTier::High);
+
+        let profile = PerformanceProfile::measure();
+        assert!(profile.throughput > 0.0);
+        assert_eq!(profile.tier, PerformanceTier::from_throughput(profile.throughput));
+    }
+}
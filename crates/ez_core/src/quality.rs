@@ -0,0 +1,152 @@
+//! Adaptive quality: trading simulation work for a steady frame rate.
+//!
+//! [`AdaptiveQualityPlugin`] keeps a rolling average of real frame times in
+//! the [`AdaptiveQuality`] resource. When the average runs over the target
+//! frame time the quality level drops, and when there is headroom again it
+//! climbs back toward 1. Simulations read [`AdaptiveQuality::scale`] to size
+//! their per-frame work (FDTD steps, emitted particles) by that level.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+/// Frame rate aimed for by default, a 20 ms frame.
+pub const DEFAULT_TARGET_FPS: f32 = 50.0;
+/// Lowest quality level; work never drops below this fraction.
+pub const MIN_QUALITY: f32 = 0.1;
+/// Frames averaged before each adjustment.
+const FRAME_WINDOW: usize = 30;
+/// Factor applied to the level when frames run over budget.
+const STEP_DOWN: f32 = 0.8;
+/// Factor applied to the level when there is headroom.
+const STEP_UP: f32 = 1.1;
+/// Frames averaging under this fraction of the budget count as headroom.
+/// The gap to 1 keeps the level from flipping back and forth.
+const HEADROOM: f32 = 0.75;
+
+/// Plugin tracking frame times and adjusting [`AdaptiveQuality`].
+pub struct AdaptiveQualityPlugin;
+
+impl Plugin for AdaptiveQualityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AdaptiveQuality>().add_systems(First, record_frame_time);
+    }
+}
+
+/// How much per-frame work simulations should do, from the recent frame rate.
+///
+/// Off by default, so work is only scaled down once the user opts in.
+#[derive(Resource, Debug, Clone)]
+pub struct AdaptiveQuality {
+    pub enabled: bool,
+    pub target_fps: f32,
+    level: f32,
+    frame_times: VecDeque<f32>,
+}
+
+impl Default for AdaptiveQuality {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_fps: DEFAULT_TARGET_FPS,
+            level: 1.0,
+            frame_times: VecDeque::with_capacity(FRAME_WINDOW),
+        }
+    }
+}
+
+impl AdaptiveQuality {
+    /// Fraction of full work to do, from [`MIN_QUALITY`] to 1; always 1
+    /// while disabled.
+    pub fn level(&self) -> f32 {
+        if self.enabled {
+            self.level
+        } else {
+            1.0
+        }
+    }
+
+    /// Scales a per-frame amount of work by [`Self::level`], keeping at
+    /// least one unit of any nonzero amount.
+    pub fn scale(&self, work: usize) -> usize {
+        if work == 0 {
+            return 0;
+        }
+        ((work as f32 * self.level()).round() as usize).max(1)
+    }
+
+    /// Mean of the frames in the current window, in seconds.
+    pub fn average_frame_time(&self) -> Option<f32> {
+        (!self.frame_times.is_empty()).then(|| self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32)
+    }
+
+    /// Adds a frame and, once a full window has been seen, moves the level.
+    ///
+    /// The window starts over after every adjustment, so each decision is
+    /// based only on frames run at the current level.
+    pub fn record(&mut self, frame_time: f32) {
+        if self.frame_times.len() == FRAME_WINDOW {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        if !self.enabled || self.frame_times.len() < FRAME_WINDOW {
+            return;
+        }
+
+        let budget = 1.0 / self.target_fps.max(1.0);
+        let average = self.average_frame_time().unwrap_or(0.0);
+        let level = if average > budget {
+            (self.level * STEP_DOWN).max(MIN_QUALITY)
+        } else if average < budget * HEADROOM {
+            (self.level * STEP_UP).min(1.0)
+        } else {
+            self.level
+        };
+        if level != self.level {
+            self.level = level;
+            self.frame_times.clear();
+        }
+    }
+}
+
+fn record_frame_time(time: Res<Time<Real>>, mut quality: ResMut<AdaptiveQuality>) {
+    let frame_time = time.delta_seconds();
+    if frame_time > 0.0 {
+        quality.record(frame_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_drops_under_load_and_recovers() {
+        let mut quality = AdaptiveQuality::default();
+        assert!(!quality.enabled, "adaptive quality is opt-in");
+        quality.enabled = true;
+        for _ in 0..FRAME_WINDOW * 20 {
+            quality.record(0.05);
+        }
+        assert_eq!(quality.level(), MIN_QUALITY);
+        assert_eq!(quality.scale(4), 1);
+        assert_eq!(quality.scale(0), 0);
+
+        // Within the budget but without headroom, the level holds.
+        for _ in 0..FRAME_WINDOW * 5 {
+            quality.record(0.018);
+        }
+        assert_eq!(quality.level(), MIN_QUALITY);
+
+        for _ in 0..FRAME_WINDOW * 40 {
+            quality.record(0.005);
+        }
+        assert_eq!(quality.level(), 1.0);
+
+        quality.enabled = false;
+        for _ in 0..FRAME_WINDOW * 5 {
+            quality.record(0.05);
+        }
+        assert_eq!(quality.scale(1000), 1000);
+    }
+}
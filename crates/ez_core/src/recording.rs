@@ -0,0 +1,225 @@
+//! Recording scalar observables over a run.
+//!
+//! Simulation systems call [`DataRecorder::record`] with a channel name and a
+//! value each frame; the recorder stamps it with the current time and keeps
+//! the latest [`DEFAULT_CHANNEL_CAPACITY`] samples of every channel in a ring
+//! buffer. [`DataRecorder::to_csv`] lines the channels up by time for export.
+//!
+//! [`DataRecorderPlugin`] advances the recorder's clock with virtual time and
+//! clears it whenever the active simulation changes. Simulations with a clock
+//! of their own call [`DataRecorder::set_time`] before recording.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::registry::ActiveSimulation;
+
+/// Samples kept per channel before the oldest are dropped.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Plugin keeping the [`DataRecorder`] clock and clearing it between
+/// simulations.
+pub struct DataRecorderPlugin;
+
+impl Plugin for DataRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataRecorder>().add_systems(
+            First,
+            (clear_recorder.run_if(state_changed::<ActiveSimulation>), advance_recorder_clock).chain(),
+        );
+    }
+}
+
+/// One named time series.
+#[derive(Debug, Clone)]
+pub struct RecordedChannel {
+    pub name: String,
+    /// Whether new samples are kept; disabled channels keep what they have.
+    pub enabled: bool,
+    samples: VecDeque<(f32, f32)>,
+}
+
+impl RecordedChannel {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), enabled: true, samples: VecDeque::new() }
+    }
+
+    /// `(time, value)` pairs, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Values alone, oldest first, for plotting.
+    pub fn values(&self) -> Vec<f32> {
+        self.samples.iter().map(|&(_, value)| value).collect()
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().map(|&(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// Named ring buffers of scalar samples, in the order channels were first
+/// recorded.
+#[derive(Resource, Debug, Clone)]
+pub struct DataRecorder {
+    /// Samples kept per channel.
+    pub capacity: usize,
+    time: f32,
+    channels: Vec<RecordedChannel>,
+}
+
+impl Default for DataRecorder {
+    fn default() -> Self {
+        Self { capacity: DEFAULT_CHANNEL_CAPACITY, time: 0.0, channels: Vec::new() }
+    }
+}
+
+impl DataRecorder {
+    /// Time the next samples are stamped with.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    /// Appends `value` to the named channel, creating it on first use.
+    ///
+    /// A channel holds one sample per instant: recording again at the same
+    /// time replaces the value, so a paused simulation adds no rows.
+    pub fn record(&mut self, name: &str, value: f32) {
+        let index = match self.channels.iter().position(|channel| channel.name == name) {
+            Some(index) => index,
+            None => {
+                self.channels.push(RecordedChannel::new(name));
+                self.channels.len() - 1
+            }
+        };
+        let (time, capacity) = (self.time, self.capacity.max(1));
+        let channel = &mut self.channels[index];
+        if !channel.enabled {
+            return;
+        }
+        match channel.samples.back_mut() {
+            Some(last) if last.0 == time => last.1 = value,
+            _ => {
+                while channel.samples.len() >= capacity {
+                    channel.samples.pop_front();
+                }
+                channel.samples.push_back((time, value));
+            }
+        }
+    }
+
+    pub fn channel(&self, name: &str) -> Option<&RecordedChannel> {
+        self.channels.iter().find(|channel| channel.name == name)
+    }
+
+    pub fn channels(&self) -> &[RecordedChannel] {
+        &self.channels
+    }
+
+    pub fn channels_mut(&mut self) -> &mut [RecordedChannel] {
+        &mut self.channels
+    }
+
+    /// Drops every channel and its samples.
+    pub fn clear(&mut self) {
+        self.channels.clear();
+    }
+
+    /// Formats every channel with samples as CSV, one column each after a
+    /// leading time column. Each row is one instant; a channel without a
+    /// sample at that instant leaves its cell empty.
+    pub fn to_csv(&self) -> String {
+        let channels: Vec<&RecordedChannel> = self.channels.iter().filter(|channel| !channel.is_empty()).collect();
+        let mut times: Vec<f32> = channels.iter().flat_map(|channel| channel.samples()).map(|(time, _)| time).collect();
+        times.sort_by(f32::total_cmp);
+        times.dedup();
+
+        let mut csv = String::from("time");
+        for channel in &channels {
+            csv.push(',');
+            csv.push_str(&csv_field(&channel.name));
+        }
+        csv.push('\n');
+
+        let mut cursors = vec![0; channels.len()];
+        for time in times {
+            csv.push_str(&format!("{time:.6}"));
+            for (channel, cursor) in channels.iter().zip(&mut cursors) {
+                csv.push(',');
+                if let Some(&(sample_time, value)) = channel.samples.get(*cursor) {
+                    if sample_time == time {
+                        csv.push_str(&format!("{value}"));
+                        *cursor += 1;
+                    }
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quotes a field if it contains a separator, quote, or line break.
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn advance_recorder_clock(time: Res<Time<Virtual>>, mut recorder: ResMut<DataRecorder>) {
+    recorder.time = time.elapsed_seconds();
+}
+
+fn clear_recorder(mut recorder: ResMut<DataRecorder>) {
+    recorder.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channels_are_ring_buffers_aligned_by_time_in_csv() {
+        let mut recorder = DataRecorder { capacity: 2, ..default() };
+        for step in 0..3 {
+            recorder.set_time(step as f32);
+            recorder.record("energy", step as f32 * 10.0);
+            if step == 2 {
+                recorder.record("a, \"b\"", 5.0);
+            }
+        }
+        // A second sample at the same instant replaces the first.
+        recorder.record("energy", 25.0);
+
+        let energy = recorder.channel("energy").expect("recorded");
+        assert_eq!(energy.values(), vec![10.0, 25.0]);
+
+        recorder.channels_mut()[1].enabled = false;
+        recorder.set_time(3.0);
+        recorder.record("a, \"b\"", 7.0);
+        assert_eq!(recorder.channel("a, \"b\"").and_then(RecordedChannel::latest), Some(5.0));
+
+        assert_eq!(
+            recorder.to_csv(),
+            "time,energy,\"a, \"\"b\"\"\"\n\
+             1.000000,10,\n\
+             2.000000,25,5\n"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Runtime registry for switching between simulations.
+//!
+//! Bevy plugins cannot be removed once added, so switching simulations at
+//! runtime is built on a `States` gate rather than on adding and removing
+//! plugins:
+//!
+//! - `register_simulations` builds every simulation's plugin up front and
+//!   inserts the [`ActiveSimulation`] state.
+//! - Simulations register their per-frame systems with
+//!   [`SimulationAppExt::add_simulation_systems`], which gates them on
+//!   [`simulation_active`], and their scene setup with
+//!   [`SimulationAppExt::add_simulation_setup`], which runs on `OnEnter`.
+//! - Entities tagged with [`SimulationEntity`] are despawned on `OnExit`.
+//!   Teardown that isn't an entity, such as removing a resource the setup
+//!   inserted, goes in [`SimulationAppExt::add_simulation_cleanup`].
+//!
+//! Simulation plugins added directly (without a registry) keep working: the
+//! gate is open when no [`ActiveSimulation`] state exists and setup runs at
+//! `Startup` instead.
+
+use bevy::ecs::schedule::SystemConfigs;
+use bevy::prelude::*;
+
+use crate::traits::{Simulation, SimulationMetadata};
+
+/// State holding the id of the simulation whose systems may run.
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ActiveSimulation(pub Option<&'static str>);
+
+/// Marker for entities owned by a simulation, despawned when it is exited.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationEntity(pub &'static str);
+
+/// Registry of all simulations available in the application.
+#[derive(Resource, Default)]
+pub struct SimulationRegistry {
+    simulations: Vec<Box<dyn Simulation>>,
+    metadata: Vec<SimulationMetadata>,
+    active: Option<&'static str>,
+}
+
+impl SimulationRegistry {
+    /// Metadata for every registered simulation, in registration order.
+    pub fn metadata(&self) -> &[SimulationMetadata] {
+        &self.metadata
+    }
+
+    /// Looks up a registered simulation by id.
+    pub fn get(&self, id: &str) -> Option<&dyn Simulation> {
+        self.simulations
+            .iter()
+            .find(|sim| sim.id() == id)
+            .map(|sim| sim.as_ref())
+    }
+
+    /// Id of the currently active simulation.
+    pub fn active(&self) -> Option<&'static str> {
+        self.active
+    }
+
+    /// Requests a switch to the simulation with the given id.
+    ///
+    /// The old simulation's entities are despawned and the new one's setup
+    /// runs during the next state transition. Returns `false` if the id is
+    /// not registered.
+    pub fn switch_to(&mut self, id: &str, next: &mut NextState<ActiveSimulation>) -> bool {
+        let Some(id) = self.get(id).map(|sim| sim.id()) else {
+            warn!("Unknown simulation id: {}", id);
+            return false;
+        };
+        self.active = Some(id);
+        next.set(ActiveSimulation(Some(id)));
+        true
+    }
+}
+
+/// Registers simulations with the app and activates the first one.
+///
+/// Every simulation's `build_plugin` closure is called here, because plugins
+/// can only be added before the app runs; switching afterwards only moves the
+/// [`ActiveSimulation`] state.
+pub fn register_simulations(app: &mut App, simulations: Vec<Box<dyn Simulation>>) {
+    let initial = simulations.first().map(|sim| sim.id());
+    app.insert_state(ActiveSimulation(initial));
+
+    let mut registry = SimulationRegistry {
+        active: initial,
+        ..default()
+    };
+
+    for sim in simulations {
+        let id = sim.id();
+        info!("Registering simulation: {}", id);
+
+        (sim.build_plugin())(app);
+        app.add_systems(OnExit(ActiveSimulation(Some(id))), despawn_simulation_entities(id));
+
+        registry.metadata.push(SimulationMetadata::from(sim.as_ref()));
+        registry.simulations.push(sim);
+    }
+
+    app.insert_resource(registry);
+}
+
+/// Run condition that is true while the given simulation is active.
+///
+/// Also true when no [`ActiveSimulation`] state exists, so plugins added
+/// directly to an app run unconditionally.
+pub fn simulation_active(
+    id: &'static str,
+) -> impl FnMut(Option<Res<State<ActiveSimulation>>>) -> bool + Clone {
+    move |state: Option<Res<State<ActiveSimulation>>>| match state {
+        Some(state) => state.0 == Some(id),
+        None => true,
+    }
+}
+
+fn despawn_simulation_entities(
+    id: &'static str,
+) -> impl FnMut(Commands, Query<(Entity, &SimulationEntity)>) {
+    move |mut commands: Commands, entities: Query<(Entity, &SimulationEntity)>| {
+        for (entity, owner) in entities.iter() {
+            if owner.0 == id {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Extension methods for registering state-gated simulation systems.
+pub trait SimulationAppExt {
+    /// Adds scene setup systems for a simulation.
+    ///
+    /// They run on entering the simulation when a registry is present, and
+    /// at `Startup` otherwise.
+    fn add_simulation_setup<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self;
+
+    /// Adds `Update` systems that only run while the simulation is active.
+    fn add_simulation_systems<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self;
+
+    /// Adds teardown systems that run on leaving the simulation, alongside
+    /// the despawning of its [`SimulationEntity`]s.
+    ///
+    /// Without a registry a simulation is never left, so they never run.
+    fn add_simulation_cleanup<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self;
+}
+
+impl SimulationAppExt for App {
+    fn add_simulation_setup<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        if self.world().contains_resource::<State<ActiveSimulation>>() {
+            self.add_systems(OnEnter(ActiveSimulation(Some(id))), systems)
+        } else {
+            self.add_systems(Startup, systems)
+        }
+    }
+
+    fn add_simulation_systems<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        let systems: SystemConfigs = systems.into_configs();
+        self.add_systems(Update, systems.run_if(simulation_active(id)))
+    }
+
+    fn add_simulation_cleanup<M>(
+        &mut self,
+        id: &'static str,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        if self.world().contains_resource::<State<ActiveSimulation>>() {
+            self.add_systems(OnExit(ActiveSimulation(Some(id))), systems)
+        } else {
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameters::ParameterDef;
+    use crate::taxonomy::SimulationCategory;
+    use bevy::state::app::StatesPlugin;
+
+    #[derive(Resource, Default)]
+    struct Ticks(u32);
+
+    /// Resource a simulation's setup inserts and its cleanup removes.
+    #[derive(Resource, Debug, PartialEq)]
+    struct Setting(&'static str);
+
+    struct TestSim(&'static str);
+
+    impl Simulation for TestSim {
+        fn id(&self) -> &'static str {
+            self.0
+        }
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn category(&self) -> SimulationCategory {
+            SimulationCategory::CellularAutomata
+        }
+        fn description(&self) -> &'static str {
+            ""
+        }
+        fn parameters(&self) -> Vec<ParameterDef> {
+            vec![]
+        }
+        fn build_plugin(&self) -> Box<dyn Fn(&mut App) + Send + Sync> {
+            let id = self.0;
+            Box::new(move |app| {
+                app.add_simulation_setup(id, move |mut commands: Commands| {
+                    commands.spawn(SimulationEntity(id));
+                    commands.insert_resource(Setting(id));
+                })
+                .add_simulation_systems(id, |mut ticks: ResMut<Ticks>| ticks.0 += 1)
+                .add_simulation_cleanup(id, |mut commands: Commands| commands.remove_resource::<Setting>());
+            })
+        }
+    }
+
+    fn count_entities(app: &mut App, id: &'static str) -> usize {
+        let mut query = app.world_mut().query::<&SimulationEntity>();
+        query.iter(app.world()).filter(|owner| owner.0 == id).count()
+    }
+
+    #[test]
+    fn test_switch_despawns_old_entities() {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin).init_resource::<Ticks>();
+        register_simulations(&mut app, vec![Box::new(TestSim("a")), Box::new(TestSim("b"))]);
+
+        app.update();
+        assert_eq!(count_entities(&mut app, "a"), 1);
+        assert_eq!(count_entities(&mut app, "b"), 0);
+        assert_eq!(app.world().get_resource::<Setting>(), Some(&Setting("a")));
+        // Only the active simulation's systems run.
+        assert_eq!(app.world().resource::<Ticks>().0, 1);
+
+        app.world_mut()
+            .resource_scope(|world, mut registry: Mut<SimulationRegistry>| {
+                let mut next = world.resource_mut::<NextState<ActiveSimulation>>();
+                assert!(registry.switch_to("b", &mut next));
+            });
+        app.update();
+
+        assert_eq!(count_entities(&mut app, "a"), 0);
+        assert_eq!(count_entities(&mut app, "b"), 1);
+        assert_eq!(app.world().resource::<SimulationRegistry>().active(), Some("b"));
+        // The old simulation's cleanup ran before the new one's setup.
+        assert_eq!(app.world().get_resource::<Setting>(), Some(&Setting("b")));
+
+        app.world_mut()
+            .resource_scope(|world, mut registry: Mut<SimulationRegistry>| {
+                let mut next = world.resource_mut::<NextState<ActiveSimulation>>();
+                assert!(registry.switch_to("a", &mut next));
+            });
+        app.update();
+        assert_eq!(count_entities(&mut app, "a"), 1, "re-entering doesn't leak the first run's entities");
+        assert_eq!(count_entities(&mut app, "b"), 0);
+    }
+}
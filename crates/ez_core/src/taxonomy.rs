@@ -3,8 +3,12 @@
 //! This module provides a rigorous classification system for organizing
 //! simulations by their scientific domain.
 
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 
+use crate::traits::SimulationMetadata;
+
 /// Top-level simulation category classification.
 ///
 /// Simulations are organized into major scientific branches:
@@ -48,6 +52,30 @@ pub enum SimulationCategory {
 }
 
 impl SimulationCategory {
+    /// One category per variant, in declaration order.
+    ///
+    /// Variants with a subdomain use its first value as the representative.
+    pub fn all_top_level() -> Vec<SimulationCategory> {
+        vec![
+            Self::ClassicalMechanics(ClassicalMechanicsSubdomain::Kinematics),
+            Self::Electromagnetism(ElectromagnetismSubdomain::Electrostatics),
+            Self::WavePhysics(WavePhysicsSubdomain::MechanicalWaves),
+            Self::Optics(OpticsSubdomain::GeometricOptics),
+            Self::Thermodynamics(ThermodynamicsSubdomain::HeatTransfer),
+            Self::RelativisticPhysics(RelativisticSubdomain::SpecialRelativity),
+            Self::QuantumMechanics(QuantumSubdomain::WaveFunctions),
+            Self::Epidemiology,
+            Self::Ecology,
+            Self::Neuroscience,
+            Self::Economics,
+            Self::GameTheory,
+            Self::SocialNetworks,
+            Self::CellularAutomata,
+            Self::ChaosTheory,
+            Self::Fractals,
+        ]
+    }
+
     /// Returns the human-readable name of this category.
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -91,7 +119,9 @@ impl SimulationCategory {
 }
 
 /// Top-level science branch classification.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+///
+/// Ordered as branches are listed in menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect)]
 pub enum ScienceBranch {
     Physical,
     Life,
@@ -110,6 +140,15 @@ impl ScienceBranch {
     }
 }
 
+/// Groups simulations by science branch, keeping their order within each branch.
+pub fn group_by_branch(simulations: &[SimulationMetadata]) -> BTreeMap<ScienceBranch, Vec<&SimulationMetadata>> {
+    let mut groups: BTreeMap<ScienceBranch, Vec<&SimulationMetadata>> = BTreeMap::new();
+    for metadata in simulations {
+        groups.entry(metadata.category.science_branch()).or_default().push(metadata);
+    }
+    groups
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Subdomain enums
 // ══════════════════════════════════════════════════════════════════════════════
@@ -209,4 +248,33 @@ mod tests {
         let cat = SimulationCategory::Economics;
         assert_eq!(cat.science_branch(), ScienceBranch::Social);
     }
+
+    #[test]
+    fn test_grouping_covers_every_branch() {
+        let simulations: Vec<SimulationMetadata> = SimulationCategory::all_top_level()
+            .into_iter()
+            .map(|category| SimulationMetadata {
+                id: category.display_name(),
+                name: category.display_name(),
+                category,
+                description: "",
+                difficulty: 1,
+                tags: Vec::new(),
+                thumbnail: None,
+                author: None,
+                year: None,
+                references: Vec::new(),
+            })
+            .collect();
+
+        let groups = group_by_branch(&simulations);
+
+        let branches: Vec<ScienceBranch> = groups.keys().copied().collect();
+        assert_eq!(
+            branches,
+            [ScienceBranch::Physical, ScienceBranch::Life, ScienceBranch::Social, ScienceBranch::Formal]
+        );
+        assert_eq!(groups.values().map(Vec::len).sum::<usize>(), simulations.len());
+        assert_eq!(groups[&ScienceBranch::Physical].len(), 7);
+    }
 }
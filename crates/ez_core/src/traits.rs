@@ -5,7 +5,8 @@
 
 use bevy::prelude::*;
 
-use crate::parameters::ParameterDef;
+use crate::parameters::{ParameterDef, ParameterPreset, ParameterValue};
+use crate::performance::PerformanceTier;
 use crate::taxonomy::SimulationCategory;
 
 /// Core trait that all simulations must implement.
@@ -58,7 +59,8 @@ pub trait Simulation: Send + Sync + 'static {
     /// This allows the platform to dynamically load simulations.
     fn build_plugin(&self) -> Box<dyn Fn(&mut App) + Send + Sync>;
 
-    /// Optional: Thumbnail asset path for gallery display.
+    /// Optional: Thumbnail asset path for gallery display, conventionally
+    /// `thumbnails/{id}.png` (see `ez_renderer::thumbnail`).
     fn thumbnail(&self) -> Option<&'static str> {
         None
     }
@@ -72,6 +74,38 @@ pub trait Simulation: Send + Sync + 'static {
     fn tags(&self) -> &'static [&'static str] {
         &[]
     }
+
+    /// Optional: Named parameter combinations offered in the control panel.
+    ///
+    /// Each preset lists the values it overrides; parameters it omits keep
+    /// their current value.
+    fn presets(&self) -> Vec<ParameterPreset> {
+        vec![]
+    }
+
+    /// Optional: Parameter values suited to a machine of the given tier,
+    /// applied once before the simulation first spawns its scene.
+    ///
+    /// Use it for whatever sets the per-frame cost, such as a particle
+    /// count; parameters left out keep their defaults.
+    fn recommended_defaults(&self, _tier: PerformanceTier) -> Vec<(&'static str, ParameterValue)> {
+        vec![]
+    }
+
+    /// Optional: Who wrote or designed the simulation.
+    fn author(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Optional: Year the simulation was written.
+    fn year(&self) -> Option<u16> {
+        None
+    }
+
+    /// Optional: Citations for the model or method, for further reading.
+    fn references(&self) -> &'static [&'static str] {
+        &[]
+    }
 }
 
 /// Simulation metadata for registry and UI display.
@@ -84,9 +118,12 @@ pub struct SimulationMetadata {
     pub difficulty: u8,
     pub tags: Vec<&'static str>,
     pub thumbnail: Option<&'static str>,
+    pub author: Option<&'static str>,
+    pub year: Option<u16>,
+    pub references: Vec<&'static str>,
 }
 
-impl<T: Simulation> From<&T> for SimulationMetadata {
+impl<T: Simulation + ?Sized> From<&T> for SimulationMetadata {
     fn from(sim: &T) -> Self {
         Self {
             id: sim.id(),
@@ -96,6 +133,9 @@ impl<T: Simulation> From<&T> for SimulationMetadata {
             difficulty: sim.difficulty(),
             tags: sim.tags().to_vec(),
             thumbnail: sim.thumbnail(),
+            author: sim.author(),
+            year: sim.year(),
+            references: sim.references().to_vec(),
         }
     }
 }
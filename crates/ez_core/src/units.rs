@@ -0,0 +1,123 @@
+//! Unit-aware display formatting.
+//!
+//! Scales values by SI prefixes so readouts stay short: a million particles
+//! reads `1M`, a 2500 Hz tone `2.5 kHz`.
+
+/// SI prefixes used for display, with their powers of ten.
+///
+/// Milli is left out on purpose; values between 0.001 and 1 read fine as
+/// plain decimals and `m` would be easy to mistake for metres.
+const SI_PREFIXES: [(&str, i32); 6] = [("n", -9), ("µ", -6), ("", 0), ("k", 3), ("M", 6), ("G", 9)];
+
+/// Significant figures shown by [`format_si`].
+const SIGNIFICANT_FIGURES: i32 = 3;
+
+/// Formats a value to three significant figures with an SI prefix, dropping
+/// trailing zeros.
+///
+/// ```
+/// use ez_core::units::format_si;
+///
+/// assert_eq!(format_si(1_000_000.0), "1M");
+/// assert_eq!(format_si(2.5e-6), "2.5µ");
+/// assert_eq!(format_si(0.25), "0.25");
+/// ```
+pub fn format_si(value: f64) -> String {
+    let (number, prefix) = scale_si(value);
+    format!("{}{}", number, prefix)
+}
+
+/// Formats a value like [`format_si`], followed by the unit with the prefix
+/// attached (`2.5 kHz`).
+pub fn format_with_unit(value: f64, unit: Option<&str>) -> String {
+    let (number, prefix) = scale_si(value);
+    match unit {
+        Some(unit) => format!("{} {}{}", number, prefix, unit),
+        None => format!("{}{}", number, prefix),
+    }
+}
+
+/// Parses text written by [`format_si`], such as `1.5k` or `20 µ`.
+///
+/// `u` is accepted for `µ`. Returns `None` for anything else.
+pub fn parse_si(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (number, prefix) = match text.char_indices().last() {
+        Some((i, c)) if c.is_alphabetic() => (&text[..i], if c == 'u' { "µ" } else { &text[i..] }),
+        _ => (text, ""),
+    };
+    let (_, exponent) = SI_PREFIXES.iter().find(|(p, _)| !p.is_empty() && *p == prefix).copied().unwrap_or(("", 0));
+    if exponent == 0 && !prefix.is_empty() {
+        return None;
+    }
+    let value: f64 = number.trim().parse().ok()?;
+    Some(value * 10f64.powi(exponent))
+}
+
+/// Splits a value into a rounded, trimmed number and its SI prefix.
+fn scale_si(value: f64) -> (String, &'static str) {
+    if value == 0.0 || !value.is_finite() {
+        return (format!("{}", value), "");
+    }
+
+    // Round to significant figures first, so 999.96 becomes 1k, not 1000.
+    let magnitude = value.abs().log10().floor() as i32;
+    let quantum = 10f64.powi(magnitude - (SIGNIFICANT_FIGURES - 1));
+    let rounded = (value / quantum).round() * quantum;
+    let magnitude = rounded.abs().log10().floor() as i32;
+
+    let (prefix, exponent) = if (-3..3).contains(&magnitude) {
+        ("", 0)
+    } else {
+        SI_PREFIXES
+            .iter()
+            .rev()
+            .find(|(_, exponent)| magnitude >= *exponent)
+            .copied()
+            .unwrap_or(SI_PREFIXES[0])
+    };
+
+    let scaled = rounded / 10f64.powi(exponent);
+    let decimals = (SIGNIFICANT_FIGURES - 1 - (magnitude - exponent)).max(0) as usize;
+    let mut number = format!("{:.*}", decimals, scaled);
+    if number.contains('.') {
+        number = number.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    (number, prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_si_across_prefix_boundaries() {
+        assert_eq!(format_si(0.0), "0");
+        assert_eq!(format_si(999.0), "999");
+        assert_eq!(format_si(1000.0), "1k");
+        assert_eq!(format_si(999.96), "1k");
+        assert_eq!(format_si(12_345.0), "12.3k");
+        assert_eq!(format_si(999_999.0), "1M");
+        assert_eq!(format_si(-2_500_000.0), "-2.5M");
+        assert_eq!(format_si(0.001), "0.001");
+        assert_eq!(format_si(0.000_999), "999µ");
+        assert_eq!(format_si(4.2e-8), "42n");
+    }
+
+    #[test]
+    fn test_format_with_unit_attaches_prefix() {
+        assert_eq!(format_with_unit(2500.0, Some("Hz")), "2.5 kHz");
+        assert_eq!(format_with_unit(9.8, Some("m/s²")), "9.8 m/s²");
+        assert_eq!(format_with_unit(1e6, None), "1M");
+    }
+
+    #[test]
+    fn test_parse_si_reads_formatted_values() {
+        for value in [0.25, 1500.0, 2.5e6, 3.3e-7] {
+            let parsed = parse_si(&format_si(value)).unwrap();
+            assert!((parsed - value).abs() <= value.abs() * 1e-9, "{value} -> {parsed}");
+        }
+        assert!((parse_si("20 u").unwrap() - 20e-6).abs() < 1e-15);
+        assert_eq!(parse_si("5x"), None);
+    }
+}
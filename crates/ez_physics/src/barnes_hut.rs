@@ -0,0 +1,179 @@
+//! Barnes-Hut octree for O(n log n) N-body gravity.
+
+use bevy::prelude::*;
+
+use crate::forces::gravitational_force;
+
+/// Maximum subdivision depth; coincident bodies end up sharing a leaf.
+const MAX_DEPTH: u32 = 24;
+
+/// Octree over point masses, storing each node's total mass and center of mass.
+pub struct Octree {
+    bodies: Vec<(Vec3, f32)>,
+    nodes: Vec<OctreeNode>,
+}
+
+struct OctreeNode {
+    center: Vec3,
+    half_size: f32,
+    mass: f32,
+    center_of_mass: Vec3,
+    children: Option<[usize; 8]>,
+    /// Body indices held by a leaf.
+    bodies: Vec<usize>,
+}
+
+impl Octree {
+    /// Builds an octree from `(position, mass)` pairs.
+    pub fn build(bodies: &[(Vec3, f32)]) -> Self {
+        let (min, max) = bodies.iter().fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), (position, _)| (min.min(*position), max.max(*position)),
+        );
+        let (center, half_size) = if bodies.is_empty() {
+            (Vec3::ZERO, 1.0)
+        } else {
+            ((min + max) * 0.5, ((max - min).max_element() * 0.5).max(1e-3))
+        };
+
+        let mut tree = Self {
+            bodies: bodies.to_vec(),
+            nodes: Vec::new(),
+        };
+        let indices: Vec<usize> = (0..bodies.len()).collect();
+        tree.build_node(indices, center, half_size, 0);
+        tree
+    }
+
+    fn build_node(&mut self, indices: Vec<usize>, center: Vec3, half_size: f32, depth: u32) -> usize {
+        let mass: f32 = indices.iter().map(|&i| self.bodies[i].1).sum();
+        let center_of_mass = if mass > 0.0 {
+            indices.iter().map(|&i| self.bodies[i].0 * self.bodies[i].1).sum::<Vec3>() / mass
+        } else {
+            center
+        };
+
+        let node = self.nodes.len();
+        self.nodes.push(OctreeNode {
+            center,
+            half_size,
+            mass,
+            center_of_mass,
+            children: None,
+            bodies: Vec::new(),
+        });
+
+        if indices.len() <= 1 || depth >= MAX_DEPTH {
+            self.nodes[node].bodies = indices;
+            return node;
+        }
+
+        let mut octants: [Vec<usize>; 8] = Default::default();
+        for i in indices {
+            octants[Self::octant(center, self.bodies[i].0)].push(i);
+        }
+
+        let quarter = half_size * 0.5;
+        let mut children = [0; 8];
+        for (octant, members) in octants.into_iter().enumerate() {
+            let offset = Vec3::new(
+                if octant & 1 != 0 { quarter } else { -quarter },
+                if octant & 2 != 0 { quarter } else { -quarter },
+                if octant & 4 != 0 { quarter } else { -quarter },
+            );
+            children[octant] = self.build_node(members, center + offset, quarter, depth + 1);
+        }
+        self.nodes[node].children = Some(children);
+        node
+    }
+
+    fn octant(center: Vec3, position: Vec3) -> usize {
+        (position.x >= center.x) as usize
+            | ((position.y >= center.y) as usize) << 1
+            | ((position.z >= center.z) as usize) << 2
+    }
+
+    /// Gravitational force on body `index` from all other bodies.
+    ///
+    /// A node is treated as a single mass when its width over its distance
+    /// is below `theta`; `theta = 0` reproduces the exact pairwise sum.
+    pub fn force_on(&self, index: usize, theta: f32, g: f32) -> Vec3 {
+        let (position, mass) = self.bodies[index];
+        let mut force = Vec3::ZERO;
+        let mut stack = vec![0];
+
+        while let Some(node_index) = stack.pop() {
+            let Some(node) = self.nodes.get(node_index) else { continue };
+            if node.mass <= 0.0 {
+                continue;
+            }
+
+            match node.children {
+                None => {
+                    for &other in node.bodies.iter().filter(|&&other| other != index) {
+                        let (other_position, other_mass) = self.bodies[other];
+                        force += gravitational_force(mass, other_mass, position, other_position, g);
+                    }
+                }
+                Some(children) => {
+                    let outside = (position - node.center).abs().max_element() > node.half_size;
+                    let distance = position.distance(node.center_of_mass);
+                    if outside && 2.0 * node.half_size < theta * distance {
+                        force += gravitational_force(mass, node.mass, position, node.center_of_mass, g);
+                    } else {
+                        stack.extend(children);
+                    }
+                }
+            }
+        }
+
+        force
+    }
+
+    /// Number of bodies in the tree.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_bodies() -> Vec<(Vec3, f32)> {
+        (0..200)
+            .map(|i| {
+                let t = i as f32;
+                let position = Vec3::new((t * 1.7).sin() * 40.0, (t * 0.9).cos() * 25.0, (t * 2.3).sin() * 10.0);
+                (position, 1.0 + (t * 0.37).sin().abs() * 5.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_theta_zero_matches_pairwise() {
+        let bodies = test_bodies();
+        let tree = Octree::build(&bodies);
+
+        for index in [0, 57, 199] {
+            let (position, mass) = bodies[index];
+            let exact: Vec3 = bodies
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, (other_position, other_mass))| {
+                    gravitational_force(mass, *other_mass, position, *other_position, 1.0)
+                })
+                .sum();
+            let approx = tree.force_on(index, 0.0, 1.0);
+            assert!(
+                (approx - exact).length() <= 1e-4 * exact.length().max(1.0),
+                "body {index}: {approx:?} vs {exact:?}"
+            );
+        }
+    }
+}
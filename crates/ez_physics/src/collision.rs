@@ -0,0 +1,138 @@
+//! Collision detection primitives and impulse resolution.
+//!
+//! The tests report a [`Contact`] only for shapes that overlap; shapes that
+//! just touch count as separated. Every contact normal points from the
+//! first shape toward the second, so pushing the second shape along the
+//! normal by the depth separates them.
+
+use bevy::prelude::*;
+
+/// Where two shapes overlap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// Unit vector from the first shape toward the second.
+    pub normal: Vec3,
+    /// How far the shapes overlap along `normal`.
+    pub depth: f32,
+}
+
+/// Overlap of two spheres.
+///
+/// Spheres with coincident centers get an arbitrary normal (`+Y`).
+pub fn sphere_sphere(c1: Vec3, r1: f32, c2: Vec3, r2: f32) -> Option<Contact> {
+    let offset = c2 - c1;
+    let distance_sq = offset.length_squared();
+    let reach = r1 + r2;
+    if distance_sq >= reach * reach {
+        return None;
+    }
+
+    let distance = distance_sq.sqrt();
+    let normal = if distance > f32::EPSILON { offset / distance } else { Vec3::Y };
+    Some(Contact { normal, depth: reach - distance })
+}
+
+/// Overlap of a plane, the first shape, and a sphere.
+///
+/// The plane passes through `plane_point` and faces along `normal`; the
+/// space behind it is solid, so a sphere that has passed right through
+/// still collides, with a depth beyond its diameter.
+pub fn sphere_plane(center: Vec3, radius: f32, plane_point: Vec3, normal: Vec3) -> Option<Contact> {
+    let normal = normal.normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return None;
+    }
+    let distance = (center - plane_point).dot(normal);
+    (distance < radius).then_some(Contact { normal, depth: radius - distance })
+}
+
+/// Overlap of two axis-aligned boxes given by their corners.
+///
+/// The normal is along the axis of least overlap, the shortest way out.
+pub fn aabb_aabb(min1: Vec3, max1: Vec3, min2: Vec3, max2: Vec3) -> Option<Contact> {
+    let overlap = max1.min(max2) - min1.max(min2);
+    if overlap.min_element() <= 0.0 {
+        return None;
+    }
+
+    let axis = if overlap.x <= overlap.y && overlap.x <= overlap.z {
+        Vec3::X
+    } else if overlap.y <= overlap.z {
+        Vec3::Y
+    } else {
+        Vec3::Z
+    };
+    let toward_second = ((min2 + max2) - (min1 + max1)).dot(axis);
+    let normal = if toward_second < 0.0 { -axis } else { axis };
+    Some(Contact { normal, depth: overlap.dot(axis) })
+}
+
+/// Applies the impulse that stops two bodies approaching along `normal`,
+/// which points from body 1 toward body 2, and returns its magnitude.
+///
+/// `restitution` is 1 for a perfectly elastic bounce and 0 for bodies that
+/// stop dead along the normal. Pass `f32::INFINITY` as a mass for an
+/// immovable body, such as a wall. Bodies already separating are left
+/// alone and the impulse is zero.
+pub fn resolve_contact(vel1: &mut Vec3, vel2: &mut Vec3, m1: f32, m2: f32, normal: Vec3, restitution: f32) -> f32 {
+    let closing = (*vel2 - *vel1).dot(normal);
+    let inverse_mass = 1.0 / m1 + 1.0 / m2;
+    if closing >= 0.0 || inverse_mass <= 0.0 {
+        return 0.0;
+    }
+
+    let impulse = -(1.0 + restitution) * closing / inverse_mass;
+    *vel1 -= normal * (impulse / m1);
+    *vel2 += normal * (impulse / m2);
+    impulse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sphere_sphere_overlapping_and_separated() {
+        let contact = sphere_sphere(Vec3::ZERO, 1.0, Vec3::new(1.5, 0.0, 0.0), 1.0).expect("overlapping");
+        assert_eq!(contact.normal, Vec3::X);
+        assert!((contact.depth - 0.5).abs() < 1e-6);
+
+        assert_eq!(sphere_sphere(Vec3::ZERO, 1.0, Vec3::new(3.0, 0.0, 0.0), 1.0), None);
+        assert_eq!(sphere_sphere(Vec3::ZERO, 1.0, Vec3::new(0.0, 2.0, 0.0), 1.0), None, "touching");
+        assert_eq!(sphere_sphere(Vec3::ONE, 1.0, Vec3::ONE, 0.5).map(|contact| contact.normal), Some(Vec3::Y));
+    }
+
+    #[test]
+    fn test_sphere_plane_and_aabb() {
+        let contact = sphere_plane(Vec3::new(0.0, 0.5, 0.0), 1.0, Vec3::ZERO, Vec3::Y * 3.0).expect("resting on floor");
+        assert_eq!(contact.normal, Vec3::Y);
+        assert!((contact.depth - 0.5).abs() < 1e-6);
+        assert_eq!(sphere_plane(Vec3::new(0.0, 2.0, 0.0), 1.0, Vec3::ZERO, Vec3::Y), None);
+
+        let contact = aabb_aabb(Vec3::ZERO, Vec3::ONE, Vec3::new(-0.8, 0.5, 0.0), Vec3::new(0.2, 1.5, 1.0)).expect("overlapping");
+        assert_eq!(contact.normal, -Vec3::X);
+        assert!((contact.depth - 0.2).abs() < 1e-6);
+        assert_eq!(aabb_aabb(Vec3::ZERO, Vec3::ONE, Vec3::new(1.0, 0.0, 0.0), Vec3::new(2.0, 1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn test_resolve_contact_conserves_momentum() {
+        // Equal masses swap velocities in an elastic head-on collision.
+        let (mut v1, mut v2) = (Vec3::X, Vec3::ZERO);
+        resolve_contact(&mut v1, &mut v2, 2.0, 2.0, Vec3::X, 1.0);
+        assert_eq!((v1, v2), (Vec3::ZERO, Vec3::X));
+
+        let (mut v1, mut v2) = (Vec3::new(3.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let before = v1 * 1.0 + v2 * 3.0;
+        resolve_contact(&mut v1, &mut v2, 1.0, 3.0, Vec3::X, 0.5);
+        assert!((v1 * 1.0 + v2 * 3.0 - before).length() < 1e-5);
+        assert!(((v2 - v1).x - 2.0).abs() < 1e-5, "separates at half the closing speed");
+        assert_eq!(v1.y, 1.0, "tangential velocity is untouched");
+
+        // A wall reflects the ball, and separating bodies are left alone.
+        let (mut ball, mut wall) = (Vec3::new(0.0, -2.0, 0.0), Vec3::ZERO);
+        resolve_contact(&mut wall, &mut ball, f32::INFINITY, 1.0, Vec3::Y, 1.0);
+        assert_eq!((ball, wall), (Vec3::new(0.0, 2.0, 0.0), Vec3::ZERO));
+        assert_eq!(resolve_contact(&mut wall, &mut ball, f32::INFINITY, 1.0, Vec3::Y, 1.0), 0.0);
+    }
+}
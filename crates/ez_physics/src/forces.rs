@@ -30,6 +30,101 @@ pub fn gravitational_force(
     direction.normalize() * magnitude
 }
 
+/// Point mass gravity with Plummer softening.
+///
+/// Uses `G·m1·m2·r / (r² + ε²)^(3/2)`, which stays finite at `r = 0` and
+/// approaches the inverse-square law once `r ≫ ε`. Use it where close
+/// encounters would otherwise fling bodies apart.
+pub fn gravitational_force_softened(
+    mass1: f32,
+    mass2: f32,
+    position1: Vec3,
+    position2: Vec3,
+    g: f32,
+    epsilon: f32,
+) -> Vec3 {
+    let direction = position2 - position1;
+    let denominator = (direction.length_squared() + epsilon * epsilon).powf(1.5);
+    if denominator <= 0.0 {
+        return Vec3::ZERO;
+    }
+    direction * (g * mass1 * mass2 / denominator)
+}
+
+/// Plummer-softened gravitational potential `Φ = −Σ G·mᵢ / √(rᵢ² + ε²)` at
+/// `position` from `bodies`, given as `(position, mass)` pairs.
+pub fn gravitational_potential_softened(position: Vec3, bodies: &[(Vec3, f32)], g: f32, epsilon: f32) -> f32 {
+    bodies
+        .iter()
+        .map(|&(body, mass)| -g * mass / (position.distance_squared(body) + epsilon * epsilon).sqrt())
+        .sum()
+}
+
+/// A point body for collision helpers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointMass {
+    pub mass: f32,
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+impl PointMass {
+    /// Combines two bodies into one at their center of mass, conserving
+    /// mass and momentum (kinetic energy is lost, as in any perfectly
+    /// inelastic collision).
+    pub fn merge(self, other: PointMass) -> PointMass {
+        let mass = self.mass + other.mass;
+        if mass <= 0.0 {
+            return PointMass { mass, ..self };
+        }
+        PointMass {
+            mass,
+            position: (self.position * self.mass + other.position * other.mass) / mass,
+            velocity: (self.velocity * self.mass + other.velocity * other.mass) / mass,
+        }
+    }
+}
+
+/// Merges two bodies if they are within `contact_distance` (usually the sum
+/// of their radii), or returns `None` if they are apart.
+pub fn merge_on_contact(a: PointMass, b: PointMass, contact_distance: f32) -> Option<PointMass> {
+    (a.position.distance_squared(b.position) <= contact_distance * contact_distance).then(|| a.merge(b))
+}
+
+/// Lennard-Jones force on particle 1 from particle 2.
+///
+/// Repulsive inside the potential minimum at `r = 2^(1/6)·sigma`,
+/// attractive outside it.
+pub fn lennard_jones_force(
+    position1: Vec3,
+    position2: Vec3,
+    epsilon: f32,
+    sigma: f32,
+) -> Vec3 {
+    let direction = position1 - position2;
+    let distance_sq = direction.length_squared().max(0.01); // Avoid division by zero
+    let sr6 = (sigma * sigma / distance_sq).powi(3);
+    // F = 24ε/r · (2(σ/r)¹² − (σ/r)⁶) along r̂; the extra 1/r normalizes `direction`.
+    let magnitude_over_r = 24.0 * epsilon * (2.0 * sr6 * sr6 - sr6) / distance_sq;
+    direction * magnitude_over_r
+}
+
+/// Coulomb force on charge 1 from charge 2.
+///
+/// Like charges repel, opposite charges attract.
+pub fn coulomb_force(
+    charge1: f32,
+    charge2: f32,
+    position1: Vec3,
+    position2: Vec3,
+    k: f32,
+) -> Vec3 {
+    let direction = position1 - position2;
+    let distance_sq = direction.length_squared().max(0.01); // Avoid division by zero
+    let magnitude = k * charge1 * charge2 / distance_sq;
+    direction.normalize_or_zero() * magnitude
+}
+
 /// Hooke's law spring force.
 pub fn spring_force(
     position: Vec3,
@@ -51,3 +146,64 @@ pub fn spring_force(
 pub fn damping_force(velocity: Vec3, coefficient: f32) -> Vec3 {
     -coefficient * velocity
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lennard_jones_zero_at_minimum() {
+        let sigma = 1.5;
+        let r_min = 2.0f32.powf(1.0 / 6.0) * sigma;
+        let force = lennard_jones_force(Vec3::ZERO, Vec3::new(r_min, 0.0, 0.0), 2.0, sigma);
+        assert!(force.length() < 1e-4, "force at minimum: {force:?}");
+
+        // Repulsive just inside the minimum, attractive just outside.
+        let inside = lennard_jones_force(Vec3::ZERO, Vec3::new(r_min * 0.9, 0.0, 0.0), 2.0, sigma);
+        let outside = lennard_jones_force(Vec3::ZERO, Vec3::new(r_min * 1.1, 0.0, 0.0), 2.0, sigma);
+        assert!(inside.x < 0.0);
+        assert!(outside.x > 0.0);
+    }
+
+    #[test]
+    fn test_softened_gravity_finite_at_contact_and_newtonian_far_away() {
+        let at_contact = gravitational_force_softened(2.0, 3.0, Vec3::ONE, Vec3::ONE, 1.0, 0.1);
+        assert!(at_contact.is_finite());
+        assert_eq!(at_contact, Vec3::ZERO);
+
+        let (p1, p2) = (Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0));
+        let softened = gravitational_force_softened(2.0, 3.0, p1, p2, 1.0, 0.1);
+        let newtonian = gravitational_force(2.0, 3.0, p1, p2, 1.0);
+        assert!((softened - newtonian).length() < 1e-5 * newtonian.length());
+    }
+
+    #[test]
+    fn test_softened_potential_sums_point_masses() {
+        let bodies = [(Vec3::ZERO, 2.0), (Vec3::new(10.0, 0.0, 0.0), 3.0)];
+        let potential = gravitational_potential_softened(Vec3::new(5.0, 0.0, 0.0), &bodies, 1.0, 0.0);
+        assert!((potential + (2.0 + 3.0) / 5.0).abs() < 1e-6);
+        assert!(gravitational_potential_softened(Vec3::ZERO, &bodies, 1.0, 0.5).is_finite());
+    }
+
+    #[test]
+    fn test_merge_conserves_mass_and_momentum() {
+        let a = PointMass { mass: 1.0, position: Vec3::ZERO, velocity: Vec3::new(3.0, 0.0, 0.0) };
+        let b = PointMass { mass: 2.0, position: Vec3::new(0.9, 0.0, 0.0), velocity: Vec3::new(0.0, -1.5, 0.0) };
+        assert_eq!(merge_on_contact(a, b, 0.5), None);
+
+        let merged = merge_on_contact(a, b, 1.0).expect("bodies touch");
+        assert_eq!(merged.mass, 3.0);
+        assert_eq!(merged.velocity * merged.mass, a.velocity * a.mass + b.velocity * b.mass);
+        assert!((merged.position.x - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_coulomb_like_charges_repel() {
+        let force = coulomb_force(1.0, 1.0, Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0), 8.99);
+        assert!(force.x < 0.0);
+        assert!((force.length() - 8.99 / 4.0).abs() < 1e-4);
+
+        let force = coulomb_force(1.0, -1.0, Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0), 8.99);
+        assert!(force.x > 0.0);
+    }
+}
@@ -2,15 +2,18 @@
 
 use bevy::prelude::*;
 
-/// Euler integration (first-order, simple but less accurate).
+/// Explicit Euler integration (first-order, simple but less accurate).
+///
+/// Position moves with the velocity from the start of the step, which adds
+/// energy every step; see [`semi_implicit_euler`] for the stable variant.
 pub fn euler_integrate(
     position: &mut Vec3,
     velocity: &mut Vec3,
     acceleration: Vec3,
     dt: f32,
 ) {
-    *velocity += acceleration * dt;
     *position += *velocity * dt;
+    *velocity += acceleration * dt;
 }
 
 /// Semi-implicit Euler (symplectic, better energy conservation).
@@ -36,6 +39,31 @@ pub fn verlet_integrate(
     *velocity += 0.5 * (prev_acceleration + acceleration) * dt;
 }
 
+/// Kick-drift-kick leapfrog (second-order, symplectic).
+///
+/// `acceleration` must hold the acceleration at the current `position`
+/// when called; it is replaced by the acceleration at the new position, so
+/// each step costs one evaluation of `acceleration_fn`. Seed it with
+/// `acceleration_fn(position)` before the first step.
+///
+/// Energy errors stay bounded instead of accumulating, which keeps orbits
+/// stable over long runs.
+pub fn leapfrog_integrate<F>(
+    position: &mut Vec3,
+    velocity: &mut Vec3,
+    acceleration: &mut Vec3,
+    dt: f32,
+    acceleration_fn: F,
+) where
+    F: Fn(Vec3) -> Vec3,
+{
+    // Half kick, drift, recompute, half kick
+    *velocity += *acceleration * (0.5 * dt);
+    *position += *velocity * dt;
+    *acceleration = acceleration_fn(*position);
+    *velocity += *acceleration * (0.5 * dt);
+}
+
 /// Fourth-order Runge-Kutta (high accuracy, expensive).
 pub fn rk4_integrate<F>(
     position: &mut Vec3,
@@ -79,4 +107,66 @@ mod tests {
         assert!((vel.y - (-10.0)).abs() < 0.1);
         assert!((pos.y - (-5.0)).abs() < 0.5);
     }
+
+    #[test]
+    fn test_explicit_euler_gains_energy_where_semi_implicit_loses_it() {
+        let gravity = Vec3::new(0.0, -10.0, 0.0);
+        let energy = |pos: Vec3, vel: Vec3| 0.5 * vel.length_squared() - gravity.dot(pos);
+
+        let (mut euler_pos, mut euler_vel) = (Vec3::ZERO, Vec3::ZERO);
+        let (mut semi_pos, mut semi_vel) = (Vec3::ZERO, Vec3::ZERO);
+        for _ in 0..100 {
+            euler_integrate(&mut euler_pos, &mut euler_vel, gravity, 0.01);
+            semi_implicit_euler(&mut semi_pos, &mut semi_vel, gravity, 0.01);
+        }
+
+        assert!(energy(euler_pos, euler_vel) > 0.4);
+        assert!(energy(semi_pos, semi_vel) < -0.4);
+    }
+
+    /// Semi-major axis `a = -GM / 2E` of an orbit around a unit mass at the origin.
+    fn semi_major_axis(position: Vec3, velocity: Vec3) -> f32 {
+        let energy = 0.5 * velocity.length_squared() - 1.0 / position.length();
+        -1.0 / (2.0 * energy)
+    }
+
+    fn central_gravity(position: Vec3) -> Vec3 {
+        -position / position.length().powi(3)
+    }
+
+    #[test]
+    fn test_leapfrog_conserves_orbit_better_than_verlet() {
+        let start_pos = Vec3::new(1.0, 0.0, 0.0);
+        let start_vel = Vec3::new(0.0, 1.1, 0.0);
+        let expected = semi_major_axis(start_pos, start_vel);
+        let dt = 0.01;
+
+        let (mut pos, mut vel) = (start_pos, start_vel);
+        let mut acc = central_gravity(pos);
+        let mut leapfrog_error = 0.0f32;
+        for _ in 0..10_000 {
+            leapfrog_integrate(&mut pos, &mut vel, &mut acc, dt, central_gravity);
+            leapfrog_error = leapfrog_error.max((semi_major_axis(pos, vel) - expected).abs());
+        }
+
+        // Verlet as used per frame: the acceleration at the current position
+        // alongside the one from the previous step.
+        let (mut pos, mut vel) = (start_pos, start_vel);
+        let mut prev_acc = central_gravity(pos);
+        let mut verlet_error = 0.0f32;
+        for _ in 0..10_000 {
+            let acc = central_gravity(pos);
+            verlet_integrate(&mut pos, &mut vel, acc, prev_acc, dt);
+            prev_acc = acc;
+            verlet_error = verlet_error.max((semi_major_axis(pos, vel) - expected).abs());
+        }
+
+        assert!(leapfrog_error < 0.01 * expected, "leapfrog drifted by {}", leapfrog_error);
+        assert!(
+            leapfrog_error < verlet_error,
+            "leapfrog {} not better than verlet {}",
+            leapfrog_error,
+            verlet_error
+        );
+    }
 }
@@ -3,15 +3,23 @@
 //! Shared physics primitives for Entropy Zero simulations.
 //!
 //! Provides:
-//! - Force types (gravity, springs, EM)
-//! - Numerical integrators (Euler, RK4, Verlet)
+//! - Force types (gravity, springs, Lennard-Jones, electrostatics)
+//! - Numerical integrators (Euler, RK4, Verlet, leapfrog)
+//! - Fixed-timestep accumulator
+//! - Barnes-Hut octree for N-body gravity
 //! - Collision detection primitives
 
+pub mod barnes_hut;
+pub mod collision;
 pub mod forces;
 pub mod integrators;
+pub mod timestep;
 
 /// Prelude for convenient imports.
 pub mod prelude {
+    pub use crate::barnes_hut::Octree;
+    pub use crate::collision::*;
     pub use crate::forces::*;
     pub use crate::integrators::*;
+    pub use crate::timestep::FixedTimestep;
 }
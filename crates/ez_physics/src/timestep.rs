@@ -0,0 +1,69 @@
+//! Fixed-timestep accumulator.
+//!
+//! Decouples simulation steps from the display frame rate: frame time is
+//! accumulated and spent in whole steps of a fixed `dt`, so a stable
+//! integrator stays stable at any refresh rate.
+
+use bevy::prelude::*;
+
+/// Accumulates elapsed time and hands it out as whole fixed steps.
+///
+/// At most `max_steps` are run per frame. If a frame took longer than that
+/// (a stutter, a backgrounded tab) the excess time is dropped rather than
+/// carried over, so the simulation briefly runs slower than real time
+/// instead of falling further behind each frame (the "spiral of death").
+#[derive(Resource, Debug, Clone)]
+pub struct FixedTimestep {
+    /// Length of one step in seconds.
+    pub dt: f32,
+    /// Most steps returned by a single [`FixedTimestep::advance`].
+    pub max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(dt: f32, max_steps: u32) -> Self {
+        Self {
+            dt,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Adds `elapsed` seconds and returns how many steps to run now.
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed.max(0.0);
+        let due = (self.accumulator / self.dt) as u32;
+        let steps = due.min(self.max_steps);
+        self.accumulator = if due > self.max_steps { 0.0 } else { self.accumulator - steps as f32 * self.dt };
+        steps
+    }
+
+    /// Fraction of a step left in the accumulator, for interpolating
+    /// between the last two states when rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt
+    }
+
+    /// Discards any accumulated time.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_carry_remainder_and_cap_drops_backlog() {
+        let mut clock = FixedTimestep::new(0.01, 4);
+        assert_eq!(clock.advance(0.025), 2);
+        assert!((clock.alpha() - 0.5).abs() < 1e-3);
+        assert_eq!(clock.advance(0.005), 1);
+
+        // A one-second stall runs the cap and forgets the rest.
+        assert_eq!(clock.advance(1.0), 4);
+        assert_eq!(clock.alpha(), 0.0);
+    }
+}
@@ -0,0 +1,141 @@
+//! Orientation triad and scale bar for 3D views.
+//!
+//! While the [`ShowAxes`] resource exists, every [`OrbitCamera`] view gets
+//! an XYZ triad pinned to its bottom-left corner, turning with the camera
+//! like a modelling tool's orientation widget, and a scale bar at the orbit
+//! focus sized to a round number of world units.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use ez_core::units::format_with_unit;
+
+use crate::camera::OrbitCamera;
+
+/// Length of each triad axis on screen, in logical pixels.
+const TRIAD_PIXELS: f32 = 40.0;
+/// Distance of the triad's origin from the view's bottom-left corner.
+const TRIAD_MARGIN: f32 = 60.0;
+/// Distance in front of the camera at which the triad is drawn.
+const TRIAD_DEPTH: f32 = 1.0;
+/// Longest the scale bar may be on screen, in logical pixels.
+const SCALE_BAR_PIXELS: f32 = 150.0;
+
+/// Plugin drawing the axis triad and scale bar while [`ShowAxes`] exists.
+pub struct AxesPlugin;
+
+impl Plugin for AxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_axes.run_if(resource_exists::<ShowAxes>));
+    }
+}
+
+/// Inserted by 3D simulations to show the axis triad and scale bar.
+#[derive(Resource, Debug, Clone)]
+pub struct ShowAxes {
+    /// What one world unit is, written on the scale bar (e.g. `"m"`).
+    pub unit: &'static str,
+}
+
+/// Cleanup system removing [`ShowAxes`], for simulations that insert it.
+pub fn hide_axes(mut commands: Commands) {
+    commands.remove_resource::<ShowAxes>();
+}
+
+/// The largest 1, 2 or 5 × 10ⁿ that does not exceed `length`.
+pub fn nice_length(length: f32) -> f32 {
+    if length <= 0.0 || !length.is_finite() {
+        return 0.0;
+    }
+    let power = 10f32.powf(length.log10().floor());
+    let mantissa = length / power;
+    let step = if mantissa >= 5.0 {
+        5.0
+    } else if mantissa >= 2.0 {
+        2.0
+    } else {
+        1.0
+    };
+    step * power
+}
+
+/// World length covered by one logical pixel at `depth` in front of the camera.
+fn world_per_pixel(projection: &Projection, viewport_height: f32, depth: f32) -> f32 {
+    match projection {
+        Projection::Perspective(perspective) => 2.0 * depth * (perspective.fov / 2.0).tan() / viewport_height,
+        Projection::Orthographic(orthographic) => orthographic.area.height() / viewport_height,
+    }
+}
+
+fn draw_axes(
+    show: Res<ShowAxes>,
+    cameras: Query<(&Camera, &GlobalTransform, &Projection, &OrbitCamera)>,
+    mut gizmos: Gizmos,
+    mut contexts: EguiContexts,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else { return };
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    let label = |position: Vec3, camera: &Camera, transform: &GlobalTransform, text: String, color: Color| {
+        if let Some(screen) = camera.world_to_viewport(transform, position) {
+            let [r, g, b, _] = color.to_srgba().to_u8_array();
+            painter.text(
+                egui::pos2(screen.x, screen.y),
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(12.0),
+                egui::Color32::from_rgb(r, g, b),
+            );
+        }
+    };
+
+    for (camera, transform, projection, orbit) in &cameras {
+        let Some(size) = camera.logical_viewport_size() else { continue };
+
+        // Orientation triad, just in front of the camera so it keeps its
+        // screen position and size however the camera moves.
+        if let Some(ray) = camera.viewport_to_world(transform, Vec2::new(TRIAD_MARGIN, size.y - TRIAD_MARGIN)) {
+            let origin = ray.get_point(TRIAD_DEPTH);
+            let length = TRIAD_PIXELS * world_per_pixel(projection, size.y, TRIAD_DEPTH);
+            for (axis, name, color) in [
+                (Vec3::X, "X", Color::srgb(0.95, 0.3, 0.3)),
+                (Vec3::Y, "Y", Color::srgb(0.3, 0.9, 0.3)),
+                (Vec3::Z, "Z", Color::srgb(0.35, 0.55, 1.0)),
+            ] {
+                let tip = origin + axis * length;
+                gizmos.arrow(origin, tip, color);
+                label(origin + axis * length * 1.3, camera, transform, name.to_string(), color);
+            }
+        }
+
+        // Scale bar lying across the view at the focus.
+        let pose = orbit.current();
+        let per_pixel = world_per_pixel(projection, size.y, pose.distance);
+        let bar = nice_length(SCALE_BAR_PIXELS * per_pixel);
+        if bar <= 0.0 {
+            continue;
+        }
+        let right = transform.right().as_vec3();
+        let up = transform.up().as_vec3();
+        let (start, end) = (pose.focus - right * bar / 2.0, pose.focus + right * bar / 2.0);
+        let tick = up * 6.0 * per_pixel;
+        let color = Color::srgba(1.0, 1.0, 1.0, 0.8);
+        gizmos.line(start, end, color);
+        gizmos.line(start - tick, start + tick, color);
+        gizmos.line(end - tick, end + tick, color);
+        label(pose.focus - up * 14.0 * per_pixel, camera, transform, format_with_unit(bar as f64, Some(show.unit)), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_length_rounds_down_to_1_2_5() {
+        assert_eq!(nice_length(0.0), 0.0);
+        assert_eq!(nice_length(1.0), 1.0);
+        assert_eq!(nice_length(3.7), 2.0);
+        assert_eq!(nice_length(49.0), 20.0);
+        assert!((nice_length(0.07) - 0.05).abs() < 1e-6);
+        assert_eq!(nice_length(800.0), 500.0);
+    }
+}
@@ -1,23 +1,56 @@
-//! Camera controllers for 3D navigation.
+//! Camera controllers for 3D orbiting and 2D panning.
 
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::EguiContext;
+
+/// Fractional zoom change per scroll line.
+const ZOOM_SENSITIVITY: f32 = 0.1;
+
+/// Default [`OrbitCamera::smoothing`] time constant, in seconds.
+pub const DEFAULT_ORBIT_SMOOTHING: f32 = 0.08;
 
 /// Plugin for camera control systems.
 pub struct CameraControllerPlugin;
 
 impl Plugin for CameraControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, orbit_camera_system);
+        app.add_systems(
+            Update,
+            ((orbit_camera_input, orbit_camera_system).chain(), pan_2d_camera_input),
+        );
     }
 }
 
 /// Component marking a camera with orbit controls.
+///
+/// With `auto_rotate` set, the camera slowly advances `yaw` for unattended
+/// demos, pausing whenever the user clicks or scrolls in the scene and
+/// resuming after `auto_rotate_delay` seconds of inactivity. Input over egui
+/// panels doesn't count, so toggling the mode doesn't delay it.
+///
+/// `focus`, `distance`, `pitch` and `yaw` are targets: each frame the view
+/// eases toward them with time constant `smoothing`, so input and scripted
+/// moves both glide rather than jump.
 #[derive(Component)]
 pub struct OrbitCamera {
     pub focus: Vec3,
     pub distance: f32,
     pub pitch: f32,
     pub yaw: f32,
+    /// Seconds for the view to cover about 63% of the way to its targets,
+    /// independent of frame rate; 0 follows them instantly.
+    pub smoothing: f32,
+    /// Pose the view is drawn from, or `None` to start at the targets.
+    pub current: Option<OrbitPose>,
+    pub auto_rotate: bool,
+    /// Auto-rotation speed in radians per second.
+    pub auto_rotate_speed: f32,
+    /// Seconds without input before auto-rotation resumes.
+    pub auto_rotate_delay: f32,
+    /// Seconds since the last user interaction.
+    pub idle_time: f32,
 }
 
 impl Default for OrbitCamera {
@@ -27,18 +60,78 @@ impl Default for OrbitCamera {
             distance: 50.0,
             pitch: -0.5,
             yaw: 0.0,
+            smoothing: DEFAULT_ORBIT_SMOOTHING,
+            current: None,
+            auto_rotate: false,
+            auto_rotate_speed: 0.2,
+            auto_rotate_delay: 3.0,
+            idle_time: 0.0,
         }
     }
 }
 
+impl OrbitCamera {
+    /// The pose the view is easing toward.
+    pub fn target(&self) -> OrbitPose {
+        OrbitPose { focus: self.focus, distance: self.distance, pitch: self.pitch, yaw: self.yaw }
+    }
+
+    /// The pose the view is drawn from this frame.
+    pub fn current(&self) -> OrbitPose {
+        self.current.unwrap_or_else(|| self.target())
+    }
+
+    /// Jumps straight to the targets on the next frame, skipping the ease.
+    pub fn snap(&mut self) {
+        self.current = None;
+    }
+}
+
+/// Where an orbit camera looks from: `distance` away from `focus` at the
+/// given `pitch` and `yaw`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitPose {
+    pub focus: Vec3,
+    pub distance: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl OrbitPose {
+    pub fn position(&self) -> Vec3 {
+        let x = self.distance * self.pitch.cos() * self.yaw.sin();
+        let y = self.distance * self.pitch.sin();
+        let z = self.distance * self.pitch.cos() * self.yaw.cos();
+        self.focus + Vec3::new(x, -y, z)
+    }
+
+    /// Moves `fraction` of the way toward `target`.
+    pub fn approach(&mut self, target: OrbitPose, fraction: f32) {
+        self.focus = self.focus.lerp(target.focus, fraction);
+        self.distance += (target.distance - self.distance) * fraction;
+        self.pitch += (target.pitch - self.pitch) * fraction;
+        self.yaw += (target.yaw - self.yaw) * fraction;
+    }
+}
+
+/// Fraction of the remaining way covered in `dt` seconds by an exponential
+/// ease with time constant `smoothing`; 1 when smoothing is off.
+pub fn smoothing_fraction(smoothing: f32, dt: f32) -> f32 {
+    if smoothing <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-dt / smoothing).exp()
+    }
+}
+
 /// Spawns a 3D camera with orbit controls.
 pub fn spawn_orbit_camera(commands: &mut Commands, config: OrbitCamera) -> Entity {
-    let position = calculate_camera_position(&config);
+    let pose = config.current();
 
     commands
         .spawn((
             Camera3dBundle {
-                transform: Transform::from_translation(position).looking_at(config.focus, Vec3::Y),
+                transform: Transform::from_translation(pose.position()).looking_at(pose.focus, Vec3::Y),
                 ..default()
             },
             config,
@@ -46,19 +139,148 @@ pub fn spawn_orbit_camera(commands: &mut Commands, config: OrbitCamera) -> Entit
         .id()
 }
 
-fn calculate_camera_position(config: &OrbitCamera) -> Vec3 {
-    let x = config.distance * config.pitch.cos() * config.yaw.sin();
-    let y = config.distance * config.pitch.sin();
-    let z = config.distance * config.pitch.cos() * config.yaw.cos();
-    config.focus + Vec3::new(x, -y, z)
+fn orbit_camera_input(
+    time: Res<Time>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut egui_contexts: Query<&mut EguiContext>,
+    mut query: Query<&mut OrbitCamera>,
+) {
+    let scrolled = mouse_wheel.read().any(|event| event.y != 0.0);
+    let interacted =
+        (mouse_buttons.get_pressed().next().is_some() || scrolled) && !egui_wants_pointer(&mut egui_contexts);
+    let dt = time.delta_seconds();
+
+    for mut orbit in query.iter_mut() {
+        if interacted {
+            orbit.idle_time = 0.0;
+        } else {
+            orbit.idle_time += dt;
+        }
+
+        if orbit.auto_rotate && orbit.idle_time >= orbit.auto_rotate_delay {
+            orbit.yaw += orbit.auto_rotate_speed * dt;
+        }
+    }
+}
+
+/// Component adding pan and zoom controls to a 2D orthographic camera.
+///
+/// Middle-drag pans and the scroll wheel zooms toward the cursor, with
+/// `OrthographicProjection::scale` kept within `min_scale..=max_scale`.
+/// Input is ignored while egui has the pointer.
+#[derive(Component, Debug, Clone)]
+pub struct Pan2dCamera {
+    pub min_scale: f32,
+    pub max_scale: f32,
+}
+
+impl Default for Pan2dCamera {
+    fn default() -> Self {
+        Self {
+            min_scale: 0.05,
+            max_scale: 10.0,
+        }
+    }
+}
+
+/// Whether any egui context is using the pointer (hovering a panel or
+/// dragging a widget).
+fn egui_wants_pointer(contexts: &mut Query<&mut EguiContext>) -> bool {
+    contexts.iter_mut().any(|mut context| {
+        let ctx = context.get_mut();
+        ctx.is_pointer_over_area() || ctx.wants_pointer_input()
+    })
 }
 
-fn orbit_camera_system(
-    mut query: Query<(&mut Transform, &OrbitCamera)>,
+fn pan_2d_camera_input(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut egui_contexts: Query<&mut EguiContext>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection, &Pan2dCamera)>,
 ) {
-    for (mut transform, orbit) in query.iter_mut() {
-        let position = calculate_camera_position(orbit);
-        transform.translation = position;
-        transform.look_at(orbit.focus, Vec3::Y);
+    let motion: Vec2 = mouse_motion.read().map(|event| event.delta).sum();
+    let scroll: f32 = mouse_wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        })
+        .sum();
+    let panning = mouse_buttons.pressed(MouseButton::Middle) && motion != Vec2::ZERO;
+    if (!panning && scroll == 0.0) || egui_wants_pointer(&mut egui_contexts) {
+        return;
+    }
+
+    // Cursor offset from the window center in world-aligned (y-up) pixels
+    let cursor_offset = windows
+        .get_single()
+        .ok()
+        .and_then(|window| Some((window.cursor_position()?, window.size())))
+        .map(|(cursor, size)| {
+            let offset = cursor - size / 2.0;
+            Vec2::new(offset.x, -offset.y)
+        })
+        .unwrap_or(Vec2::ZERO);
+
+    for (mut transform, mut projection, pan) in query.iter_mut() {
+        if panning {
+            transform.translation.x -= motion.x * projection.scale;
+            transform.translation.y += motion.y * projection.scale;
+        }
+        if scroll != 0.0 {
+            let old_scale = projection.scale;
+            let new_scale = (old_scale * (1.0 - scroll * ZOOM_SENSITIVITY)).clamp(pan.min_scale, pan.max_scale);
+            projection.scale = new_scale;
+            // Keep the point under the cursor fixed
+            let shift = cursor_offset * (old_scale - new_scale);
+            transform.translation.x += shift.x;
+            transform.translation.y += shift.y;
+        }
+    }
+}
+
+/// Eases each orbit camera toward its targets and places it there.
+fn orbit_camera_system(time: Res<Time>, mut query: Query<(&mut Transform, &mut OrbitCamera)>) {
+    for (mut transform, mut orbit) in query.iter_mut() {
+        let target = orbit.target();
+        let pose = match orbit.current {
+            Some(mut pose) => {
+                pose.approach(target, smoothing_fraction(orbit.smoothing, time.delta_seconds()));
+                pose
+            }
+            None => target,
+        };
+        orbit.current = Some(pose);
+        transform.translation = pose.position();
+        transform.look_at(pose.focus, Vec3::Y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing_is_independent_of_frame_rate() {
+        let target = OrbitPose { focus: Vec3::X * 10.0, distance: 20.0, pitch: 0.5, yaw: 1.0 };
+        let start = OrbitPose { focus: Vec3::ZERO, distance: 50.0, pitch: -0.5, yaw: 0.0 };
+        let ease = |frames: usize| {
+            let mut pose = start;
+            for _ in 0..frames {
+                pose.approach(target, smoothing_fraction(DEFAULT_ORBIT_SMOOTHING, 0.5 / frames as f32));
+            }
+            pose
+        };
+
+        // Half a second at 30 and at 240 frames per second ends up in the
+        // same place, most of the way there.
+        let (slow, fast) = (ease(15), ease(120));
+        assert!((slow.distance - fast.distance).abs() < 1e-3);
+        assert!(slow.focus.distance(fast.focus) < 1e-3);
+        assert!((slow.distance - target.distance).abs() < 0.1);
+        assert_eq!(smoothing_fraction(0.0, 0.016), 1.0);
     }
 }
@@ -3,14 +3,22 @@
 //! Shared rendering infrastructure for Entropy Zero simulations.
 //!
 //! Provides:
+//! - Runtime anti-aliasing (MSAA) setting
 //! - Camera controllers (orbit, pan, zoom)
 //! - Grid and axis visualization
-//! - Gizmos (vectors, arrows, coordinate frames)
-//! - Common materials and shaders
+//! - Split-screen viewports for side-by-side comparisons
+//! - Gizmos (vectors, arrows, coordinate frames) and vector field arrows
+//! - Common materials, scientific colormaps, and shaders
+//! - A dev tool capturing gallery thumbnails
 
+pub mod antialiasing;
+pub mod axes;
 pub mod camera;
 pub mod grid;
 pub mod materials;
+pub mod thumbnail;
+pub mod vector_field;
+pub mod viewport;
 
 use bevy::prelude::*;
 
@@ -19,15 +27,24 @@ pub struct EzRendererPlugin;
 
 impl Plugin for EzRendererPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(camera::CameraControllerPlugin)
-            .add_plugins(grid::GridPlugin);
+        app.add_plugins(antialiasing::AntiAliasingPlugin)
+            .add_plugins(camera::CameraControllerPlugin)
+            .add_plugins(axes::AxesPlugin)
+            .add_plugins(grid::GridPlugin)
+            .add_plugins(vector_field::VectorFieldPlugin)
+            .add_plugins(viewport::SplitViewportPlugin);
     }
 }
 
 /// Prelude for convenient imports.
 pub mod prelude {
+    pub use crate::antialiasing::*;
+    pub use crate::axes::*;
     pub use crate::camera::*;
     pub use crate::grid::*;
     pub use crate::materials::*;
+    pub use crate::thumbnail::*;
+    pub use crate::vector_field::*;
+    pub use crate::viewport::*;
     pub use crate::EzRendererPlugin;
 }
@@ -19,6 +19,84 @@ pub fn simple_material(color: Color) -> StandardMaterial {
     }
 }
 
+// ══════════════════════════════════════════════════════════════════════════════
+// Colormaps
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Evaluates a degree-6 polynomial fit of a colormap channel-wise.
+fn polynomial_colormap(coefficients: &[[f32; 3]; 7], t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let [r, g, b] = std::array::from_fn(|channel| {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, c| acc * t + c[channel])
+            .clamp(0.0, 1.0)
+    });
+    Color::srgb(r, g, b)
+}
+
+/// Perceptually uniform sequential colormap (dark purple → green → yellow).
+pub fn viridis(t: f32) -> Color {
+    const COEFFICIENTS: [[f32; 3]; 7] = [
+        [0.27773, 0.0054073, 0.3341],
+        [0.10509, 1.4046, 1.3846],
+        [-0.33086, 0.21485, 0.095095],
+        [-4.6342, -5.7991, -19.332],
+        [6.2283, 14.18, 56.691],
+        [4.7764, -13.745, -65.353],
+        [-5.4355, 4.6459, 26.312],
+    ];
+    polynomial_colormap(&COEFFICIENTS, t)
+}
+
+/// Perceptually uniform sequential colormap (black → purple → orange → pale yellow).
+pub fn inferno(t: f32) -> Color {
+    const COEFFICIENTS: [[f32; 3]; 7] = [
+        [0.00021894, 0.001651, -0.019481],
+        [0.10651, 0.56396, 3.9327],
+        [11.602, -3.9729, -15.942],
+        [-41.704, 17.436, 44.354],
+        [77.163, -33.402, -81.807],
+        [-71.319, 32.626, 73.21],
+        [25.131, -12.243, -23.07],
+    ];
+    polynomial_colormap(&COEFFICIENTS, t)
+}
+
+/// Rainbow colormap with smooth lightness (blue → green → yellow → red).
+///
+/// An improved replacement for "jet".
+pub fn turbo(t: f32) -> Color {
+    const COEFFICIENTS: [[f32; 3]; 7] = [
+        [0.13572, 0.091403, 0.10667],
+        [4.6154, 2.1942, 12.642],
+        [-42.66, 4.843, -60.582],
+        [132.13, -14.185, 110.36],
+        [-152.94, 4.2773, -89.903],
+        [59.286, 2.8296, 27.348],
+        [0.0, 0.0, 0.0],
+    ];
+    polynomial_colormap(&COEFFICIENTS, t)
+}
+
+/// Diverging colormap for signed data (blue → light gray → red), with zero
+/// at `t = 0.5`.
+pub fn diverging_blue_red(t: f32) -> Color {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.230, 0.299, 0.754],
+        [0.552, 0.690, 0.996],
+        [0.865, 0.865, 0.865],
+        [0.958, 0.604, 0.482],
+        [0.706, 0.016, 0.150],
+    ];
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (scaled as usize).min(STOPS.len() - 2);
+    let f = scaled - i as f32;
+    let [r, g, b] = std::array::from_fn(|c| STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * f);
+    Color::srgb(r, g, b)
+}
+
 /// Common color palette for simulations.
 pub mod palette {
     use bevy::prelude::*;
@@ -30,3 +108,21 @@ pub mod palette {
     pub const FIELD_BLUE: Color = Color::srgb(0.3, 0.3, 1.0);
     pub const GRID_GRAY: Color = Color::srgba(0.5, 0.5, 0.5, 0.3);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn luminance(color: Color) -> f32 {
+        let linear = color.to_linear();
+        0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+    }
+
+    #[test]
+    fn test_viridis_luminance_increases() {
+        let samples: Vec<f32> = (0..=10).map(|i| luminance(viridis(i as f32 / 10.0))).collect();
+        for pair in samples.windows(2) {
+            assert!(pair[1] > pair[0], "luminance not increasing: {:?}", samples);
+        }
+    }
+}
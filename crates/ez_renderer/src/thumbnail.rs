@@ -0,0 +1,401 @@
+//! Dev tool that captures gallery thumbnails.
+//!
+//! [`Simulation::thumbnail`] paths conventionally point at
+//! `assets/thumbnails/{id}.png`. With [`ThumbnailCapturePlugin`] added,
+//! [`CAPTURE_KEY`] saves the active simulation there, and running with
+//! [`CAPTURE_THUMBNAILS_FLAG`] walks every registered simulation, lets each
+//! run for [`THUMBNAIL_DELAY`] seconds, saves it, and exits.
+//!
+//! Captures are rendered offscreen: every camera drawing to the primary
+//! window is pointed at a window-sized [`Image`] for a few frames, the render
+//! graph copies that image back to the CPU, and the cameras go back to the
+//! window. egui draws straight to the window, so its panels stay out of the
+//! thumbnail.
+//!
+//! [`Simulation::thumbnail`]: ez_core::traits::Simulation::thumbnail
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_graph::{self, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel};
+use bevy::render::render_resource::{
+    Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout, Maintain, MapMode,
+    TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::GpuImage;
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+use bevy::window::{PrimaryWindow, WindowRef};
+use ez_core::registry::{ActiveSimulation, SimulationRegistry};
+use image::ExtendedColorType;
+
+/// Directory thumbnails are written to, relative to the working directory.
+pub const THUMBNAIL_DIR: &str = "assets/thumbnails";
+/// Seconds a simulation runs before its thumbnail is taken, so waves and
+/// particles have time to fill the view.
+pub const THUMBNAIL_DELAY: f32 = 3.0;
+/// Command-line flag that captures every simulation, then exits.
+pub const CAPTURE_THUMBNAILS_FLAG: &str = "--capture-thumbnails";
+/// Key that captures the active simulation straight away.
+pub const CAPTURE_KEY: KeyCode = KeyCode::F9;
+/// Frames the cameras draw into the offscreen image before it is read back,
+/// so the render world has prepared the image and rendered a full frame.
+const THUMBNAIL_SETTLE_FRAMES: u32 = 3;
+/// Format of the offscreen image, which is also the byte layout saved to disk.
+const THUMBNAIL_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// Plugin saving thumbnails on [`CAPTURE_KEY`] or [`CAPTURE_THUMBNAILS_FLAG`].
+pub struct ThumbnailCapturePlugin;
+
+impl Plugin for ThumbnailCapturePlugin {
+    fn build(&self, app: &mut App) {
+        let batch = std::env::args().any(|arg| arg == CAPTURE_THUMBNAILS_FLAG);
+        app.insert_resource(ThumbnailCapture { batch, ..default() })
+            .add_systems(PostStartup, queue_all_thumbnails.run_if(|capture: Res<ThumbnailCapture>| capture.batch))
+            .add_systems(
+                Update,
+                (capture_on_key, run_thumbnail_batch.run_if(|capture: Res<ThumbnailCapture>| capture.batch)),
+            )
+            .add_systems(PostUpdate, finish_thumbnails);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        if let Some(mut graph) = render_app.world_mut().get_resource_mut::<RenderGraph>() {
+            graph.add_node(ThumbnailCopy, ThumbnailCopyNode);
+            graph.add_node_edge(bevy::render::graph::CameraDriverLabel, ThumbnailCopy);
+        }
+        render_app
+            .init_resource::<ThumbnailCopiers>()
+            .add_systems(ExtractSchedule, extract_thumbnail_copiers)
+            .add_systems(Render, read_back_thumbnails.after(RenderSet::Render));
+    }
+}
+
+/// Progress of a [`CAPTURE_THUMBNAILS_FLAG`] run.
+#[derive(Resource, Default)]
+pub struct ThumbnailCapture {
+    /// Capturing every simulation, then exiting.
+    pub batch: bool,
+    /// Simulations still to capture, in registration order.
+    pub pending: VecDeque<&'static str>,
+    /// Seconds since the current simulation was entered or the last capture.
+    pub elapsed: f32,
+    /// The front of `pending` has been handed to an offscreen render.
+    pub started: bool,
+}
+
+/// Where a simulation's thumbnail is saved.
+pub fn thumbnail_path(id: &str) -> String {
+    format!("{THUMBNAIL_DIR}/{id}.png")
+}
+
+/// An offscreen thumbnail render, extracted so the render graph can copy it
+/// into a CPU-readable buffer.
+#[derive(Component, Clone)]
+struct ThumbnailCopier {
+    image: Handle<Image>,
+    buffer: Buffer,
+    /// Set once the view has settled; the render graph copies the next frame
+    /// and clears it, so each capture is copied exactly once.
+    requested: Arc<AtomicBool>,
+    /// Set by the copy so the buffer is mapped after the graph has run.
+    copied: Arc<AtomicBool>,
+    /// Pixels read back from the buffer, rows padded to the copy alignment.
+    pixels: Arc<Mutex<Option<Vec<u8>>>>,
+}
+
+/// Main-world bookkeeping for the capture on the same entity.
+#[derive(Component)]
+struct ThumbnailShot {
+    id: &'static str,
+    size: UVec2,
+    /// Cameras drawing into the image, with the targets they go back to.
+    cameras: Vec<(Entity, RenderTarget)>,
+    frames: u32,
+}
+
+/// Copiers in flight this frame, in the render world.
+#[derive(Resource, Default, Deref)]
+struct ThumbnailCopiers(Vec<ThumbnailCopier>);
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, RenderLabel)]
+struct ThumbnailCopy;
+
+/// Render graph node copying each requested thumbnail image into its buffer.
+#[derive(Default)]
+struct ThumbnailCopyNode;
+
+/// Bytes per row of the read-back buffer for an image `width` pixels wide.
+fn padded_bytes_per_row(width: u32) -> usize {
+    RenderDevice::align_copy_bytes_per_row(width as usize * 4)
+}
+
+/// Points every camera drawing to the primary window at a fresh window-sized
+/// image, and spawns the copier that reads it back for `id`.
+fn start_thumbnail(
+    id: &'static str,
+    commands: &mut Commands,
+    window: (Entity, &Window),
+    cameras: &mut Query<(Entity, &mut Camera)>,
+    images: &mut Assets<Image>,
+    render_device: &RenderDevice,
+) {
+    let (window_entity, window) = window;
+    let size = window.physical_size();
+    if size.x == 0 || size.y == 0 {
+        warn!("Thumbnail {} not saved: the window has no area", id);
+        return;
+    }
+
+    let extent = Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 };
+    let mut image = Image::new_fill(extent, TextureDimension::D2, &[0; 4], THUMBNAIL_FORMAT, RenderAssetUsages::default());
+    image.texture_descriptor.usage |=
+        TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+    let image = images.add(image);
+
+    let mut retargeted = Vec::new();
+    for (entity, mut camera) in cameras.iter_mut() {
+        let on_window = match camera.target {
+            RenderTarget::Window(WindowRef::Primary) => true,
+            RenderTarget::Window(WindowRef::Entity(entity)) => entity == window_entity,
+            _ => false,
+        };
+        if camera.is_active && on_window {
+            let target = std::mem::replace(&mut camera.target, RenderTarget::Image(image.clone()));
+            retargeted.push((entity, target));
+        }
+    }
+    if retargeted.is_empty() {
+        warn!("Thumbnail {} not saved: no camera draws to the window", id);
+        images.remove(&image);
+        return;
+    }
+
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("thumbnail_readback"),
+        size: (padded_bytes_per_row(size.x) * size.y as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    commands.spawn((
+        ThumbnailCopier {
+            image,
+            buffer,
+            requested: default(),
+            copied: default(),
+            pixels: default(),
+        },
+        ThumbnailShot { id, size, cameras: retargeted, frames: 0 },
+    ));
+}
+
+/// Writes read-back pixels to the simulation's thumbnail file, dropping the
+/// row padding the copy needed.
+fn save_thumbnail(id: &str, size: UVec2, padded: &[u8]) {
+    if padded.is_empty() {
+        warn!("Thumbnail {} not saved: nothing was read back", id);
+        return;
+    }
+    if let Err(error) = std::fs::create_dir_all(THUMBNAIL_DIR) {
+        error!("Can't create {}: {}", THUMBNAIL_DIR, error);
+        return;
+    }
+    let row_bytes = size.x as usize * 4;
+    let pixels: Vec<u8> = padded
+        .chunks(padded_bytes_per_row(size.x))
+        .take(size.y as usize)
+        .flat_map(|row| &row[..row_bytes])
+        .copied()
+        .collect();
+
+    let path = thumbnail_path(id);
+    match image::save_buffer(&path, &pixels, size.x, size.y, ExtendedColorType::Rgba8) {
+        Ok(()) => info!("Saved thumbnail {}", path),
+        Err(error) => warn!("Thumbnail {} not saved: {}", path, error),
+    }
+}
+
+fn queue_all_thumbnails(mut capture: ResMut<ThumbnailCapture>, registry: Option<Res<SimulationRegistry>>) {
+    let Some(registry) = registry else {
+        warn!("{} needs a simulation registry", CAPTURE_THUMBNAILS_FLAG);
+        capture.batch = false;
+        return;
+    };
+    capture.pending = registry.metadata().iter().map(|meta| meta.id).collect();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn capture_on_key(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    registry: Option<Res<SimulationRegistry>>,
+    window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut cameras: Query<(Entity, &mut Camera)>,
+    mut images: ResMut<Assets<Image>>,
+    render_device: Res<RenderDevice>,
+    shots: Query<(), With<ThumbnailShot>>,
+) {
+    if !keys.just_pressed(CAPTURE_KEY) || !shots.is_empty() {
+        return;
+    }
+    let (Some(id), Ok(window)) = (registry.and_then(|registry| registry.active()), window.get_single()) else {
+        return;
+    };
+    start_thumbnail(id, &mut commands, window, &mut cameras, &mut images, &render_device);
+}
+
+/// Switches to each pending simulation in turn and captures it once it has
+/// run for [`THUMBNAIL_DELAY`], exiting after the last has been saved.
+#[allow(clippy::too_many_arguments)]
+fn run_thumbnail_batch(
+    mut commands: Commands,
+    mut capture: ResMut<ThumbnailCapture>,
+    mut registry: ResMut<SimulationRegistry>,
+    mut next_state: ResMut<NextState<ActiveSimulation>>,
+    window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut cameras: Query<(Entity, &mut Camera)>,
+    mut images: ResMut<Assets<Image>>,
+    render_device: Res<RenderDevice>,
+    shots: Query<(), With<ThumbnailShot>>,
+    time: Res<Time>,
+    mut exit: EventWriter<AppExit>,
+) {
+    capture.elapsed += time.delta_seconds();
+    if !shots.is_empty() {
+        return;
+    }
+    let Some(&id) = capture.pending.front() else {
+        exit.send(AppExit::Success);
+        return;
+    };
+    if capture.started {
+        capture.pending.pop_front();
+        capture.started = false;
+        capture.elapsed = 0.0;
+        return;
+    }
+    if registry.active() != Some(id) {
+        registry.switch_to(id, &mut next_state);
+        capture.elapsed = 0.0;
+        return;
+    }
+    if capture.elapsed < THUMBNAIL_DELAY {
+        return;
+    }
+    if let Ok(window) = window.get_single() {
+        start_thumbnail(id, &mut commands, window, &mut cameras, &mut images, &render_device);
+    }
+    capture.started = true;
+}
+
+/// Requests the copy once the image has settled, then saves the read-back
+/// pixels and hands the cameras back to the window.
+fn finish_thumbnails(
+    mut commands: Commands,
+    mut shots: Query<(Entity, &ThumbnailCopier, &mut ThumbnailShot)>,
+    mut cameras: Query<&mut Camera>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (entity, copier, mut shot) in shots.iter_mut() {
+        shot.frames += 1;
+        if shot.frames == THUMBNAIL_SETTLE_FRAMES {
+            copier.requested.store(true, Ordering::Release);
+        }
+        let Some(pixels) = copier.pixels.lock().ok().and_then(|mut pixels| pixels.take()) else {
+            continue;
+        };
+
+        save_thumbnail(shot.id, shot.size, &pixels);
+        for (camera, target) in shot.cameras.drain(..) {
+            if let Ok(mut camera) = cameras.get_mut(camera) {
+                camera.target = target;
+            }
+        }
+        images.remove(&copier.image);
+        commands.entity(entity).despawn();
+    }
+}
+
+fn extract_thumbnail_copiers(mut commands: Commands, copiers: Extract<Query<&ThumbnailCopier>>) {
+    commands.insert_resource(ThumbnailCopiers(copiers.iter().cloned().collect()));
+}
+
+impl render_graph::Node for ThumbnailCopyNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let (Some(copiers), Some(gpu_images)) =
+            (world.get_resource::<ThumbnailCopiers>(), world.get_resource::<RenderAssets<GpuImage>>())
+        else {
+            return Ok(());
+        };
+
+        for copier in copiers.iter() {
+            // The image may not be on the GPU yet; keep the request for next frame.
+            let Some(image) = gpu_images.get(&copier.image) else {
+                continue;
+            };
+            if !copier.requested.swap(false, Ordering::AcqRel) {
+                continue;
+            }
+            render_context.command_encoder().copy_texture_to_buffer(
+                image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &copier.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_bytes_per_row(image.size.x) as u32),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d { width: image.size.x, height: image.size.y, depth_or_array_layers: 1 },
+            );
+            copier.copied.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+}
+
+/// Maps the buffers copied this frame and hands their pixels to the main
+/// world. Blocks on the GPU, which is fine for a native-only dev tool.
+fn read_back_thumbnails(copiers: Res<ThumbnailCopiers>, render_device: Res<RenderDevice>) {
+    for copier in copiers.iter() {
+        if !copier.copied.swap(false, Ordering::AcqRel) {
+            continue;
+        }
+        let slice = copier.buffer.slice(..);
+        let (mapped, on_mapped) = mpsc::sync_channel(1);
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = mapped.send(result);
+        });
+        render_device.poll(Maintain::wait()).panic_on_timeout();
+
+        // An empty read-back still finishes the capture, so a batch run moves on.
+        let pixels = match on_mapped.recv() {
+            Ok(Ok(())) => {
+                let pixels = slice.get_mapped_range().to_vec();
+                copier.buffer.unmap();
+                pixels
+            }
+            Ok(Err(error)) => {
+                error!("Thumbnail read-back failed: {}", error);
+                Vec::new()
+            }
+            Err(_) => {
+                error!("Thumbnail read-back was dropped");
+                Vec::new()
+            }
+        };
+        if let Ok(mut slot) = copier.pixels.lock() {
+            *slot = Some(pixels);
+        }
+    }
+}
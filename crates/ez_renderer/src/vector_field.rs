@@ -0,0 +1,77 @@
+//! Vector field (quiver) rendering.
+//!
+//! Simulations fill the [`VectorField`] resource each frame and the plugin
+//! draws one gizmo arrow per sample, colored by magnitude with [`viridis`].
+
+use bevy::prelude::*;
+
+use crate::materials::viridis;
+
+/// Plugin that draws the [`VectorField`] resource as arrows.
+pub struct VectorFieldPlugin;
+
+impl Plugin for VectorFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VectorField>()
+            .add_systems(PostUpdate, draw_vector_field);
+    }
+}
+
+/// Arrows to draw this frame, as parallel lists of origins and vectors.
+#[derive(Resource, Clone)]
+pub struct VectorField {
+    pub origins: Vec<Vec3>,
+    pub vectors: Vec<Vec3>,
+    /// World length of an arrow per unit of vector magnitude.
+    pub scale: f32,
+    /// Vectors shorter than this (before scaling) are skipped.
+    pub min_magnitude: f32,
+}
+
+impl Default for VectorField {
+    fn default() -> Self {
+        Self {
+            origins: Vec::new(),
+            vectors: Vec::new(),
+            scale: 1.0,
+            min_magnitude: 1e-3,
+        }
+    }
+}
+
+impl VectorField {
+    pub fn clear(&mut self) {
+        self.origins.clear();
+        self.vectors.clear();
+    }
+
+    pub fn push(&mut self, origin: Vec3, vector: Vec3) {
+        self.origins.push(origin);
+        self.vectors.push(vector);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+
+    /// Largest vector magnitude in the field, or 0 when empty.
+    pub fn max_magnitude(&self) -> f32 {
+        self.vectors.iter().map(|v| v.length()).fold(0.0, f32::max)
+    }
+}
+
+fn draw_vector_field(field: Res<VectorField>, mut gizmos: Gizmos) {
+    let max_magnitude = field.max_magnitude();
+    if max_magnitude <= 0.0 {
+        return;
+    }
+
+    for (&origin, &vector) in field.origins.iter().zip(&field.vectors) {
+        let magnitude = vector.length();
+        if magnitude < field.min_magnitude {
+            continue;
+        }
+        let color = viridis(magnitude / max_magnitude);
+        gizmos.arrow(origin, origin + vector * field.scale, color);
+    }
+}
@@ -0,0 +1,318 @@
+//! Split-screen viewports for side-by-side comparisons.
+//!
+//! Enabling [`SplitViewportConfig`] spawns a left/right pair of cameras, each
+//! drawing into its half of the window via [`split_viewport_rect`]. Entities
+//! on the default render layer appear in both views; tag an entity with a
+//! [`ViewportSide`] to show it on that side only, e.g. the wave field with a
+//! slit on the left and without on the right. While split, the other cameras
+//! drawing to the window are switched off, and switched back on afterwards.
+
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, Viewport};
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::Pan2dCamera;
+
+/// Plugin that spawns the split-screen camera pair and keeps it sized to
+/// the window.
+pub struct SplitViewportPlugin;
+
+impl Plugin for SplitViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SplitViewportConfig>().add_systems(
+            PostUpdate,
+            (sync_split_cameras, route_split_entities, update_split_viewports).chain(),
+        );
+    }
+}
+
+/// Configuration for split-screen rendering.
+#[derive(Resource, Clone)]
+pub struct SplitViewportConfig {
+    pub enabled: bool,
+    /// Horizontal position of the divider as a fraction of the window width.
+    pub divider: f32,
+    /// Where both cameras start when the split is enabled.
+    pub camera_transform: Transform,
+    /// Kind of camera pair to spawn.
+    pub cameras: SplitCameraKind,
+}
+
+/// Kind of camera pair [`SplitViewportConfig`] spawns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitCameraKind {
+    /// Perspective 3D cameras.
+    Camera3d,
+    /// Orthographic 2D cameras at this projection scale, panning and zooming
+    /// together.
+    Camera2d { scale: f32 },
+}
+
+impl Default for SplitViewportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            divider: 0.5,
+            camera_transform: Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+            cameras: SplitCameraKind::Camera3d,
+        }
+    }
+}
+
+/// Which half of the window a camera or entity belongs to.
+///
+/// As a component it routes an entity to that side's camera only.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewportSide {
+    Left,
+    Right,
+}
+
+impl ViewportSide {
+    /// Render layer shared by both views (the default layer).
+    pub const SHARED_LAYER: usize = 0;
+
+    /// Render layer that only this side's camera draws.
+    pub fn layer(&self) -> usize {
+        match self {
+            Self::Left => 1,
+            Self::Right => 2,
+        }
+    }
+
+    /// Layers a camera on this side should render.
+    pub fn camera_layers(&self) -> RenderLayers {
+        RenderLayers::from_layers(&[Self::SHARED_LAYER, self.layer()])
+    }
+
+    /// Layers for an entity that should only appear on this side.
+    pub fn exclusive_layers(&self) -> RenderLayers {
+        RenderLayers::layer(self.layer())
+    }
+}
+
+/// Component marking a camera as one half of a split-screen pair.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SplitViewport {
+    pub side: ViewportSide,
+}
+
+/// Component marking a window camera switched off while the split is on.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SuspendedBySplit;
+
+/// Spawns a left/right pair of 3D cameras with identical transforms.
+///
+/// The right camera renders after the left one so the two don't conflict.
+pub fn spawn_split_cameras_3d(commands: &mut Commands, transform: Transform) -> [Entity; 2] {
+    [ViewportSide::Left, ViewportSide::Right].map(|side| {
+        commands
+            .spawn((
+                Camera3dBundle {
+                    camera: Camera {
+                        order: 1 + side as isize,
+                        ..default()
+                    },
+                    transform,
+                    ..default()
+                },
+                side.camera_layers(),
+                SplitViewport { side },
+            ))
+            .id()
+    })
+}
+
+/// Spawns a left/right pair of 2D cameras with identical transforms and
+/// projection `scale`, panned and zoomed together by [`Pan2dCamera`].
+pub fn spawn_split_cameras_2d(commands: &mut Commands, transform: Transform, scale: f32) -> [Entity; 2] {
+    [ViewportSide::Left, ViewportSide::Right].map(|side| {
+        commands
+            .spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        order: 1 + side as isize,
+                        ..default()
+                    },
+                    transform,
+                    projection: OrthographicProjection {
+                        scale,
+                        ..default()
+                    },
+                    ..default()
+                },
+                Pan2dCamera::default(),
+                side.camera_layers(),
+                SplitViewport { side },
+            ))
+            .id()
+    })
+}
+
+/// The active camera whose viewport holds `cursor`, in logical window
+/// pixels, so picking works in either half of a split.
+pub fn camera_under_cursor<'a>(
+    cameras: impl IntoIterator<Item = (&'a Camera, &'a GlobalTransform)>,
+    cursor: Vec2,
+) -> Option<(&'a Camera, &'a GlobalTransform)> {
+    cameras.into_iter().find(|(camera, _)| {
+        camera.is_active && camera.logical_viewport_rect().is_some_and(|rect| rect.contains(cursor))
+    })
+}
+
+/// Computes the physical viewport for one side of a window.
+///
+/// The divider sits at `divider` times the window width, kept at least one
+/// pixel from either edge so neither side is empty; the two sides tile the
+/// window exactly.
+pub fn split_viewport_rect(window_size: UVec2, divider: f32, side: ViewportSide) -> Viewport {
+    let split = ((window_size.x as f32 * divider.clamp(0.0, 1.0)) as u32).clamp(1, window_size.x.max(2) - 1);
+    let (x, width) = match side {
+        ViewportSide::Left => (0, split),
+        ViewportSide::Right => (split, window_size.x.saturating_sub(split).max(1)),
+    };
+    Viewport {
+        physical_position: UVec2::new(x, 0),
+        physical_size: UVec2::new(width, window_size.y.max(1)),
+        ..default()
+    }
+}
+
+/// Spawns the camera pair when the split is enabled and removes it when
+/// disabled, suspending the other window cameras in between.
+fn sync_split_cameras(
+    mut commands: Commands,
+    config: Res<SplitViewportConfig>,
+    cameras: Query<Entity, With<SplitViewport>>,
+    mut others: Query<(Entity, &mut Camera, Has<SuspendedBySplit>), Without<SplitViewport>>,
+) {
+    // Checked every frame, so cameras spawned while split are caught too.
+    for (entity, mut camera, suspended) in others.iter_mut() {
+        if config.enabled && camera.is_active && matches!(camera.target, RenderTarget::Window(_)) {
+            camera.is_active = false;
+            commands.entity(entity).insert(SuspendedBySplit);
+        } else if !config.enabled && suspended {
+            camera.is_active = true;
+            commands.entity(entity).remove::<SuspendedBySplit>();
+        }
+    }
+
+    if !config.is_changed() {
+        return;
+    }
+    if config.enabled && cameras.is_empty() {
+        match config.cameras {
+            SplitCameraKind::Camera3d => spawn_split_cameras_3d(&mut commands, config.camera_transform),
+            SplitCameraKind::Camera2d { scale } => spawn_split_cameras_2d(&mut commands, config.camera_transform, scale),
+        };
+    } else if !config.enabled {
+        for entity in cameras.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Puts newly tagged entities on their side's exclusive render layer.
+fn route_split_entities(mut commands: Commands, tagged: Query<(Entity, &ViewportSide), Added<ViewportSide>>) {
+    for (entity, side) in tagged.iter() {
+        commands.entity(entity).insert(side.exclusive_layers());
+    }
+}
+
+fn update_split_viewports(
+    config: Res<SplitViewportConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Camera, &SplitViewport)>,
+) {
+    let Ok(window) = windows.get_single() else { return };
+    let window_size = window.physical_size();
+
+    for (mut camera, split) in cameras.iter_mut() {
+        camera.viewport = Some(split_viewport_rect(window_size, config.divider, split.side));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_viewports_tile_odd_windows() {
+        let window = UVec2::new(101, 51);
+        let sides = |divider: f32| {
+            let left = split_viewport_rect(window, divider, ViewportSide::Left);
+            let right = split_viewport_rect(window, divider, ViewportSide::Right);
+            assert_eq!(left.physical_position, UVec2::ZERO);
+            assert_eq!(right.physical_position.x, left.physical_size.x, "the halves meet at the divider");
+            assert_eq!(left.physical_size.x + right.physical_size.x, window.x);
+            assert_eq!((left.physical_size.y, right.physical_size.y), (51, 51));
+            (left.physical_size.x, right.physical_size.x)
+        };
+
+        assert_eq!(sides(0.5), (50, 51));
+        assert_eq!(sides(0.3), (30, 71));
+        // A divider at or past an edge still leaves a pixel on each side.
+        assert_eq!(sides(0.0), (1, 100));
+        assert_eq!(sides(1.0), (100, 1));
+        assert_eq!(sides(-2.0), (1, 100));
+    }
+
+    #[test]
+    fn test_enabling_the_split_spawns_a_routed_camera_pair() {
+        let mut app = App::new();
+        app.add_plugins(SplitViewportPlugin);
+        let left_only = app.world_mut().spawn(ViewportSide::Left).id();
+        app.update();
+        assert_eq!(app.world_mut().query::<&SplitViewport>().iter(app.world()).count(), 0);
+        assert_eq!(app.world().get::<RenderLayers>(left_only), Some(&ViewportSide::Left.exclusive_layers()));
+
+        app.world_mut().resource_mut::<SplitViewportConfig>().enabled = true;
+        app.update();
+        let mut cameras = app.world_mut().query::<(&SplitViewport, &RenderLayers)>();
+        let mut sides: Vec<_> = cameras.iter(app.world()).map(|(split, layers)| (split.side, layers.clone())).collect();
+        sides.sort_by_key(|(side, _)| *side as usize);
+        assert_eq!(
+            sides,
+            [
+                (ViewportSide::Left, ViewportSide::Left.camera_layers()),
+                (ViewportSide::Right, ViewportSide::Right.camera_layers()),
+            ]
+        );
+        // Only the left camera sees the left-only entity.
+        assert!(sides[0].1.intersects(&ViewportSide::Left.exclusive_layers()));
+        assert!(!sides[1].1.intersects(&ViewportSide::Left.exclusive_layers()));
+
+        app.world_mut().resource_mut::<SplitViewportConfig>().enabled = false;
+        app.update();
+        assert_eq!(app.world_mut().query::<&SplitViewport>().iter(app.world()).count(), 0);
+    }
+
+    #[test]
+    fn test_split_2d_cameras_stand_in_for_the_window_camera() {
+        let mut app = App::new();
+        app.add_plugins(SplitViewportPlugin);
+        let own = app.world_mut().spawn(Camera2dBundle::default()).id();
+        let is_active = |app: &App| app.world().get::<Camera>(own).unwrap().is_active;
+
+        let mut config = app.world_mut().resource_mut::<SplitViewportConfig>();
+        config.enabled = true;
+        config.cameras = SplitCameraKind::Camera2d { scale: 2.5 };
+        app.update();
+        assert!(!is_active(&app), "the simulation's own camera is off while split");
+        let mut split = app.world_mut().query_filtered::<&OrthographicProjection, With<SplitViewport>>();
+        let scales: Vec<f32> = split.iter(app.world()).map(|projection| projection.scale).collect();
+        assert_eq!(scales, [2.5, 2.5]);
+
+        // A camera spawned while split is caught too.
+        let late = app.world_mut().spawn(Camera2dBundle::default()).id();
+        app.update();
+        assert!(!app.world().get::<Camera>(late).unwrap().is_active);
+
+        app.world_mut().resource_mut::<SplitViewportConfig>().enabled = false;
+        app.update();
+        assert!(is_active(&app));
+        assert!(app.world().get::<Camera>(late).unwrap().is_active);
+        assert!(app.world().get::<SuspendedBySplit>(own).is_none());
+    }
+}
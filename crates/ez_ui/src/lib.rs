@@ -4,10 +4,14 @@
 //!
 //! Provides:
 //! - Control panels with automatic parameter binding
-//! - Real-time plotting
+//! - Real-time line and scatter plots
 //! - Common widgets (sliders, toggles, etc.)
+//! - A keyboard shortcut help overlay toggled with F1
+//! - Parameter values saved between sessions
 
 pub mod panels;
+pub mod persistence;
+pub mod shortcuts;
 pub mod widgets;
 
 use bevy::prelude::*;
@@ -21,12 +25,16 @@ impl Plugin for EzUiPlugin {
         if !app.is_plugin_added::<EguiPlugin>() {
             app.add_plugins(EguiPlugin);
         }
+        if !app.is_plugin_added::<shortcuts::ShortcutsOverlayPlugin>() {
+            app.add_plugins(shortcuts::ShortcutsOverlayPlugin);
+        }
     }
 }
 
 /// Prelude for convenient imports.
 pub mod prelude {
     pub use crate::panels::*;
+    pub use crate::shortcuts::*;
     pub use crate::widgets::*;
     pub use crate::EzUiPlugin;
 }
@@ -2,20 +2,26 @@
 
 use bevy::prelude::*;
 use bevy_egui::egui;
-use ez_core::parameters::{ParameterDef, ParameterValue};
+use ez_core::parameters::{ParameterDef, ParameterPreset, ParameterValue};
+use ez_core::units::{format_si, format_with_unit, parse_si};
 use std::collections::HashMap;
 
 /// Resource holding current parameter values for a simulation.
 #[derive(Resource, Default)]
 pub struct SimulationParameters {
     pub values: HashMap<&'static str, ParameterValue>,
+    /// Name of the most recently applied preset.
+    pub preset: Option<&'static str>,
 }
 
 impl SimulationParameters {
     /// Initialize from parameter definitions.
+    ///
+    /// Debug builds panic on a definition that fails [`ParameterDef::validate`].
     pub fn from_defs(defs: &[ParameterDef]) -> Self {
         let mut values = HashMap::new();
         for def in defs {
+            debug_assert_eq!(def.validate(), Ok(()), "invalid parameter definition");
             let value = match def {
                 ParameterDef::Float { id, default, .. } => {
                     (*id, ParameterValue::Float(*default))
@@ -26,6 +32,9 @@ impl SimulationParameters {
                 ParameterDef::Bool { id, default, .. } => {
                     (*id, ParameterValue::Bool(*default))
                 }
+                ParameterDef::Vec2 { id, default, .. } => {
+                    (*id, ParameterValue::Vec2(Vec2::from_array(*default)))
+                }
                 ParameterDef::Vec3 { id, default, .. } => {
                     (*id, ParameterValue::Vec3(Vec3::from_array(*default)))
                 }
@@ -43,7 +52,20 @@ impl SimulationParameters {
             };
             values.insert(value.0, value.1);
         }
-        Self { values }
+        Self { values, preset: None }
+    }
+
+    /// Overwrite the values named in a preset.
+    ///
+    /// Ids that are not part of this parameter set are ignored.
+    pub fn apply_preset(&mut self, preset: &ParameterPreset) {
+        let (name, overrides) = preset;
+        for (id, value) in overrides {
+            if let Some(current) = self.values.get_mut(id) {
+                *current = value.clone();
+            }
+        }
+        self.preset = Some(*name);
     }
 
     /// Get a float parameter value.
@@ -51,42 +73,110 @@ impl SimulationParameters {
         self.values.get(id).and_then(|v| v.as_float())
     }
 
+    /// Get an integer parameter value.
+    pub fn get_int(&self, id: &str) -> Option<i32> {
+        self.values.get(id).and_then(|v| v.as_int())
+    }
+
     /// Get a bool parameter value.
     pub fn get_bool(&self, id: &str) -> Option<bool> {
         self.values.get(id).and_then(|v| v.as_bool())
     }
+
+    /// Get a 2D vector parameter value.
+    pub fn get_vec2(&self, id: &str) -> Option<Vec2> {
+        self.values.get(id).and_then(|v| v.as_vec2())
+    }
+
+    /// Get a 3D vector parameter value.
+    pub fn get_vec3(&self, id: &str) -> Option<Vec3> {
+        self.values.get(id).and_then(|v| v.as_vec3())
+    }
+
+    /// Get a color parameter value.
+    pub fn get_color(&self, id: &str) -> Option<Color> {
+        self.values.get(id).and_then(|v| v.as_color())
+    }
+
+    /// Get the selected option index of an enum parameter.
+    pub fn get_enum(&self, id: &str) -> Option<usize> {
+        self.values.get(id).and_then(|v| v.as_enum())
+    }
 }
 
 /// Render a control panel for the given parameter definitions.
+///
+/// When `presets` is non-empty, a preset picker is shown above the controls.
 pub fn render_parameter_panel(
     ui: &mut egui::Ui,
     defs: &[ParameterDef],
+    presets: &[ParameterPreset],
     params: &mut SimulationParameters,
 ) {
+    if !presets.is_empty() {
+        let mut chosen = None;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("parameter_presets")
+                .selected_text(params.preset.unwrap_or("Custom"))
+                .show_ui(ui, |ui| {
+                    for preset in presets {
+                        if ui.selectable_label(params.preset == Some(preset.0), preset.0).clicked() {
+                            chosen = Some(preset);
+                        }
+                    }
+                });
+            ui.label("Preset");
+        });
+        if let Some(preset) = chosen {
+            params.apply_preset(preset);
+        }
+        ui.separator();
+    }
+
     for def in defs {
         match def {
             ParameterDef::Float {
                 id,
                 name,
+                description,
                 min,
                 max,
+                step,
                 unit,
                 ..
             } => {
                 if let Some(ParameterValue::Float(ref mut value)) = params.values.get_mut(id) {
-                    let label = if let Some(u) = unit {
-                        format!("{} ({})", name, u)
-                    } else {
-                        name.to_string()
-                    };
-                    ui.add(egui::Slider::new(value, *min..=*max).text(label));
+                    let unit = *unit;
+                    let mut slider = egui::Slider::new(value, *min..=*max)
+                        .text(*name)
+                        .custom_formatter(move |v, _| format_with_unit(v, unit))
+                        .custom_parser(move |text| parse_si(unit.and_then(|u| text.trim().strip_suffix(u)).unwrap_or(text)));
+                    if let Some(step) = step {
+                        slider = slider.step_by(*step as f64);
+                    }
+                    ui.add(slider).on_hover_text(*description);
                 }
             }
             ParameterDef::Int {
-                id, name, min, max, ..
+                id,
+                name,
+                description,
+                min,
+                max,
+                step,
+                logarithmic,
+                ..
             } => {
                 if let Some(ParameterValue::Int(ref mut value)) = params.values.get_mut(id) {
-                    ui.add(egui::Slider::new(value, *min..=*max).text(*name));
+                    let mut slider = egui::Slider::new(value, *min..=*max)
+                        .text(*name)
+                        .logarithmic(*logarithmic)
+                        .custom_formatter(|v, _| format_si(v))
+                        .custom_parser(parse_si);
+                    if let Some(step) = step {
+                        slider = slider.step_by(*step as f64);
+                    }
+                    ui.add(slider).on_hover_text(*description);
                 }
             }
             ParameterDef::Bool { id, name, .. } => {
@@ -94,9 +184,198 @@ pub fn render_parameter_panel(
                     ui.checkbox(value, *name);
                 }
             }
-            _ => {
-                // Other parameter types will be implemented as needed
+            ParameterDef::Vec2 { id, name, unit, .. } => {
+                if let Some(ParameterValue::Vec2(ref mut value)) = params.values.get_mut(id) {
+                    ui.horizontal(|ui| {
+                        ui.label(*name);
+                        vector_drag_values(ui, ["x", "y"].into_iter().zip(value.as_mut()), *unit);
+                    });
+                }
+            }
+            ParameterDef::Vec3 { id, name, unit, .. } => {
+                if let Some(ParameterValue::Vec3(ref mut value)) = params.values.get_mut(id) {
+                    ui.horizontal(|ui| {
+                        ui.label(*name);
+                        vector_drag_values(ui, ["x", "y", "z"].into_iter().zip(value.as_mut()), *unit);
+                    });
+                }
+            }
+            ParameterDef::Color { id, name, .. } => {
+                if let Some(ParameterValue::Color(ref mut value)) = params.values.get_mut(id) {
+                    ui.horizontal(|ui| {
+                        let mut rgba = value.to_srgba().to_f32_array();
+                        if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                            *value = Color::srgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+                        }
+                        ui.label(*name);
+                    });
+                }
             }
+            ParameterDef::Enum { id, name, options, .. } => {
+                if let Some(ParameterValue::Enum(ref mut index)) = params.values.get_mut(id) {
+                    let selected = options.get(*index).copied().unwrap_or("—");
+                    egui::ComboBox::from_id_source(*id)
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (i, option) in options.iter().enumerate() {
+                                ui.selectable_value(index, i, *option);
+                            }
+                        });
+                    ui.label(*name);
+                }
+            }
+        }
+    }
+}
+
+/// Adds one labeled drag-value per vector component.
+fn vector_drag_values<'a>(
+    ui: &mut egui::Ui,
+    components: impl Iterator<Item = (&'static str, &'a mut f32)>,
+    unit: Option<&'static str>,
+) {
+    for (axis, component) in components {
+        let mut drag = egui::DragValue::new(component).speed(0.1).prefix(format!("{}: ", axis));
+        if let Some(u) = unit {
+            drag = drag.suffix(format!(" {}", u));
+        }
+        ui.add(drag);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_of_each() -> Vec<ParameterDef> {
+        vec![
+            ParameterDef::Float {
+                id: "float",
+                name: "Float",
+                description: "",
+                min: 0.0,
+                max: 10.0,
+                default: 2.5,
+                step: None,
+                unit: None,
+            },
+            ParameterDef::Int {
+                id: "int",
+                name: "Int",
+                description: "",
+                min: 0,
+                max: 10,
+                default: 7,
+                step: None,
+                logarithmic: false,
+            },
+            ParameterDef::Bool {
+                id: "bool",
+                name: "Bool",
+                description: "",
+                default: true,
+            },
+            ParameterDef::Vec2 {
+                id: "vec2",
+                name: "Vec2",
+                description: "",
+                default: [4.0, -5.0],
+                unit: None,
+            },
+            ParameterDef::Vec3 {
+                id: "vec3",
+                name: "Vec3",
+                description: "",
+                default: [1.0, -2.0, 3.0],
+                unit: Some("m"),
+            },
+            ParameterDef::Color {
+                id: "color",
+                name: "Color",
+                description: "",
+                default: [0.25, 0.5, 0.75, 1.0],
+            },
+            ParameterDef::Enum {
+                id: "enum",
+                name: "Enum",
+                description: "",
+                options: &["A", "B", "C"],
+                default_index: 2,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_values_round_trip_through_panel() {
+        let defs = one_of_each();
+        let mut params = SimulationParameters::from_defs(&defs);
+
+        // Rendering without input must leave every value untouched.
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                render_parameter_panel(ui, &defs, &[], &mut params);
+            });
+        });
+
+        assert_eq!(params.values.len(), defs.len());
+        assert_eq!(params.get_float("float"), Some(2.5));
+        assert_eq!(params.values["int"].as_int(), Some(7));
+        assert_eq!(params.get_bool("bool"), Some(true));
+        assert_eq!(params.get_vec2("vec2"), Some(Vec2::new(4.0, -5.0)));
+        assert_eq!(params.get_vec3("vec3"), Some(Vec3::new(1.0, -2.0, 3.0)));
+        assert_eq!(
+            params.get_color("color").map(|c| c.to_srgba().to_f32_array()),
+            Some([0.25, 0.5, 0.75, 1.0])
+        );
+        assert_eq!(params.get_enum("enum"), Some(2));
+    }
+
+    #[test]
+    fn test_apply_preset_overrides_known_ids() {
+        let defs = one_of_each();
+        let mut params = SimulationParameters::from_defs(&defs);
+        let preset: ParameterPreset = (
+            "Test",
+            vec![
+                ("float", ParameterValue::Float(9.0)),
+                ("bool", ParameterValue::Bool(false)),
+                ("enum", ParameterValue::Enum(0)),
+                ("missing", ParameterValue::Float(1.0)),
+            ],
+        );
+
+        params.apply_preset(&preset);
+
+        assert_eq!(params.preset, Some("Test"));
+        assert_eq!(params.get_float("float"), Some(9.0));
+        assert_eq!(params.get_bool("bool"), Some(false));
+        assert_eq!(params.get_enum("enum"), Some(0));
+        assert_eq!(params.values["int"].as_int(), Some(7));
+        assert!(!params.values.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_every_value_type_survives_json() {
+        let defs = one_of_each();
+        let mut saved = SimulationParameters::from_defs(&defs);
+        saved.values.insert("float", ParameterValue::Float(-1.5));
+        saved.values.insert("int", ParameterValue::Int(3));
+        saved.values.insert("bool", ParameterValue::Bool(false));
+        saved.values.insert("vec2", ParameterValue::Vec2(Vec2::new(0.5, 6.0)));
+        saved.values.insert("vec3", ParameterValue::Vec3(Vec3::new(7.0, 8.0, -9.0)));
+        saved.values.insert("color", ParameterValue::Color(Color::srgba(1.0, 0.0, 0.5, 0.25)));
+        saved.values.insert("enum", ParameterValue::Enum(1));
+
+        let mut restored = SimulationParameters::from_defs(&defs);
+        assert_eq!(restored.restore_json(&saved.to_json()), defs.len());
+
+        for def in &defs {
+            assert_eq!(restored.values[def.id()], saved.values[def.id()], "{}", def.id());
         }
+        // Mismatched types and malformed input leave values alone.
+        assert_eq!(restored.restore_json(r#"{"float":{"Bool":true}}"#), 0);
+        assert_eq!(restored.restore_json("not json"), 0);
+        assert_eq!(restored.get_float("float"), Some(-1.5));
     }
 }
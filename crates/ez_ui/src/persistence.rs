@@ -0,0 +1,162 @@
+//! Saving parameter values between sessions.
+//!
+//! Web builds keep values in `localStorage`; native builds write a JSON file
+//! per simulation under the user's config directory. Either way the entry is
+//! keyed by simulation id.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use ez_core::parameters::{ParameterDef, ParameterValue};
+use serde::{Deserialize, Serialize};
+
+use crate::panels::SimulationParameters;
+
+/// Prefix of every `localStorage` key, followed by the simulation id.
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY_PREFIX: &str = "entropy_zero.parameters.";
+
+/// Serialized form of a [`ParameterValue`].
+#[derive(Serialize, Deserialize)]
+enum StoredValue {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    /// sRGBA components.
+    Color([f32; 4]),
+    Enum(usize),
+}
+
+impl From<&ParameterValue> for StoredValue {
+    fn from(value: &ParameterValue) -> Self {
+        match value {
+            ParameterValue::Float(v) => Self::Float(*v),
+            ParameterValue::Int(v) => Self::Int(*v),
+            ParameterValue::Bool(v) => Self::Bool(*v),
+            ParameterValue::Vec2(v) => Self::Vec2(v.to_array()),
+            ParameterValue::Vec3(v) => Self::Vec3(v.to_array()),
+            ParameterValue::Color(v) => Self::Color(v.to_srgba().to_f32_array()),
+            ParameterValue::Enum(v) => Self::Enum(*v),
+        }
+    }
+}
+
+impl From<StoredValue> for ParameterValue {
+    fn from(value: StoredValue) -> Self {
+        match value {
+            StoredValue::Float(v) => Self::Float(v),
+            StoredValue::Int(v) => Self::Int(v),
+            StoredValue::Bool(v) => Self::Bool(v),
+            StoredValue::Vec2(v) => Self::Vec2(Vec2::from_array(v)),
+            StoredValue::Vec3(v) => Self::Vec3(Vec3::from_array(v)),
+            StoredValue::Color([r, g, b, a]) => Self::Color(Color::srgba(r, g, b, a)),
+            StoredValue::Enum(v) => Self::Enum(v),
+        }
+    }
+}
+
+impl SimulationParameters {
+    /// Defaults from `defs`, overwritten by any values saved for `simulation_id`.
+    pub fn load(simulation_id: &str, defs: &[ParameterDef]) -> Self {
+        let mut params = Self::from_defs(defs);
+        if let Some(json) = read_entry(simulation_id) {
+            params.restore_json(&json);
+        }
+        params
+    }
+
+    /// Saves the current values under `simulation_id`.
+    pub fn save(&self, simulation_id: &str) {
+        write_entry(simulation_id, &self.to_json());
+    }
+
+    /// Serializes every value as a JSON object keyed by parameter id.
+    pub fn to_json(&self) -> String {
+        let stored: HashMap<&str, StoredValue> =
+            self.values.iter().map(|(id, value)| (*id, StoredValue::from(value))).collect();
+        serde_json::to_string(&stored).expect("parameter values serialize to JSON")
+    }
+
+    /// Overwrites values from JSON written by [`Self::to_json`].
+    ///
+    /// Unknown ids and values whose type no longer matches the definition
+    /// are skipped. Returns how many values were restored.
+    pub fn restore_json(&mut self, json: &str) -> usize {
+        let stored: HashMap<String, StoredValue> = match serde_json::from_str(json) {
+            Ok(stored) => stored,
+            Err(err) => {
+                warn!("Ignoring saved parameters: {}", err);
+                return 0;
+            }
+        };
+
+        let mut restored = 0;
+        for (id, value) in stored {
+            let value = ParameterValue::from(value);
+            if let Some(current) = self.values.get_mut(id.as_str()) {
+                if std::mem::discriminant(current) == std::mem::discriminant(&value) {
+                    *current = value;
+                    restored += 1;
+                }
+            }
+        }
+        restored
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Storage
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_entry(simulation_id: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{STORAGE_KEY_PREFIX}{simulation_id}")).ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_entry(simulation_id: &str, json: &str) {
+    let key = format!("{STORAGE_KEY_PREFIX}{simulation_id}");
+    if let Some(Err(err)) = local_storage().map(|storage| storage.set_item(&key, json)) {
+        warn!("Failed to save parameters: {:?}", err);
+    }
+}
+
+/// `<config dir>/entropy_zero/parameters/<simulation id>.json`, using
+/// `XDG_CONFIG_HOME`, then `~/.config`, then `%APPDATA%`.
+#[cfg(not(target_arch = "wasm32"))]
+fn entry_path(simulation_id: &str) -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(config_dir.join("entropy_zero").join("parameters").join(format!("{simulation_id}.json")))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_entry(simulation_id: &str) -> Option<String> {
+    std::fs::read_to_string(entry_path(simulation_id)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_entry(simulation_id: &str, json: &str) {
+    let Some(path) = entry_path(simulation_id) else {
+        warn!("No config directory; parameters for {} are not saved", simulation_id);
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, json));
+    if let Err(err) = result {
+        warn!("Failed to save parameters to {}: {}", path.display(), err);
+    }
+}
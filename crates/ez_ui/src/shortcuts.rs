@@ -0,0 +1,64 @@
+//! Keyboard shortcut help overlay.
+//!
+//! Every simulation lists its key bindings with [`shortcuts_overlay`], and
+//! [`SHORTCUTS_HELP_KEY`] (F1) toggles the overlay everywhere.
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+/// Key that toggles the shortcut overlay in every simulation.
+pub const SHORTCUTS_HELP_KEY: KeyCode = KeyCode::F1;
+
+/// Plugin that tracks whether the shortcut overlay is open.
+pub struct ShortcutsOverlayPlugin;
+
+impl Plugin for ShortcutsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShortcutsOverlay>()
+            .add_systems(Update, toggle_shortcuts_overlay);
+    }
+}
+
+/// Whether the shortcut overlay is showing.
+#[derive(Resource, Default)]
+pub struct ShortcutsOverlay {
+    pub open: bool,
+}
+
+fn toggle_shortcuts_overlay(keyboard: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<ShortcutsOverlay>) {
+    if keyboard.just_pressed(SHORTCUTS_HELP_KEY) {
+        overlay.open = !overlay.open;
+    }
+}
+
+/// Shows a centered window listing `(key, action)` pairs while `open`.
+///
+/// The window ignores the pointer, so it never takes focus or clicks from
+/// the panels underneath; it is closed with [`SHORTCUTS_HELP_KEY`].
+pub fn shortcuts_overlay(ctx: &egui::Context, open: bool, shortcuts: &[(&str, &str)]) {
+    if !open {
+        return;
+    }
+
+    egui::Window::new("⌨ Keyboard Shortcuts")
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .interactable(false)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts_overlay_grid")
+                .num_columns(2)
+                .spacing([24.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for (key, action) in shortcuts {
+                        ui.strong(*key);
+                        ui.label(*action);
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.small("Press F1 to close");
+        });
+}
@@ -1,6 +1,23 @@
 //! Common UI widgets.
 
 use bevy_egui::egui;
+use ez_core::quality::AdaptiveQuality;
+use ez_core::recording::DataRecorder;
+use ez_core::traits::SimulationMetadata;
+use std::ops::RangeInclusive;
+
+/// Height of a [`line_plot`] or [`scatter_plot`] in points.
+pub const PLOT_HEIGHT: f32 = 60.0;
+
+/// Colors given to plot series that don't pick their own, in order.
+pub const PLOT_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(90, 170, 255),
+    egui::Color32::from_rgb(255, 140, 60),
+    egui::Color32::from_rgb(120, 220, 120),
+    egui::Color32::from_rgb(240, 90, 110),
+    egui::Color32::from_rgb(200, 140, 255),
+    egui::Color32::from_rgb(240, 220, 90),
+];
 
 /// A styled play/pause button.
 pub fn play_pause_button(ui: &mut egui::Ui, paused: &mut bool) -> bool {
@@ -23,7 +40,221 @@ pub fn fps_display(ui: &mut egui::Ui, fps: f32) {
     ui.label(format!("FPS: {:.0}", fps));
 }
 
+/// Toggle and target frame rate for [`AdaptiveQuality`], with the current
+/// level and average frame time.
+pub fn adaptive_quality_controls(ui: &mut egui::Ui, quality: &mut AdaptiveQuality) {
+    ui.checkbox(&mut quality.enabled, "Adaptive Quality")
+        .on_hover_text("Do less work per frame when frames run slow, and more again when they speed up");
+    if quality.enabled {
+        ui.add(egui::Slider::new(&mut quality.target_fps, 20.0..=120.0).step_by(5.0).text("Target FPS"));
+        let average = quality.average_frame_time().map_or(String::from("–"), |time| format!("{:.1} ms", time * 1000.0));
+        ui.small(format!("Quality {:.0}% · frame {}", quality.level() * 100.0, average));
+    }
+}
+
+/// Lists a [`DataRecorder`]'s channels with a checkbox enabling each, its
+/// latest value and sample count. Returns true when "Export CSV" is clicked.
+pub fn data_recorder_panel(ui: &mut egui::Ui, recorder: &mut DataRecorder) -> bool {
+    if recorder.channels().is_empty() {
+        ui.small("Nothing recorded yet");
+        return false;
+    }
+    for channel in recorder.channels_mut() {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut channel.enabled, &channel.name).on_hover_text("Keep recording this channel");
+            if let Some(latest) = channel.latest() {
+                ui.label(format!("{latest:.4}"));
+            }
+            ui.small(format!("{} samples", channel.len()));
+        });
+    }
+    let mut export = false;
+    ui.horizontal(|ui| {
+        export = ui.button("📄 Export CSV").on_hover_text("Save every channel as a CSV column against time").clicked();
+        if ui.button("🗑 Clear").clicked() {
+            recorder.clear();
+        }
+    });
+    export
+}
+
 /// Display entity/particle count.
 pub fn entity_count_display(ui: &mut egui::Ui, count: usize, label: &str) {
     ui.label(format!("{}: {}", label, count));
 }
+
+/// Collapsible "About this simulation" section with its description,
+/// attribution, and references.
+pub fn about_simulation(ui: &mut egui::Ui, meta: &SimulationMetadata) {
+    egui::CollapsingHeader::new("About this simulation")
+        .id_source(("about_simulation", meta.id))
+        .show(ui, |ui| {
+            ui.label(meta.description);
+            let byline = match (meta.author, meta.year) {
+                (Some(author), Some(year)) => Some(format!("By {} ({})", author, year)),
+                (Some(author), None) => Some(format!("By {}", author)),
+                (None, Some(year)) => Some(format!("Written {}", year)),
+                (None, None) => None,
+            };
+            if let Some(byline) = byline {
+                ui.small(byline);
+            }
+            if !meta.references.is_empty() {
+                ui.separator();
+                ui.label("References:");
+                for reference in &meta.references {
+                    ui.small(format!("• {}", reference));
+                }
+            }
+        });
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Plots
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// One labeled line (or set of points) in a plot, sampled at evenly spaced x.
+#[derive(Debug, Clone, Copy)]
+pub struct Series<'a> {
+    pub label: &'a str,
+    pub values: &'a [f32],
+    /// Falls back to [`PLOT_COLORS`] by position when `None`.
+    pub color: Option<egui::Color32>,
+}
+
+impl<'a> Series<'a> {
+    pub fn new(label: &'a str, values: &'a [f32]) -> Self {
+        Self { label, values, color: None }
+    }
+
+    pub fn color(mut self, color: egui::Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl<'a> From<(&'a str, &'a [f32])> for Series<'a> {
+    fn from((label, values): (&'a str, &'a [f32])) -> Self {
+        Self::new(label, values)
+    }
+}
+
+/// Draws each series as a line, with a legend underneath.
+///
+/// Pass a `y_range` to fix the vertical axis, or `None` to fit it to the
+/// data. Series are stretched across the full width, so plot equal-length
+/// histories to keep them aligned.
+///
+/// ```
+/// use bevy_egui::egui;
+/// use ez_ui::widgets::{line_plot, Series};
+///
+/// fn energy_panel(ui: &mut egui::Ui, kinetic: &[f32], potential: &[f32]) {
+///     line_plot(ui, &[("Kinetic", kinetic).into(), Series::new("Potential", potential)], None);
+/// }
+/// ```
+pub fn line_plot(ui: &mut egui::Ui, series: &[Series], y_range: Option<RangeInclusive<f32>>) -> egui::Response {
+    plot(ui, series, y_range, |painter, points, color| {
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+    })
+}
+
+/// Like [`line_plot`], but draws each sample as a dot.
+pub fn scatter_plot(ui: &mut egui::Ui, series: &[Series], y_range: Option<RangeInclusive<f32>>) -> egui::Response {
+    plot(ui, series, y_range, |painter, points, color| {
+        for point in points {
+            painter.circle_filled(point, 1.5, color);
+        }
+    })
+}
+
+/// Vertical extent of the data, padded by 5%. A flat series gets a unit
+/// range around its value, and no data gets −1..=1.
+pub fn auto_y_range(series: &[Series]) -> RangeInclusive<f32> {
+    let values = series.iter().flat_map(|series| series.values.iter().copied()).filter(|v| v.is_finite());
+    let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+    if min > max {
+        return -1.0..=1.0;
+    }
+    if max <= min {
+        return min - 0.5..=max + 0.5;
+    }
+    let padding = (max - min) * 0.05;
+    min - padding..=max + padding
+}
+
+/// `range`, or a unit range around its middle if its ends are equal or
+/// reversed, so every value maps to a finite height.
+fn nonempty_y_range(range: RangeInclusive<f32>) -> RangeInclusive<f32> {
+    let (start, end) = range.into_inner();
+    if end > start {
+        start..=end
+    } else {
+        let middle = (start + end) / 2.0;
+        middle - 0.5..=middle + 0.5
+    }
+}
+
+fn plot(
+    ui: &mut egui::Ui,
+    series: &[Series],
+    y_range: Option<RangeInclusive<f32>>,
+    draw: impl Fn(&egui::Painter, Vec<egui::Pos2>, egui::Color32),
+) -> egui::Response {
+    let y_range = nonempty_y_range(y_range.unwrap_or_else(|| auto_y_range(series)));
+    let (y_min, y_max) = (*y_range.start(), *y_range.end());
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), PLOT_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let to_y = |value: f32| rect.bottom() - (value - y_min) / (y_max - y_min) * rect.height();
+    if y_min < 0.0 && y_max > 0.0 {
+        painter.hline(rect.x_range(), to_y(0.0), egui::Stroke::new(1.0, egui::Color32::from_gray(60)));
+    }
+
+    let color_of = |index: usize, series: &Series| series.color.unwrap_or(PLOT_COLORS[index % PLOT_COLORS.len()]);
+    for (index, series) in series.iter().enumerate() {
+        let last = series.values.len().saturating_sub(1).max(1) as f32;
+        let points = series
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_finite())
+            .map(|(i, &value)| egui::pos2(rect.left() + i as f32 / last * rect.width(), to_y(value.clamp(y_min, y_max))))
+            .collect();
+        draw(&painter, points, color_of(index, series));
+    }
+
+    let text = egui::Color32::from_gray(140);
+    painter.text(rect.left_top() + egui::vec2(2.0, 1.0), egui::Align2::LEFT_TOP, format!("{y_max:.2}"), egui::FontId::monospace(9.0), text);
+    painter.text(rect.left_bottom() + egui::vec2(2.0, -1.0), egui::Align2::LEFT_BOTTOM, format!("{y_min:.2}"), egui::FontId::monospace(9.0), text);
+
+    if series.len() > 1 {
+        ui.horizontal_wrapped(|ui| {
+            for (index, series) in series.iter().enumerate() {
+                ui.colored_label(color_of(index, series), format!("— {}", series.label));
+            }
+        });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_y_range_pads_data_and_handles_flat_series() {
+        let wave = [-1.0, 0.5, 1.0];
+        let range = auto_y_range(&[Series::new("wave", &wave)]);
+        assert!((range.start() + 1.1).abs() < 1e-6 && (range.end() - 1.1).abs() < 1e-6);
+
+        let flat = [2.0, 2.0];
+        assert_eq!(auto_y_range(&[("flat", &flat[..]).into()]), 1.5..=2.5);
+        assert_eq!(auto_y_range(&[]), -1.0..=1.0);
+
+        // A fixed range with equal ends would divide by zero.
+        assert_eq!(nonempty_y_range(3.0..=3.0), 2.5..=3.5);
+        assert_eq!(nonempty_y_range(0.0..=1.0), 0.0..=1.0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Headless throughput benchmark for the CPU particle integrator.
+//!
+//! Runs the particle system's real update under `MinimalPlugins`, with no
+//! window or renderer, and reports how many steps and particle·steps it
+//! sustains per second. Like the simulation, counts above
+//! `INSTANCED_PARTICLE_THRESHOLD` run the flat `ParticleCloud` through
+//! `update_particle_cloud`; smaller ones run entities through
+//! `update_particles`.
+//!
+//! ```sh
+//! cargo run --release --bin bench_particles -- [particle_count] [steps]
+//! ```
+//!
+//! The counts can also be set with `BENCH_PARTICLES` and `BENCH_STEPS`.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use classical_mechanics::particle_system::{
+    update_particle_cloud, update_particles, ParticleCloud, ParticleConfig, SpawnShape, INSTANCED_PARTICLE_THRESHOLD,
+};
+
+const DEFAULT_PARTICLES: usize = 100_000;
+const DEFAULT_STEPS: usize = 1_000;
+/// Fixed simulated time per step, so every run integrates the same motion.
+const STEP_DT: f32 = 1.0 / 60.0;
+
+/// Reads the `index`th argument, falling back to `env_var`, then `default`.
+fn count_setting(index: usize, env_var: &str, default: usize) -> usize {
+    let value = std::env::args().nth(index).or_else(|| std::env::var(env_var).ok());
+    match value {
+        Some(text) => text
+            .replace('_', "")
+            .parse()
+            .unwrap_or_else(|_| panic!("{env_var}: expected a whole number, got {text:?}")),
+        None => default,
+    }
+}
+
+fn main() {
+    let particle_count = count_setting(1, "BENCH_PARTICLES", DEFAULT_PARTICLES);
+    let steps = count_setting(2, "BENCH_STEPS", DEFAULT_STEPS);
+
+    let config = ParticleConfig {
+        particle_count,
+        ..default()
+    };
+    let bounds = config.bounds;
+    let instanced = particle_count > INSTANCED_PARTICLE_THRESHOLD;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(STEP_DT)))
+        .insert_resource(config);
+
+    let mut rng = rand::thread_rng();
+    if instanced {
+        let mut cloud = ParticleCloud::default();
+        for _ in 0..particle_count {
+            let (position, particle) = SpawnShape::Box.sample(&mut rng, bounds);
+            cloud.push(position, particle);
+        }
+        app.insert_resource(cloud).add_systems(Update, update_particle_cloud);
+    } else {
+        let world = app.world_mut();
+        for _ in 0..particle_count {
+            let (position, particle) = SpawnShape::Box.sample(&mut rng, bounds);
+            world.spawn((Transform::from_translation(position), particle));
+        }
+        app.add_systems(Update, update_particles);
+    }
+
+    app.finish();
+    app.cleanup();
+
+    // Warm up: the first update initializes time and the task pools.
+    app.update();
+
+    let start = Instant::now();
+    for _ in 0..steps {
+        app.update();
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let steps_per_second = steps as f64 / elapsed;
+    println!("particles:               {particle_count}");
+    if instanced {
+        println!("path:                    instanced cloud (update_particle_cloud)");
+    } else {
+        println!("path:                    entities (update_particles)");
+    }
+    println!("steps:                   {steps}");
+    println!("elapsed:                 {elapsed:.3} s");
+    println!("steps/sec:               {steps_per_second:.1}");
+    println!("particle·steps/sec:      {:.3e}", steps_per_second * particle_count as f64);
+}
@@ -7,6 +7,7 @@
 //! - Pendulums (simple, double, chaotic)
 //! - Spring-mass systems
 
+pub mod particle_gpu;
 pub mod particle_system;
 
 use bevy::prelude::*;
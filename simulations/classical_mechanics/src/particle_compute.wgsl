@@ -0,0 +1,54 @@
+// Particle integration: gravity + gravity well + linear drag + box bounce,
+// one invocation per particle.
+
+struct Particle {
+    // xyz position, w mass
+    position: vec4<f32>,
+    velocity: vec4<f32>,
+}
+
+struct Params {
+    gravity: vec3<f32>,
+    bounds: f32,
+    dt: f32,
+    count: u32,
+    drag: f32,
+    restitution: f32,
+    friction: f32,
+    well: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= params.count {
+        return;
+    }
+
+    let mass = particles[i].position.w;
+    var x = particles[i].position.xyz;
+
+    // Point-mass well at the origin; distance clamped like gravitational_force.
+    let r2 = max(dot(x, x), 0.01);
+    let g = params.gravity - params.well * x / (r2 * sqrt(dot(x, x) + 1e-12));
+
+    // Implicit drag, matching the CPU path: v' = (v + g·dt) / (1 + drag·dt/m)
+    var v = (particles[i].velocity.xyz + g * params.dt) / (1.0 + params.drag * params.dt / mass);
+    x += v * params.dt;
+    let b = params.bounds;
+
+    // Restitution scales the normal component, friction the tangential ones.
+    for (var axis = 0; axis < 3; axis++) {
+        if abs(x[axis]) > b {
+            x[axis] = sign(x[axis]) * b;
+            let normal = v[axis];
+            v *= 1.0 - params.friction;
+            v[axis] = -normal * params.restitution;
+        }
+    }
+
+    particles[i] = Particle(vec4<f32>(x, mass), vec4<f32>(v, 0.0));
+}
@@ -0,0 +1,454 @@
+//! GPU compute path for particle integration.
+//!
+//! With [`ParticleConfig::gpu`] set, particle state is uploaded once to a
+//! storage buffer and integrated by a WGSL compute shader every frame. The
+//! buffer is copied to a staging buffer and mapped asynchronously, and the
+//! results are written back to particle transforms (or the instanced
+//! [`ParticleCloud`]) for rendering, a frame or two behind the GPU.
+//! Platforms without compute support (WebGL2) keep the CPU path.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::{
+    extract_resource::ExtractResourcePlugin,
+    render_graph::{self, RenderGraph, RenderLabel},
+    render_resource::{
+        binding_types::{storage_buffer, uniform_buffer},
+        *,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    Render, RenderApp, RenderSet,
+};
+
+use crate::particle_system::{Particle, ParticleCloud, ParticleConfig};
+
+const PARTICLE_COMPUTE_SHADER: Handle<Shader> = Handle::weak_from_u128(0x5f3a_9c1e_22d4_4b7a_8e61_0c9d_7a43_e215);
+const WORKGROUP_SIZE: u32 = 64;
+
+// Readback states shared between the render graph node and the readback system.
+const READBACK_IDLE: u8 = 0;
+const READBACK_COPIED: u8 = 1;
+const READBACK_MAPPING: u8 = 2;
+const READBACK_MAPPED: u8 = 3;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Plugin
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Plugin adding the compute-shader particle integration path.
+pub struct ParticleGpuPlugin;
+
+impl Plugin for ParticleGpuPlugin {
+    fn build(&self, app: &mut App) {
+        if app.get_sub_app(RenderApp).is_none() {
+            return;
+        }
+
+        load_internal_asset!(
+            app,
+            PARTICLE_COMPUTE_SHADER,
+            "particle_compute.wgsl",
+            Shader::from_wgsl
+        );
+
+        let bridge = GpuParticleBridge::default();
+        app.insert_resource(bridge.clone())
+            .init_resource::<GpuParticleParams>()
+            .init_resource::<GpuParticleEntities>()
+            .add_plugins(ExtractResourcePlugin::<GpuParticleParams>::default())
+            .add_systems(Update, sync_gpu_particles);
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app.insert_resource(bridge).add_systems(
+            Render,
+            (
+                prepare_particle_buffers.in_set(RenderSet::PrepareResources),
+                read_back_particles.after(RenderSet::Render),
+            ),
+        );
+
+        let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
+        render_graph.add_node(ParticleComputeLabel, ParticleComputeNode);
+        render_graph.add_node_edge(ParticleComputeLabel, bevy::render::graph::CameraDriverLabel);
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else { return };
+
+        // WebGL2 reports zero compute workgroups.
+        let supported = render_app
+            .world()
+            .resource::<RenderDevice>()
+            .limits()
+            .max_compute_workgroups_per_dimension
+            > 0;
+
+        if supported {
+            render_app
+                .init_resource::<ParticleComputePipeline>()
+                .init_resource::<ParticleGpuBuffers>();
+        }
+        app.insert_resource(GpuComputeSupport(supported));
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Shared Data
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Whether the render device can run compute shaders.
+#[derive(Resource, Clone, Copy)]
+pub struct GpuComputeSupport(pub bool);
+
+pub use shader_types::*;
+
+// The `ShaderType` derive emits layout checks that trip `dead_code`.
+mod shader_types {
+    #![allow(dead_code)]
+
+    use bevy::prelude::*;
+    use bevy::render::{extract_resource::ExtractResource, render_resource::ShaderType};
+
+    /// Per-particle state as laid out in the storage buffer.
+    #[derive(ShaderType, Clone, Copy, Default)]
+    pub struct GpuParticle {
+        /// Position in xyz, mass in w.
+        pub position: Vec4,
+        pub velocity: Vec4,
+    }
+
+    impl GpuParticle {
+        pub(super) const FLOATS: usize = 8;
+
+        pub(super) fn from_floats(floats: &[f32]) -> Self {
+            Self {
+                position: Vec4::from_slice(&floats[0..4]),
+                velocity: Vec4::from_slice(&floats[4..8]),
+            }
+        }
+    }
+
+    /// Uniform parameters for one integration step.
+    #[derive(Resource, ShaderType, ExtractResource, Clone, Copy, Default)]
+    pub struct GpuParticleParams {
+        pub gravity: Vec3,
+        pub bounds: f32,
+        pub dt: f32,
+        pub count: u32,
+        pub drag: f32,
+        pub restitution: f32,
+        pub friction: f32,
+        /// G·M of the gravity well at the origin, 0 when off.
+        pub well: f32,
+    }
+}
+
+/// Hands particle data between the main and render worlds.
+#[derive(Resource, Clone, Default)]
+struct GpuParticleBridge {
+    upload: Arc<Mutex<Option<Vec<GpuParticle>>>>,
+    readback: Arc<Mutex<Option<Vec<GpuParticle>>>>,
+}
+
+/// Entities in storage buffer order; empty when the instanced cloud is uploaded.
+#[derive(Resource, Default)]
+struct GpuParticleEntities(Vec<Entity>);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Main World
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[allow(clippy::too_many_arguments)]
+fn sync_gpu_particles(
+    mut config: ResMut<ParticleConfig>,
+    support: Option<Res<GpuComputeSupport>>,
+    bridge: Res<GpuParticleBridge>,
+    mut entities: ResMut<GpuParticleEntities>,
+    mut params: ResMut<GpuParticleParams>,
+    mut particles: Query<(Entity, &mut Transform, &mut Particle)>,
+    mut cloud: ResMut<ParticleCloud>,
+    time: Res<Time>,
+) {
+    if config.gpu && !support.is_some_and(|support| support.0) {
+        warn!("Compute shaders are unavailable, using CPU particle integration");
+        config.gpu = false;
+    }
+
+    if !config.gpu {
+        entities.0.clear();
+        params.count = 0;
+        return;
+    }
+
+    // Upload on enabling the GPU path, or after the scene was respawned.
+    let count = if cloud.is_empty() { particles.iter().len() } else { cloud.len() };
+    if params.count as usize != count {
+        let data = if cloud.is_empty() {
+            let (ids, data) = particles
+                .iter()
+                .map(|(entity, transform, particle)| {
+                    let gpu_particle = GpuParticle {
+                        position: transform.translation.extend(particle.mass),
+                        velocity: particle.velocity.extend(0.0),
+                    };
+                    (entity, gpu_particle)
+                })
+                .unzip();
+            entities.0 = ids;
+            data
+        } else {
+            entities.0.clear();
+            cloud
+                .positions
+                .iter()
+                .zip(&cloud.velocities)
+                .zip(&cloud.masses)
+                .map(|((position, velocity), mass)| GpuParticle {
+                    position: position.extend(*mass),
+                    velocity: velocity.extend(0.0),
+                })
+                .collect()
+        };
+        *bridge.upload.lock().expect("particle upload lock poisoned") = Some(data);
+    }
+
+    *params = GpuParticleParams {
+        gravity: config.gravity,
+        bounds: config.bounds,
+        dt: if config.paused { 0.0 } else { time.delta_seconds() * config.speed_multiplier },
+        count: count as u32,
+        drag: config.drag,
+        restitution: config.restitution,
+        friction: config.friction,
+        well: if config.gravity_well { config.well_strength } else { 0.0 },
+    };
+
+    let readback = bridge.readback.lock().expect("particle readback lock poisoned").take();
+    if let Some(readback) = readback {
+        if !cloud.is_empty() {
+            let ParticleCloud { positions, velocities, .. } = &mut *cloud;
+            for ((position, velocity), gpu_particle) in positions.iter_mut().zip(velocities).zip(readback) {
+                *position = gpu_particle.position.truncate();
+                *velocity = gpu_particle.velocity.truncate();
+            }
+            return;
+        }
+        for (entity, gpu_particle) in entities.0.iter().zip(readback) {
+            if let Ok((_, mut transform, mut particle)) = particles.get_mut(*entity) {
+                transform.translation = gpu_particle.position.truncate();
+                particle.velocity = gpu_particle.velocity.truncate();
+            }
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Render World
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Resource)]
+struct ParticleComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for ParticleComputePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "particle_compute_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    uniform_buffer::<GpuParticleParams>(false),
+                    storage_buffer::<Vec<GpuParticle>>(false),
+                ),
+            ),
+        );
+        let pipeline = world
+            .resource::<PipelineCache>()
+            .queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("particle_compute_pipeline".into()),
+                layout: vec![layout.clone()],
+                push_constant_ranges: Vec::new(),
+                shader: PARTICLE_COMPUTE_SHADER,
+                shader_defs: Vec::new(),
+                entry_point: "main".into(),
+            });
+        Self { layout, pipeline }
+    }
+}
+
+#[derive(Resource)]
+struct ParticleGpuBuffers {
+    params: UniformBuffer<GpuParticleParams>,
+    particles: StorageBuffer<Vec<GpuParticle>>,
+    staging: Option<Buffer>,
+    bind_group: Option<BindGroup>,
+    count: u32,
+    readback_state: Arc<AtomicU8>,
+}
+
+impl FromWorld for ParticleGpuBuffers {
+    fn from_world(_world: &mut World) -> Self {
+        let mut particles = StorageBuffer::default();
+        particles.set_label(Some("particle_storage_buffer"));
+        particles.add_usages(BufferUsages::COPY_SRC);
+        Self {
+            params: UniformBuffer::default(),
+            particles,
+            staging: None,
+            bind_group: None,
+            count: 0,
+            readback_state: Arc::new(AtomicU8::new(READBACK_IDLE)),
+        }
+    }
+}
+
+fn prepare_particle_buffers(
+    params: Res<GpuParticleParams>,
+    bridge: Res<GpuParticleBridge>,
+    pipeline: Option<Res<ParticleComputePipeline>>,
+    buffers: Option<ResMut<ParticleGpuBuffers>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let (Some(pipeline), Some(mut buffers)) = (pipeline, buffers) else { return };
+
+    buffers.params.set(*params);
+    buffers.params.write_buffer(&render_device, &render_queue);
+
+    let upload = bridge.upload.lock().expect("particle upload lock poisoned").take();
+    if let Some(data) = upload {
+        // A pending map on the old staging buffer is abandoned with it.
+        buffers.readback_state.store(READBACK_IDLE, Ordering::Release);
+        buffers.count = data.len() as u32;
+        buffers.particles.set(data);
+        buffers.particles.write_buffer(&render_device, &render_queue);
+
+        let size = buffers.particles.buffer().map_or(0, |buffer| buffer.size());
+        buffers.staging = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("particle_staging_buffer"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        buffers.bind_group = None;
+    }
+
+    if buffers.bind_group.is_none() && buffers.count > 0 {
+        let bind_group = render_device.create_bind_group(
+            "particle_compute_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::sequential((&buffers.params, &buffers.particles)),
+        );
+        buffers.bind_group = Some(bind_group);
+    }
+}
+
+fn read_back_particles(
+    buffers: Option<Res<ParticleGpuBuffers>>,
+    bridge: Res<GpuParticleBridge>,
+    render_device: Res<RenderDevice>,
+) {
+    let Some(buffers) = buffers else { return };
+    let Some(staging) = &buffers.staging else { return };
+    let state = &buffers.readback_state;
+
+    match state.load(Ordering::Acquire) {
+        READBACK_COPIED => {
+            state.store(READBACK_MAPPING, Ordering::Release);
+            let callback_state = state.clone();
+            staging.slice(..).map_async(MapMode::Read, move |result| {
+                let next = if result.is_ok() { READBACK_MAPPED } else { READBACK_IDLE };
+                // Ignore results for a staging buffer replaced by a new upload.
+                let _ = callback_state.compare_exchange(
+                    READBACK_MAPPING,
+                    next,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+            });
+        }
+        READBACK_MAPPED => {
+            let data = {
+                let view = staging.slice(..).get_mapped_range();
+                view.chunks_exact(4)
+                    .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect::<Vec<_>>()
+                    .chunks_exact(GpuParticle::FLOATS)
+                    .take(buffers.count as usize)
+                    .map(GpuParticle::from_floats)
+                    .collect()
+            };
+            staging.unmap();
+            *bridge.readback.lock().expect("particle readback lock poisoned") = Some(data);
+            state.store(READBACK_IDLE, Ordering::Release);
+        }
+        _ => {}
+    }
+
+    // Drives map callbacks on native; browsers poll on their own.
+    render_device.poll(Maintain::Poll);
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ParticleComputeLabel;
+
+struct ParticleComputeNode;
+
+impl render_graph::Node for ParticleComputeNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let (Some(params), Some(pipeline), Some(buffers)) = (
+            world.get_resource::<GpuParticleParams>(),
+            world.get_resource::<ParticleComputePipeline>(),
+            world.get_resource::<ParticleGpuBuffers>(),
+        ) else {
+            return Ok(());
+        };
+        let Some(bind_group) = &buffers.bind_group else { return Ok(()) };
+        let Some(compute_pipeline) = world
+            .resource::<PipelineCache>()
+            .get_compute_pipeline(pipeline.pipeline)
+        else {
+            return Ok(());
+        };
+        if params.count == 0 || params.count != buffers.count {
+            return Ok(());
+        }
+
+        {
+            let mut pass = render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("particle_compute_pass"),
+                    ..default()
+                });
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.set_pipeline(compute_pipeline);
+            pass.dispatch_workgroups(params.count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+
+        // Only copy when the staging buffer is not mapped or being mapped.
+        if let (Some(particles), Some(staging)) = (buffers.particles.buffer(), &buffers.staging) {
+            if buffers
+                .readback_state
+                .compare_exchange(READBACK_IDLE, READBACK_COPIED, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                render_context
+                    .command_encoder()
+                    .copy_buffer_to_buffer(particles, 0, staging, 0, staging.size());
+            }
+        }
+
+        Ok(())
+    }
+}
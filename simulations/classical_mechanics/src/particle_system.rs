@@ -4,20 +4,55 @@
 //! boundary collisions, and parallel processing.
 
 use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use bevy::tasks::ComputeTaskPool;
 use bevy_egui::{egui, EguiContexts};
 use ez_core::prelude::*;
+use ez_physics::forces::{damping_force, gravitational_force};
+use ez_physics::integrators::{euler_integrate, semi_implicit_euler};
+use ez_renderer::axes::{hide_axes, AxesPlugin, ShowAxes};
+use ez_renderer::camera::{spawn_orbit_camera, CameraControllerPlugin, OrbitCamera};
+use ez_renderer::materials::viridis;
+use ez_ui::widgets::{line_plot, Series};
 use rand::Rng;
 
+use crate::particle_gpu::ParticleGpuPlugin;
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Simulation Definition
 // ══════════════════════════════════════════════════════════════════════════════
 
+/// Unique identifier of the particle system simulation.
+pub const SIMULATION_ID: &str = "particle_system";
+
+/// Above this many particles, state lives in [`ParticleCloud`] and renders as
+/// one point mesh instead of an entity per particle.
+pub const INSTANCED_PARTICLE_THRESHOLD: usize = 10_000;
+
+/// Most particles created or removed per frame while reaching
+/// [`ParticleConfig::particle_count`].
+pub const SPAWN_BATCH: usize = 50_000;
+
+/// Heaviest particle mass offered (kg); particle colors run from 1 kg to this.
+pub const MAX_PARTICLE_MASS: f32 = 10.0;
+
+/// Distinct mass colors, and so shared materials, for entity particles.
+const MASS_COLOR_BINS: usize = 8;
+
+/// Frames of total energy kept for the diagnostics plot.
+pub const ENERGY_HISTORY_LEN: usize = 300;
+
+/// Particles integrated per parallel task on the instanced path.
+const CLOUD_CHUNK_SIZE: usize = 4096;
+
 /// The particle system simulation metadata.
 pub struct ParticleSystemSimulation;
 
 impl Simulation for ParticleSystemSimulation {
     fn id(&self) -> &'static str {
-        "particle_system"
+        SIMULATION_ID
     }
 
     fn name(&self) -> &'static str {
@@ -42,6 +77,8 @@ impl Simulation for ParticleSystemSimulation {
                 min: 100,
                 max: 1_000_000,
                 default: 100_000,
+                step: Some(100),
+                logarithmic: true,
             },
             ParameterDef::Float {
                 id: "gravity",
@@ -63,6 +100,56 @@ impl Simulation for ParticleSystemSimulation {
                 step: Some(1.0),
                 unit: Some("m"),
             },
+            ParameterDef::Float {
+                id: "drag",
+                name: "Drag",
+                description: "Linear drag coefficient; terminal speed is m·|g| / drag",
+                min: 0.0,
+                max: 5.0,
+                default: 0.0,
+                step: Some(0.05),
+                unit: Some("kg/s"),
+            },
+            ParameterDef::Float {
+                id: "restitution",
+                name: "Restitution",
+                description: "Fraction of normal velocity kept on a wall bounce",
+                min: 0.0,
+                max: 1.0,
+                default: 0.8,
+                step: Some(0.05),
+                unit: None,
+            },
+            ParameterDef::Float {
+                id: "friction",
+                name: "Wall Friction",
+                description: "Fraction of tangential velocity lost on a wall bounce",
+                min: 0.0,
+                max: 1.0,
+                default: 0.0,
+                step: Some(0.05),
+                unit: None,
+            },
+            ParameterDef::Enum {
+                id: "integrator",
+                name: "Integrator",
+                description: "Time-stepping scheme; plain Euler gains energy every step, Verlet holds it steady",
+                options: &["Euler", "Semi-implicit Euler", "Verlet"],
+                default_index: 1,
+            },
+            ParameterDef::Enum {
+                id: "mass_distribution",
+                name: "Masses",
+                description: "How particle masses are drawn; mass only changes motion through drag",
+                options: &["Equal", "Spread", "Two Populations"],
+                default_index: 0,
+            },
+            ParameterDef::Bool {
+                id: "gravity_well",
+                name: "Gravity Well",
+                description: "Add a point mass at the center of the box",
+                default: false,
+            },
             ParameterDef::Float {
                 id: "speed",
                 name: "Speed Multiplier",
@@ -82,6 +169,15 @@ impl Simulation for ParticleSystemSimulation {
         ]
     }
 
+    fn recommended_defaults(&self, tier: PerformanceTier) -> Vec<(&'static str, ParameterValue)> {
+        let particle_count = match tier {
+            PerformanceTier::Low => 10_000,
+            PerformanceTier::Medium => 100_000,
+            PerformanceTier::High => 500_000,
+        };
+        vec![("particle_count", ParameterValue::Int(particle_count))]
+    }
+
     fn build_plugin(&self) -> Box<dyn Fn(&mut App) + Send + Sync> {
         Box::new(|app| {
             app.add_plugins(ParticleSystemPlugin);
@@ -102,46 +198,392 @@ impl Simulation for ParticleSystemSimulation {
 // ══════════════════════════════════════════════════════════════════════════════
 
 /// Velocity component for particles.
-#[derive(Component, Default, Reflect)]
+#[derive(Component, Reflect)]
 pub struct Particle {
     pub velocity: Vec3,
+    /// Acceleration used in the last step, for [`Integrator::Verlet`].
+    pub prev_acceleration: Vec3,
+    /// Mass in kg.
+    pub mass: f32,
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            prev_acceleration: Vec3::ZERO,
+            mass: 1.0,
+        }
+    }
 }
 
 /// Tag component for particle entities.
 #[derive(Component)]
 pub struct ParticleTag;
 
+/// Marks the point mesh that draws [`ParticleCloud`].
+#[derive(Component)]
+pub struct ParticleCloudMesh;
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Resources
 // ══════════════════════════════════════════════════════════════════════════════
 
+/// Initial arrangement of particle positions and velocities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpawnShape {
+    /// Uniform in a box with random velocities.
+    #[default]
+    Box,
+    /// Uniform in a ball, at rest.
+    Sphere,
+    /// A flat disk spinning about the vertical axis.
+    Disk,
+    /// A jet launched upward from the center of the floor.
+    Fountain,
+}
+
+impl SpawnShape {
+    pub const ALL: [Self; 4] = [Self::Box, Self::Sphere, Self::Disk, Self::Fountain];
+
+    /// Draws one particle's initial position and state inside `bounds`.
+    pub fn sample(self, rng: &mut impl Rng, bounds: f32) -> (Vec3, Particle) {
+        let (position, velocity) = match self {
+            Self::Box => (
+                Vec3::new(
+                    rng.gen_range(-bounds..bounds),
+                    rng.gen_range(0.0..bounds),
+                    rng.gen_range(-bounds..bounds),
+                ),
+                Vec3::new(
+                    rng.gen_range(-10.0..10.0),
+                    rng.gen_range(-5.0..15.0),
+                    rng.gen_range(-10.0..10.0),
+                ),
+            ),
+            Self::Sphere => {
+                let radius = 0.5 * bounds * rng.gen::<f32>().cbrt();
+                (random_direction(rng) * radius, Vec3::ZERO)
+            }
+            Self::Disk => {
+                let disk_radius = 0.8 * bounds;
+                let radius = disk_radius * rng.gen::<f32>().sqrt();
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let position = Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin());
+                // Rigid rotation, 10 m/s at the rim.
+                (position, Vec3::Y.cross(position) * 10.0 / disk_radius)
+            }
+            Self::Fountain => {
+                let position = Vec3::new(rng.gen_range(-1.0..1.0), -bounds, rng.gen_range(-1.0..1.0));
+                // Enough speed to reach roughly the middle of the box under default gravity.
+                let speed = rng.gen_range(0.8..1.2) * (2.0 * 9.8 * bounds).sqrt();
+                let spread = Vec3::new(rng.gen_range(-0.15..0.15), 1.0, rng.gen_range(-0.15..0.15));
+                (position, spread.normalize() * speed)
+            }
+        };
+        (position, Particle { velocity, ..default() })
+    }
+}
+
+/// How particle masses are drawn at spawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MassDistribution {
+    /// Every particle is 1 kg.
+    #[default]
+    Equal,
+    /// Uniform between 1 kg and the heavy mass.
+    Spread,
+    /// Half at 1 kg, half at the heavy mass, for mixing and settling demos.
+    TwoPopulations,
+}
+
+impl MassDistribution {
+    pub const ALL: [Self; 3] = [Self::Equal, Self::Spread, Self::TwoPopulations];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Equal => "Equal",
+            Self::Spread => "Spread",
+            Self::TwoPopulations => "Two Populations",
+        }
+    }
+
+    /// Lightest and heaviest masses drawn, in kg; equal for [`Self::Equal`].
+    pub fn mass_range(self, heavy: f32) -> (f32, f32) {
+        match self {
+            Self::Equal => (1.0, 1.0),
+            Self::Spread | Self::TwoPopulations => (1.0, heavy.max(1.0)),
+        }
+    }
+
+    /// Draws one mass in kg, with `heavy` the largest.
+    pub fn sample(self, rng: &mut impl Rng, heavy: f32) -> f32 {
+        match self {
+            Self::Equal => 1.0,
+            Self::Spread => rng.gen_range(1.0..=heavy.max(1.0)),
+            Self::TwoPopulations => if rng.gen_bool(0.5) { 1.0 } else { heavy },
+        }
+    }
+}
+
+/// Color for a mass, on a log scale from 1 kg to [`MAX_PARTICLE_MASS`].
+pub fn mass_color(mass: f32) -> Color {
+    viridis(mass_fraction(mass))
+}
+
+fn mass_fraction(mass: f32) -> f32 {
+    (mass.max(1.0).ln() / MAX_PARTICLE_MASS.ln()).clamp(0.0, 1.0)
+}
+
+/// Index of the shared material closest to a mass's color.
+fn mass_bin(mass: f32) -> usize {
+    (mass_fraction(mass) * (MASS_COLOR_BINS - 1) as f32).round() as usize
+}
+
+/// Time-stepping scheme for particles integrated on the CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Integrator {
+    /// Explicit Euler: position moves with the old velocity, so energy
+    /// grows a little every step.
+    Euler,
+    /// Velocity first, then position; energy drifts down slightly.
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet; exact under constant gravity.
+    Verlet,
+}
+
+impl Integrator {
+    pub const ALL: [Self; 3] = [Self::Euler, Self::SemiImplicitEuler, Self::Verlet];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Euler => "Euler",
+            Self::SemiImplicitEuler => "Semi-implicit Euler",
+            Self::Verlet => "Verlet",
+        }
+    }
+}
+
+/// Uniformly distributed unit vector.
+fn random_direction(rng: &mut impl Rng) -> Vec3 {
+    let u: f32 = rng.gen_range(-1.0..1.0);
+    let theta: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+    let r = (1.0 - u * u).sqrt();
+    Vec3::new(r * theta.cos(), u, r * theta.sin())
+}
+
 /// Configuration for the particle simulation.
 #[derive(Resource)]
 pub struct ParticleConfig {
     pub particle_count: usize,
+    pub spawn_shape: SpawnShape,
     pub gravity: Vec3,
+    /// Masses given to new particles; applies from the next respawn.
+    pub mass_distribution: MassDistribution,
+    /// Largest mass drawn by [`MassDistribution`] (kg).
+    pub heavy_mass: f32,
+    /// Pull particles toward a point mass at the center of the box.
+    pub gravity_well: bool,
+    /// G·M of the gravity well (m³/s²).
+    pub well_strength: f32,
     pub bounds: f32,
     pub speed_multiplier: f32,
+    /// Linear drag coefficient (kg/s); terminal speed is m·|g| / drag, so heavy
+    /// particles fall faster through it.
+    pub drag: f32,
+    /// Fraction of the normal velocity kept on a wall bounce (1 is elastic).
+    pub restitution: f32,
+    /// Fraction of the tangential velocity lost on a wall bounce.
+    pub friction: f32,
+    /// CPU time-stepping scheme; the GPU path always uses semi-implicit Euler.
+    pub integrator: Integrator,
     pub paused: bool,
+    /// Integrate on the GPU with a compute shader instead of the CPU.
+    pub gpu: bool,
+    /// Peak acceleration of the mouse attractor (m/s²).
+    pub attractor_strength: f32,
+    /// Distance at which the attractor's pull falls to zero (m).
+    pub attractor_radius: f32,
 }
 
 impl Default for ParticleConfig {
     fn default() -> Self {
         Self {
             particle_count: 100_000,
+            spawn_shape: SpawnShape::Box,
             gravity: Vec3::new(0.0, -9.8, 0.0),
+            mass_distribution: MassDistribution::default(),
+            heavy_mass: 5.0,
+            gravity_well: false,
+            well_strength: 20_000.0,
             bounds: 50.0,
             speed_multiplier: 1.0,
+            drag: 0.0,
+            restitution: 0.8,
+            friction: 0.0,
+            integrator: Integrator::default(),
             paused: false,
+            gpu: false,
+            attractor_strength: 60.0,
+            attractor_radius: 30.0,
         }
     }
 }
 
+impl ParticleConfig {
+    /// Sets the field for one of the parameters in
+    /// [`ParticleSystemSimulation::parameters`], ignoring unknown ids and
+    /// mismatched values.
+    pub fn apply_parameter(&mut self, id: &str, value: &ParameterValue) {
+        match (id, value) {
+            ("particle_count", ParameterValue::Int(count)) => self.particle_count = (*count).max(0) as usize,
+            ("gravity", ParameterValue::Float(gravity)) => self.gravity = Vec3::new(0.0, -gravity, 0.0),
+            ("bounds", ParameterValue::Float(bounds)) => self.bounds = *bounds,
+            ("drag", ParameterValue::Float(drag)) => self.drag = *drag,
+            ("restitution", ParameterValue::Float(restitution)) => self.restitution = *restitution,
+            ("friction", ParameterValue::Float(friction)) => self.friction = *friction,
+            ("integrator", ParameterValue::Enum(index)) => {
+                if let Some(&integrator) = Integrator::ALL.get(*index) {
+                    self.integrator = integrator;
+                }
+            }
+            ("mass_distribution", ParameterValue::Enum(index)) => {
+                if let Some(&distribution) = MassDistribution::ALL.get(*index) {
+                    self.mass_distribution = distribution;
+                }
+            }
+            ("gravity_well", ParameterValue::Bool(on)) => self.gravity_well = *on,
+            ("speed", ParameterValue::Float(speed)) => self.speed_multiplier = *speed,
+            ("paused", ParameterValue::Bool(paused)) => self.paused = *paused,
+            _ => {}
+        }
+    }
+}
+
+/// Particle state for large counts, kept in flat arrays and drawn as a
+/// single `PointList` mesh. Empty when particles are entities.
+#[derive(Resource, Default)]
+pub struct ParticleCloud {
+    pub positions: Vec<Vec3>,
+    pub velocities: Vec<Vec3>,
+    /// Acceleration used in each particle's last step, for [`Integrator::Verlet`].
+    pub prev_accelerations: Vec<Vec3>,
+    /// Particle masses (kg).
+    pub masses: Vec<f32>,
+}
+
+impl ParticleCloud {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn push(&mut self, position: Vec3, particle: Particle) {
+        self.positions.push(position);
+        self.velocities.push(particle.velocity);
+        self.prev_accelerations.push(particle.prev_acceleration);
+        self.masses.push(particle.mass);
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.positions.truncate(len);
+        self.velocities.truncate(len);
+        self.prev_accelerations.truncate(len);
+        self.masses.truncate(len);
+    }
+
+    /// Runs `f` on every particle's position, velocity, previous
+    /// acceleration and mass across the compute task pool.
+    pub fn par_for_each_mut(&mut self, f: impl Fn(&mut Vec3, &mut Vec3, &mut Vec3, f32) + Send + Sync) {
+        let f = &f;
+        let chunks = self
+            .positions
+            .chunks_mut(CLOUD_CHUNK_SIZE)
+            .zip(self.velocities.chunks_mut(CLOUD_CHUNK_SIZE))
+            .zip(self.prev_accelerations.chunks_mut(CLOUD_CHUNK_SIZE))
+            .zip(self.masses.chunks(CLOUD_CHUNK_SIZE));
+        ComputeTaskPool::get().scope(|scope| {
+            for (((positions, velocities), prev_accelerations), masses) in chunks {
+                scope.spawn(async move {
+                    for (((position, velocity), prev_acceleration), &mass) in
+                        positions.iter_mut().zip(velocities).zip(prev_accelerations).zip(masses)
+                    {
+                        f(position, velocity, prev_acceleration, mass);
+                    }
+                });
+            }
+        });
+    }
+}
+
+/// Streams particles in and out over several frames until the live count
+/// matches [`ParticleConfig::particle_count`], so a million-particle spawn
+/// doesn't stall a single frame.
+#[derive(Resource, Default)]
+pub struct SpawnQueue {
+    /// Live count when the current fill started.
+    from: usize,
+    /// Count being worked toward.
+    target: usize,
+    /// Particles alive now.
+    current: usize,
+    /// Shared sphere mesh and per-mass materials for entity particles.
+    mesh: Handle<Mesh>,
+    materials: Vec<Handle<StandardMaterial>>,
+}
+
+impl SpawnQueue {
+    pub fn is_done(&self) -> bool {
+        self.current == self.target
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Starts progress over from an empty scene, for a respawn.
+    pub fn restart(&mut self) {
+        self.from = 0;
+    }
+
+    /// Fraction of the current fill completed, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        let total = self.target.abs_diff(self.from);
+        if total == 0 {
+            return 1.0;
+        }
+        self.current.abs_diff(self.from) as f32 / total as f32
+    }
+}
+
 /// Runtime statistics.
 #[derive(Resource, Default)]
 pub struct ParticleStats {
     pub fps: f32,
     pub particle_count: usize,
+    /// Average particle speed (m/s).
+    pub mean_speed: f32,
+    /// Kinetic energy summed over all particles (J).
+    pub kinetic_energy: f32,
+    /// Gravitational potential energy above the floor, plus the gravity
+    /// well's when it is on (J).
+    pub potential_energy: f32,
+    /// Recent total energies, oldest first.
+    pub energy_history: Vec<f32>,
+}
+
+impl ParticleStats {
+    pub fn total_energy(&self) -> f32 {
+        self.kinetic_energy + self.potential_energy
+    }
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -153,10 +595,31 @@ pub struct ParticleSystemPlugin;
 
 impl Plugin for ParticleSystemPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CameraControllerPlugin>() {
+            app.add_plugins(CameraControllerPlugin);
+        }
+        if !app.is_plugin_added::<AxesPlugin>() {
+            app.add_plugins(AxesPlugin);
+        }
+        if !app.is_plugin_added::<PerformanceProfilePlugin>() {
+            app.add_plugins(PerformanceProfilePlugin);
+        }
+
         app.init_resource::<ParticleConfig>()
             .init_resource::<ParticleStats>()
-            .add_systems(Startup, setup_particle_scene)
-            .add_systems(Update, (update_particles, update_stats, render_ui));
+            .init_resource::<ParticleCloud>()
+            .init_resource::<SpawnQueue>()
+            .add_plugins(ParticleGpuPlugin)
+            .add_simulation_setup(SIMULATION_ID, setup_particle_scene)
+            .add_simulation_cleanup(SIMULATION_ID, hide_axes)
+            .add_simulation_systems(
+                SIMULATION_ID,
+                (
+                    (stream_particle_spawns, apply_mouse_attractor, update_particles, update_particle_cloud, sync_particle_cloud_mesh).chain(),
+                    update_stats,
+                    render_ui,
+                ),
+            );
     }
 }
 
@@ -164,131 +627,491 @@ impl Plugin for ParticleSystemPlugin {
 // Systems
 // ══════════════════════════════════════════════════════════════════════════════
 
+#[allow(clippy::too_many_arguments)]
 fn setup_particle_scene(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    config: Res<ParticleConfig>,
+    mut config: ResMut<ParticleConfig>,
+    mut cloud: ResMut<ParticleCloud>,
+    mut queue: ResMut<SpawnQueue>,
+    profile: Res<PerformanceProfile>,
+    mut tuned: Local<bool>,
 ) {
-    let mut rng = rand::thread_rng();
-    let bounds = config.bounds;
+    // Size the first scene to the machine; later visits keep what the user chose.
+    if !*tuned {
+        *tuned = true;
+        for (id, value) in ParticleSystemSimulation.recommended_defaults(profile.tier) {
+            config.apply_parameter(id, &value);
+        }
+    }
 
-    // Create shared mesh and material for instancing
-    let mesh = meshes.add(
-        Sphere::new(0.05)
-            .mesh()
-            .ico(1)
-            .expect("Failed to create sphere mesh"),
-    );
-    let material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.2, 0.7, 1.0),
-        emissive: Color::srgb(0.1, 0.3, 0.5).into(),
+    // Particles stream in from `stream_particle_spawns`.
+    *cloud = ParticleCloud::default();
+    *queue = SpawnQueue {
+        target: config.particle_count,
+        mesh: meshes.add(Sphere::new(0.05).mesh().ico(1).expect("Failed to create sphere mesh")),
+        materials: (0..MASS_COLOR_BINS)
+            .map(|bin| {
+                let color = viridis(bin as f32 / (MASS_COLOR_BINS - 1) as f32);
+                materials.add(StandardMaterial {
+                    base_color: color,
+                    emissive: color.to_linear() * 0.4,
+                    ..default()
+                })
+            })
+            .collect(),
         ..default()
+    };
+
+    // Camera
+    let camera = spawn_orbit_camera(
+        &mut commands,
+        OrbitCamera {
+            distance: Vec2::new(150.0, 50.0).length(),
+            pitch: -(50.0_f32 / 150.0).atan(),
+            ..default()
+        },
+    );
+    commands.entity(camera).insert(SimulationEntity(SIMULATION_ID));
+    commands.insert_resource(ShowAxes { unit: "m" });
+
+    // Ambient light
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 500.0,
     });
 
-    // Spawn particles
-    for _ in 0..config.particle_count {
-        let position = Vec3::new(
-            rng.gen_range(-bounds..bounds),
-            rng.gen_range(0.0..bounds * 2.0),
-            rng.gen_range(-bounds..bounds),
-        );
+    // Directional light
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: 10000.0,
+                shadows_enabled: false,
+                ..default()
+            },
+            transform: Transform::from_xyz(50.0, 100.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        SimulationEntity(SIMULATION_ID),
+    ));
+}
 
-        let velocity = Vec3::new(
-            rng.gen_range(-10.0..10.0),
-            rng.gen_range(-5.0..15.0),
-            rng.gen_range(-10.0..10.0),
-        );
+/// Adds or removes up to [`SPAWN_BATCH`] particles a frame until the live
+/// count reaches [`ParticleConfig::particle_count`].
+///
+/// Crossing [`INSTANCED_PARTICLE_THRESHOLD`] first moves the existing
+/// particles between entities and the [`ParticleCloud`] in one frame; that
+/// is at most the threshold's worth of entities either way.
+#[allow(clippy::too_many_arguments)]
+fn stream_particle_spawns(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<ParticleConfig>,
+    mut queue: ResMut<SpawnQueue>,
+    mut cloud: ResMut<ParticleCloud>,
+    particles: Query<(Entity, &Transform, &Particle)>,
+    cloud_mesh: Query<Entity, With<ParticleCloudMesh>>,
+) {
+    let target = config.particle_count;
+    let instanced = target > INSTANCED_PARTICLE_THRESHOLD;
+    let entity_count = particles.iter().len();
+    let current = entity_count + cloud.len();
+    if target != queue.target {
+        queue.from = current;
+        queue.target = target;
+    }
+    queue.current = current;
 
+    let spawn_entity = |commands: &mut Commands, queue: &SpawnQueue, position: Vec3, particle: Particle| {
         commands.spawn((
             PbrBundle {
-                mesh: mesh.clone(),
-                material: material.clone(),
+                mesh: queue.mesh.clone(),
+                material: queue.materials[mass_bin(particle.mass)].clone(),
                 transform: Transform::from_translation(position),
                 ..default()
             },
-            Particle { velocity },
+            particle,
             ParticleTag,
+            SimulationEntity(SIMULATION_ID),
         ));
+    };
+
+    // Switch representation; the queue carries on next frame.
+    if instanced && entity_count > 0 {
+        for (entity, transform, particle) in &particles {
+            cloud.push(
+                transform.translation,
+                Particle { velocity: particle.velocity, prev_acceleration: particle.prev_acceleration, mass: particle.mass },
+            );
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    if !instanced && !cloud.is_empty() {
+        let keep = cloud.len().min(target.saturating_sub(entity_count));
+        let cloud = std::mem::take(&mut *cloud);
+        let particles = cloud.positions.into_iter().zip(cloud.velocities).zip(cloud.prev_accelerations).zip(cloud.masses);
+        for (((position, velocity), prev_acceleration), mass) in particles.take(keep) {
+            spawn_entity(&mut commands, &queue, position, Particle { velocity, prev_acceleration, mass });
+        }
+        for entity in &cloud_mesh {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
     }
 
-    // Camera
-    commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 50.0, 150.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
-    });
+    let mut rng = rand::thread_rng();
+    let mut sample = || {
+        let (position, mut particle) = config.spawn_shape.sample(&mut rng, config.bounds);
+        particle.mass = config.mass_distribution.sample(&mut rng, config.heavy_mass);
+        particle.prev_acceleration = particle_acceleration(position, particle.velocity, particle.mass, &config);
+        (position, particle)
+    };
+    if current < target {
+        let count = (target - current).min(SPAWN_BATCH);
+        if instanced {
+            for _ in 0..count {
+                let (position, particle) = sample();
+                cloud.push(position, particle);
+            }
+            if cloud_mesh.is_empty() {
+                commands.spawn((
+                    PbrBundle {
+                        mesh: meshes.add(create_point_mesh(&cloud)),
+                        material: materials.add(StandardMaterial {
+                            // Vertex colors carry the mass.
+                            base_color: Color::WHITE,
+                            unlit: true,
+                            ..default()
+                        }),
+                        ..default()
+                    },
+                    // The mesh bounds go stale as particles move.
+                    NoFrustumCulling,
+                    ParticleCloudMesh,
+                    SimulationEntity(SIMULATION_ID),
+                ));
+            }
+        } else {
+            for _ in 0..count {
+                let (position, particle) = sample();
+                spawn_entity(&mut commands, &queue, position, particle);
+            }
+        }
+        queue.current += count;
+    } else if current > target {
+        let count = (current - target).min(SPAWN_BATCH);
+        if instanced {
+            let len = cloud.len() - count;
+            cloud.truncate(len);
+        } else {
+            for (entity, _, _) in particles.iter().take(count) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        queue.current -= count;
+    }
+}
 
-    // Ambient light
-    commands.insert_resource(AmbientLight {
-        color: Color::WHITE,
-        brightness: 500.0,
-    });
+/// Pulls particles toward the cursor while the left button is held, or
+/// pushes them away with Shift.
+///
+/// The cursor is projected onto the y = 0 plane; the pull falls off
+/// linearly to zero at `attractor_radius`.
+#[allow(clippy::too_many_arguments)]
+fn apply_mouse_attractor(
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut query: Query<(&Transform, &mut Particle)>,
+    mut cloud: ResMut<ParticleCloud>,
+    config: Res<ParticleConfig>,
+    time: Res<Time>,
+    mut contexts: EguiContexts,
+) {
+    if config.paused || config.gpu || !buttons.pressed(MouseButton::Left) {
+        return;
+    }
+    if contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
 
-    // Directional light
-    commands.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            illuminance: 10000.0,
-            shadows_enabled: false,
-            ..default()
-        },
-        transform: Transform::from_xyz(50.0, 100.0, 50.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
+    let Ok(window) = windows.get_single() else { return };
+    let Ok((camera, camera_transform)) = cameras.get_single() else { return };
+    let Some(cursor_pos) = window.cursor_position() else { return };
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor_pos) else { return };
+
+    // Intersect with Y=0 plane
+    let t = -ray.origin.y / ray.direction.y;
+    if !t.is_finite() || t <= 0.0 {
+        return;
+    }
+    let target = ray.origin + ray.direction * t;
+
+    let repel = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let strength = if repel { -config.attractor_strength } else { config.attractor_strength };
+    let radius = config.attractor_radius.max(0.01);
+    let dt = time.delta_seconds() * config.speed_multiplier;
+
+    let pull = |position: Vec3| {
+        let offset = target - position;
+        let distance = offset.length();
+        if distance < radius && distance > 0.01 {
+            let falloff = 1.0 - distance / radius;
+            offset / distance * strength * falloff * dt
+        } else {
+            Vec3::ZERO
+        }
+    };
+
+    query.par_iter_mut().for_each(|(transform, mut particle)| {
+        particle.velocity += pull(transform.translation);
     });
+    if !cloud.is_empty() {
+        cloud.par_for_each_mut(|position, velocity, _, _| *velocity += pull(*position));
+    }
 }
 
-fn update_particles(
+/// Integrates gravity and bounces particles off the bounds on the CPU.
+pub fn update_particles(
     mut query: Query<(&mut Transform, &mut Particle)>,
     config: Res<ParticleConfig>,
     time: Res<Time>,
 ) {
-    if config.paused {
+    if config.paused || config.gpu {
         return;
     }
 
     let dt = time.delta_seconds() * config.speed_multiplier;
-    let bounds = config.bounds;
-    let gravity = config.gravity;
+    let config = &*config;
 
     query
         .par_iter_mut()
         .for_each(|(mut transform, mut particle)| {
-            particle.velocity += gravity * dt;
-            transform.translation += particle.velocity * dt;
-
-            // Bounce off bounds
-            if transform.translation.x.abs() > bounds {
-                transform.translation.x = transform.translation.x.signum() * bounds;
-                particle.velocity.x *= -0.8;
-            }
-            if transform.translation.y < -bounds {
-                transform.translation.y = -bounds;
-                particle.velocity.y *= -0.8;
-            }
-            if transform.translation.y > bounds {
-                transform.translation.y = bounds;
-                particle.velocity.y *= -0.8;
-            }
-            if transform.translation.z.abs() > bounds {
-                transform.translation.z = transform.translation.z.signum() * bounds;
-                particle.velocity.z *= -0.8;
-            }
+            let particle = &mut *particle;
+            integrate_particle(
+                &mut transform.translation,
+                &mut particle.velocity,
+                &mut particle.prev_acceleration,
+                particle.mass,
+                config,
+                dt,
+            );
         });
 }
 
-fn update_stats(query: Query<&Particle>, mut stats: ResMut<ParticleStats>, time: Res<Time>) {
+/// CPU integration for the instanced path; the same motion as
+/// [`update_particles`] on flat arrays.
+pub fn update_particle_cloud(mut cloud: ResMut<ParticleCloud>, config: Res<ParticleConfig>, time: Res<Time>) {
+    if config.paused || config.gpu || cloud.is_empty() {
+        return;
+    }
+
+    let dt = time.delta_seconds() * config.speed_multiplier;
+    let config = &*config;
+    cloud.par_for_each_mut(|position, velocity, prev_acceleration, mass| {
+        integrate_particle(position, velocity, prev_acceleration, mass, config, dt)
+    });
+}
+
+/// Acceleration toward the gravity well at the origin, if it is on.
+///
+/// The particle's mass cancels out of F/m, just as under uniform gravity:
+/// every mass falls in together unless drag is on.
+pub fn well_acceleration(position: Vec3, mass: f32, config: &ParticleConfig) -> Vec3 {
+    if !config.gravity_well {
+        return Vec3::ZERO;
+    }
+    gravitational_force(mass, config.well_strength, position, Vec3::ZERO, 1.0) / mass
+}
+
+/// Acceleration of a particle from gravity, the well and drag.
+fn particle_acceleration(position: Vec3, velocity: Vec3, mass: f32, config: &ParticleConfig) -> Vec3 {
+    config.gravity + well_acceleration(position, mass, config) + damping_force(velocity, config.drag) / mass
+}
+
+/// Advances one particle by `dt` under gravity and drag with
+/// [`ParticleConfig::integrator`], and bounces it off the bounds with
+/// [`ParticleConfig::restitution`] and [`ParticleConfig::friction`].
+///
+/// Drag is a force, so it slows light particles more than heavy ones.
+/// Semi-implicit Euler takes it implicitly: dividing the step by
+/// `1 + drag·dt/m` gives `v' = (v + g·dt) / (1 + drag·dt/m)`, which settles on
+/// the terminal velocity without overshoot however large `drag·dt` gets. The
+/// other schemes take it explicitly, as a teaching comparison.
+///
+/// Velocity Verlet drifts with the acceleration left from the previous step,
+/// then kicks with the mean of that and the acceleration at the new
+/// position, so it costs one force evaluation a step like the others.
+fn integrate_particle(
+    position: &mut Vec3,
+    velocity: &mut Vec3,
+    prev_acceleration: &mut Vec3,
+    mass: f32,
+    config: &ParticleConfig,
+    dt: f32,
+) {
+    let bounds = config.bounds;
+    match config.integrator {
+        Integrator::Euler => {
+            let acceleration = particle_acceleration(*position, *velocity, mass, config);
+            euler_integrate(position, velocity, acceleration, dt);
+            *prev_acceleration = acceleration;
+        }
+        Integrator::SemiImplicitEuler => {
+            let acceleration = particle_acceleration(*position, *velocity, mass, config);
+            semi_implicit_euler(position, velocity, acceleration / (1.0 + config.drag * dt / mass), dt);
+            *prev_acceleration = acceleration;
+        }
+        Integrator::Verlet => {
+            *position += *velocity * dt + 0.5 * *prev_acceleration * dt * dt;
+            let acceleration = particle_acceleration(*position, *velocity, mass, config);
+            *velocity += 0.5 * (*prev_acceleration + acceleration) * dt;
+            *prev_acceleration = acceleration;
+        }
+    }
+
+    // Bounce off bounds: restitution scales the normal component, friction
+    // takes its share of the tangential ones.
+    for axis in 0..3 {
+        if position[axis].abs() > bounds {
+            position[axis] = position[axis].signum() * bounds;
+            let normal = velocity[axis];
+            *velocity *= 1.0 - config.friction;
+            velocity[axis] = -normal * config.restitution;
+        }
+    }
+}
+
+/// Copies [`ParticleCloud`] positions into its point mesh, recoloring it
+/// when particles were added or removed.
+fn sync_particle_cloud_mesh(
+    cloud: Res<ParticleCloud>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    cloud_mesh: Query<&Handle<Mesh>, With<ParticleCloudMesh>>,
+) {
+    if !cloud.is_changed() {
+        return;
+    }
+    let Ok(handle) = cloud_mesh.get_single() else { return };
+    let Some(mesh) = meshes.get_mut(handle) else { return };
+    if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+        // The cloud grows and shrinks while particles stream in and out.
+        positions.resize(cloud.len(), [0.0; 3]);
+        for (vertex, position) in positions.iter_mut().zip(&cloud.positions) {
+            *vertex = position.to_array();
+        }
+    }
+    if mesh.attribute(Mesh::ATTRIBUTE_COLOR).map(|colors| colors.len()) != Some(cloud.len()) {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, mass_colors(&cloud.masses));
+    }
+}
+
+fn create_point_mesh(cloud: &ParticleCloud) -> Mesh {
+    let positions: Vec<[f32; 3]> = cloud.positions.iter().map(|position| position.to_array()).collect();
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::PointList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, mass_colors(&cloud.masses));
+    mesh
+}
+
+fn mass_colors(masses: &[f32]) -> Vec<[f32; 4]> {
+    masses.iter().map(|&mass| mass_color(mass).to_linear().to_f32_array()).collect()
+}
+
+fn update_stats(
+    query: Query<(&Transform, &Particle)>,
+    cloud: Res<ParticleCloud>,
+    config: Res<ParticleConfig>,
+    mut stats: ResMut<ParticleStats>,
+    time: Res<Time>,
+) {
     stats.fps = 1.0 / time.delta_seconds();
-    stats.particle_count = query.iter().count();
+    stats.particle_count = query.iter().count() + cloud.len();
+
+    // Heights are measured from the floor at -bounds, so uniform gravity's
+    // potential energy never goes negative; the well's always is.
+    let floor = -config.bounds;
+    let cloud_particles = cloud.positions.iter().zip(&cloud.velocities).zip(&cloud.masses);
+    let (kinetic, potential, speed) = query
+        .iter()
+        .map(|(transform, particle)| (transform.translation, particle.velocity, particle.mass))
+        .chain(cloud_particles.map(|((position, velocity), mass)| (*position, *velocity, *mass)))
+        .fold((0.0, 0.0, 0.0), |(kinetic, potential, speed), (position, velocity, mass)| {
+            let height = Vec3::new(position.x, position.y - floor, position.z);
+            let mut energy = -mass * config.gravity.dot(height);
+            if config.gravity_well {
+                // Matches gravitational_force's 0.1 m cutoff.
+                energy -= config.well_strength * mass / position.length().max(0.1);
+            }
+            (kinetic + 0.5 * mass * velocity.length_squared(), potential + energy, speed + velocity.length())
+        });
+    stats.mean_speed = speed / stats.particle_count.max(1) as f32;
+    stats.kinetic_energy = kinetic;
+    stats.potential_energy = potential;
+
+    if !config.paused {
+        let total = stats.total_energy();
+        stats.energy_history.push(total);
+        if stats.energy_history.len() > ENERGY_HISTORY_LEN {
+            stats.energy_history.remove(0);
+        }
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_ui(
     mut contexts: EguiContexts,
     mut config: ResMut<ParticleConfig>,
     stats: Res<ParticleStats>,
+    mut cameras: Query<&mut OrbitCamera>,
+    particles: Query<Entity, With<ParticleTag>>,
+    mut cloud: ResMut<ParticleCloud>,
+    mut queue: ResMut<SpawnQueue>,
+    mut commands: Commands,
 ) {
     egui::Window::new("Particle System Controls").show(contexts.ctx_mut(), |ui| {
         ui.heading("Statistics");
         ui.label(format!("FPS: {:.0}", stats.fps));
-        ui.label(format!("Particles: {}", stats.particle_count));
+        ui.label(format!("Particles: {}", format_si(stats.particle_count as f64)));
+        if !queue.is_done() {
+            ui.add(egui::ProgressBar::new(queue.progress()).text(format!(
+                "{} / {} particles",
+                format_si(queue.current() as f64),
+                format_si(queue.target() as f64)
+            )));
+        }
+        if stats.particle_count > INSTANCED_PARTICLE_THRESHOLD {
+            ui.small("Rendering as a single instanced point cloud");
+        }
+
+        ui.label(format!("Mean speed: {}", format_with_unit(stats.mean_speed as f64, Some("m/s"))));
+        if config.drag > 0.0 {
+            // m·|g| / drag, for the lightest and heaviest particles.
+            let (light, heavy) = config.mass_distribution.mass_range(config.heavy_mass);
+            let terminal_speed = |mass: f32| {
+                let speed = mass * config.gravity.length() / config.drag;
+                format!("{} at {}", format_with_unit(speed as f64, Some("m/s")), format_with_unit(mass as f64, Some("kg")))
+            };
+            if heavy > light {
+                ui.label(format!("Terminal speed: {} to {}", terminal_speed(light), terminal_speed(heavy)));
+            } else {
+                ui.label(format!("Terminal speed: {}", terminal_speed(light)));
+            }
+        }
+
+        ui.separator();
+        ui.heading("Energy");
+        ui.label(format!("Kinetic: {}", format_with_unit(stats.kinetic_energy as f64, Some("J"))));
+        ui.label(format!("Potential: {}", format_with_unit(stats.potential_energy as f64, Some("J"))));
+        ui.label(format!("Total: {}", format_with_unit(stats.total_energy() as f64, Some("J"))));
+        render_energy_plot(ui, &stats.energy_history);
 
         ui.separator();
         ui.heading("Controls");
@@ -301,11 +1124,247 @@ fn render_ui(
         }
 
         ui.add(egui::Slider::new(&mut config.speed_multiplier, 0.1..=5.0).text("Speed"));
+        ui.add(
+            egui::Slider::new(&mut config.particle_count, 0..=1_000_000)
+                .logarithmic(true)
+                .text("Particles")
+                .custom_formatter(|v, _| format_si(v))
+                .custom_parser(parse_si),
+        )
+            .on_hover_text(format!("Added or removed up to {} a frame", format_si(SPAWN_BATCH as f64)));
 
         let mut gravity_y = -config.gravity.y;
         ui.add(egui::Slider::new(&mut gravity_y, 0.0..=30.0).text("Gravity (m/s²)"));
         config.gravity.y = -gravity_y;
 
         ui.add(egui::Slider::new(&mut config.bounds, 10.0..=200.0).text("Bounds (m)"));
+        ui.add(egui::Slider::new(&mut config.drag, 0.0..=5.0).text("Drag (kg/s)"))
+            .on_hover_text("Velocity-proportional drag force; particles settle at m·|g| / drag");
+        ui.add(egui::Slider::new(&mut config.restitution, 0.0..=1.0).text("Restitution"))
+            .on_hover_text("1 bounces elastically, 0 stops dead at the wall");
+        ui.add(egui::Slider::new(&mut config.friction, 0.0..=1.0).text("Wall Friction"))
+            .on_hover_text("Share of the velocity along the wall lost on each bounce");
+
+        ui.horizontal(|ui| {
+            ui.label("Integrator:");
+            egui::ComboBox::from_id_source("integrator")
+                .selected_text(config.integrator.label())
+                .show_ui(ui, |ui| {
+                    for integrator in Integrator::ALL {
+                        ui.selectable_value(&mut config.integrator, integrator, integrator.label());
+                    }
+                })
+                .response
+                .on_hover_text("With restitution 1, watch the energy plot: Euler climbs, Verlet stays flat (CPU only)");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Shape:");
+            egui::ComboBox::from_id_source("spawn_shape")
+                .selected_text(format!("{:?}", config.spawn_shape))
+                .show_ui(ui, |ui| {
+                    for shape in SpawnShape::ALL {
+                        ui.selectable_value(&mut config.spawn_shape, shape, format!("{:?}", shape));
+                    }
+                });
+            let respawn = ui
+                .add_enabled(!config.gpu, egui::Button::new("↺ Respawn"))
+                .on_hover_text("Re-seed every particle in the chosen shape and mass distribution")
+                .on_disabled_hover_text("Particle state lives on the GPU; turn off GPU Compute to respawn");
+            if respawn.clicked() {
+                // Clear the scene and let the spawn queue stream it back in.
+                for entity in &particles {
+                    commands.entity(entity).despawn_recursive();
+                }
+                *cloud = ParticleCloud::default();
+                queue.restart();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Masses:");
+            egui::ComboBox::from_id_source("mass_distribution")
+                .selected_text(config.mass_distribution.label())
+                .show_ui(ui, |ui| {
+                    for distribution in MassDistribution::ALL {
+                        ui.selectable_value(&mut config.mass_distribution, distribution, distribution.label());
+                    }
+                })
+                .response
+                .on_hover_text("Applies on respawn; colors run from light (dark) to heavy (yellow)");
+        });
+        if config.mass_distribution != MassDistribution::Equal {
+            ui.add(egui::Slider::new(&mut config.heavy_mass, 1.0..=MAX_PARTICLE_MASS).text("Heavy Mass (kg)"));
+            if config.drag == 0.0 {
+                ui.small("Gravity pulls every mass alike; add drag to see them separate");
+            }
+        }
+
+        ui.checkbox(&mut config.gravity_well, "Gravity Well")
+            .on_hover_text("A point mass at the center of the box pulling with G·M / r²");
+        if config.gravity_well {
+            ui.add(
+                egui::Slider::new(&mut config.well_strength, 1_000.0..=100_000.0)
+                    .logarithmic(true)
+                    .text("G·M (m³/s²)"),
+            );
+        }
+
+        ui.checkbox(&mut config.gpu, "GPU Compute")
+            .on_hover_text("Integrate particles in a compute shader (falls back to CPU without compute support)");
+
+        ui.separator();
+        ui.heading("Mouse Attractor");
+        ui.label("Hold left mouse to attract, Shift + left mouse to repel");
+        ui.add(egui::Slider::new(&mut config.attractor_strength, 0.0..=200.0).text("Strength (m/s²)"));
+        ui.add(egui::Slider::new(&mut config.attractor_radius, 1.0..=100.0).text("Radius (m)"));
+
+        if let Ok(mut camera) = cameras.get_single_mut() {
+            ui.separator();
+            ui.heading("Camera");
+            ui.checkbox(&mut camera.auto_rotate, "Auto-Rotate (demo mode)");
+            if camera.auto_rotate {
+                ui.add(
+                    egui::Slider::new(&mut camera.auto_rotate_speed, 0.05..=1.0)
+                        .text("Rotate Speed (rad/s)"),
+                );
+            }
+        }
     });
 }
+
+/// Plots total energy over recent frames, scaled to its own range.
+fn render_energy_plot(ui: &mut egui::Ui, history: &[f32]) {
+    line_plot(ui, &[Series::new("Total", history).color(egui::Color32::from_rgb(255, 180, 60))], None);
+    if history.len() < 2 {
+        return;
+    }
+    let min = history.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = history.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    ui.small(format!("Range: {:.3e} – {:.3e} J over {} frames", min, max, history.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_world(particle_count: usize) -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world.init_resource::<ParticleCloud>();
+        world.insert_resource(ParticleConfig { particle_count, ..default() });
+        world.insert_resource(SpawnQueue { materials: vec![Handle::default(); MASS_COLOR_BINS], ..default() });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(stream_particle_spawns);
+        (world, schedule)
+    }
+
+    fn entity_positions(world: &mut World) -> Vec<Vec3> {
+        world.query_filtered::<&Transform, With<Particle>>().iter(world).map(|transform| transform.translation).collect()
+    }
+
+    #[test]
+    fn test_spawns_stream_in_batches_and_shrink() {
+        let target = 2 * SPAWN_BATCH + SPAWN_BATCH / 2;
+        let (mut world, mut schedule) = spawn_world(target);
+        for frame in 1..=target.div_ceil(SPAWN_BATCH) {
+            schedule.run(&mut world);
+            assert_eq!(world.resource::<ParticleCloud>().len(), (frame * SPAWN_BATCH).min(target));
+        }
+        // The queue notices on its next look that the fill is complete.
+        schedule.run(&mut world);
+        assert!(world.resource::<SpawnQueue>().is_done());
+        assert_eq!(world.query::<&ParticleCloudMesh>().iter(&world).count(), 1);
+
+        let shrunk = SPAWN_BATCH / 2 + INSTANCED_PARTICLE_THRESHOLD;
+        world.resource_mut::<ParticleConfig>().particle_count = shrunk;
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ParticleCloud>().len(), target - SPAWN_BATCH);
+        let progress = world.resource::<SpawnQueue>().progress();
+        assert!((progress - SPAWN_BATCH as f32 / (target - shrunk) as f32).abs() < 1e-6, "progress {progress}");
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ParticleCloud>().len(), shrunk);
+
+        // A respawn measures progress from an empty scene.
+        world.resource_mut::<SpawnQueue>().restart();
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpawnQueue>().progress(), 1.0);
+    }
+
+    #[test]
+    fn test_crossing_the_instancing_threshold_keeps_particles() {
+        let below = INSTANCED_PARTICLE_THRESHOLD / 2;
+        let (mut world, mut schedule) = spawn_world(below);
+        schedule.run(&mut world);
+        let positions = entity_positions(&mut world);
+        assert_eq!(positions.len(), below);
+
+        // Up: the entities move into the cloud first, then the rest stream in.
+        world.resource_mut::<ParticleConfig>().particle_count = INSTANCED_PARTICLE_THRESHOLD * 2;
+        schedule.run(&mut world);
+        assert!(entity_positions(&mut world).is_empty());
+        let cloud = world.resource::<ParticleCloud>();
+        assert_eq!(cloud.len(), below);
+        assert!(positions.iter().all(|position| cloud.positions.contains(position)));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ParticleCloud>().len(), INSTANCED_PARTICLE_THRESHOLD * 2);
+
+        // Down: the first particles of the cloud become entities, in one frame.
+        let kept = world.resource::<ParticleCloud>().positions[..below].to_vec();
+        world.resource_mut::<ParticleConfig>().particle_count = below;
+        schedule.run(&mut world);
+        assert!(world.resource::<ParticleCloud>().is_empty());
+        assert_eq!(world.query::<&ParticleCloudMesh>().iter(&world).count(), 0);
+        let positions = entity_positions(&mut world);
+        assert_eq!(positions.len(), below);
+        assert!(positions.iter().all(|position| kept.contains(position)));
+        schedule.run(&mut world);
+        assert!(world.resource::<SpawnQueue>().is_done());
+    }
+
+    #[test]
+    fn test_spawn_shapes_start_inside_the_bounds() {
+        let mut rng = rand::thread_rng();
+        for shape in SpawnShape::ALL {
+            for _ in 0..1000 {
+                let (position, _) = shape.sample(&mut rng, 20.0);
+                assert!(position.abs().max_element() <= 20.0, "{shape:?} spawned at {position}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_wall_hit_applies_restitution_and_friction() {
+        let config = ParticleConfig { gravity: Vec3::ZERO, restitution: 0.5, friction: 0.25, bounds: 10.0, ..default() };
+        let mut position = Vec3::new(9.95, 0.0, 0.0);
+        let mut velocity = Vec3::new(10.0, 0.0, 4.0);
+        integrate_particle(&mut position, &mut velocity, &mut Vec3::default(), 1.0, &config, 0.01);
+
+        assert_eq!(position.x, 10.0);
+        assert!((velocity - Vec3::new(-5.0, 0.0, 3.0)).length() < 1e-5, "{velocity}");
+    }
+
+    #[test]
+    fn test_verlet_energy_stays_bounded_in_the_well() {
+        let config = ParticleConfig {
+            gravity: Vec3::ZERO,
+            gravity_well: true,
+            integrator: Integrator::Verlet,
+            bounds: 1000.0,
+            ..default()
+        };
+        let energy = |position: Vec3, velocity: Vec3| 0.5 * velocity.length_squared() - config.well_strength / position.length();
+
+        // An eccentric orbit dipping to about a third of its starting radius.
+        let mut position = Vec3::new(20.0, 0.0, 0.0);
+        let mut velocity = Vec3::Y * 0.7 * (config.well_strength / 20.0).sqrt();
+        let mut prev_acceleration = particle_acceleration(position, velocity, 1.0, &config);
+        let initial = energy(position, velocity);
+        for _ in 0..2000 {
+            integrate_particle(&mut position, &mut velocity, &mut prev_acceleration, 1.0, &config, 1.0 / 60.0);
+            let drift = (energy(position, velocity) - initial).abs() / initial.abs();
+            assert!(drift < 0.02, "energy drifted by {drift} at {position}");
+        }
+    }
+}
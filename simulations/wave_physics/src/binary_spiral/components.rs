@@ -14,6 +14,10 @@ pub struct OrbitalSource {
     pub base_color: Color,
     pub velocity: Vec3,
     pub last_position: Vec3,
+    /// Physical velocity used by the two-body integrator.
+    pub orbit_velocity: Vec3,
+    /// Frames until the next burst in burst emission mode.
+    pub frames_to_burst: u32,
 }
 
 impl OrbitalSource {
@@ -30,9 +34,18 @@ impl OrbitalSource {
             base_color: color,
             velocity: Vec3::ZERO,
             last_position: pos,
+            orbit_velocity: Vec3::ZERO,
+            frames_to_burst: 0,
         }
     }
 
+    /// Moves the source to `position`, keeping the polar `angle`/`radius`
+    /// representation used by `current_position` in sync.
+    pub fn set_position(&mut self, position: Vec3) {
+        self.angle = position.z.atan2(position.x);
+        self.radius = Vec2::new(position.x, position.z).length();
+    }
+
     pub fn current_position(&self) -> Vec3 {
         Vec3::new(
             self.angle.cos() * self.radius,
@@ -52,8 +65,41 @@ pub struct SourceGlow;
 #[derive(Component)]
 pub struct ParticleCloud;
 
+#[derive(Component)]
+pub struct ParticleTrails;
+
 #[derive(Component)]
 pub struct OrbitRing;
 
 #[derive(Component)]
 pub struct GridFloor;
+
+/// Floor mesh colored by the gravitational potential.
+#[derive(Component)]
+pub struct PotentialFloor;
+
+/// Point cloud of background stars seen through the binary's lensing.
+#[derive(Component)]
+pub struct LensedStarfield;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Stereo Cameras
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// One of the two eye cameras parented to the orbit camera, offset to its
+/// side by half the eye separation.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StereoEye {
+    Left,
+    Right,
+}
+
+impl StereoEye {
+    /// Which way along the camera's own x axis the eye sits.
+    pub fn sign(self) -> f32 {
+        match self {
+            Self::Left => -1.0,
+            Self::Right => 1.0,
+        }
+    }
+}
@@ -5,6 +5,7 @@
 //! - Spherical particle emission with velocity-based coloring
 //! - Additive blending for density visualization
 //! - Interactive dragging to adjust orbit radius
+//! - Optional two-body gravitational dynamics for eccentric orbits
 
 mod components;
 mod resources;
@@ -18,15 +19,54 @@ pub use ui::*;
 
 use bevy::prelude::*;
 use ez_core::prelude::*;
+use ez_renderer::axes::{hide_axes, AxesPlugin};
+use ez_renderer::camera::CameraControllerPlugin;
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Constants
 // ══════════════════════════════════════════════════════════════════════════════
 
+pub const SIMULATION_ID: &str = "binary_spiral";
 pub const MAX_PARTICLES: usize = 200_000;
 pub const DEFAULT_ORBIT_RADIUS: f32 = 20.0;
 pub const DEFAULT_EMISSION_RATE: usize = 1000;
 pub const DEFAULT_PARTICLE_LIFE: u32 = 300;
+/// Frames between shells in burst emission.
+pub const DEFAULT_BURST_INTERVAL: u32 = 30;
+/// Particles per star in each burst.
+pub const DEFAULT_BURST_COUNT: usize = 5000;
+
+/// Gravitational constant for the physical orbit mode, chosen so the default
+/// equal-mass binary orbits at the same 1.5 rad/s as the kinematic mode.
+pub const ORBIT_GRAVITY: f32 = 720.0;
+/// Plummer softening length for the physical orbit mode, so a close pass
+/// (e.g. while dragging a source) can't produce an unbounded kick.
+pub const ORBIT_SOFTENING: f32 = 1.0;
+/// Side length of the floor grid and the potential heatmap beneath it.
+pub const FLOOR_SIZE: f32 = 300.0;
+/// Vertices along each side of the potential heatmap.
+pub const POTENTIAL_RESOLUTION: usize = 64;
+/// Integration substeps per frame for the physical orbit mode.
+pub const ORBIT_SUBSTEPS: u32 = 8;
+/// Default stereo eye separation, about 1/30 of the starting camera
+/// distance, the usual ratio for comfortable depth.
+pub const DEFAULT_EYE_SEPARATION: f32 = 3.0;
+/// Closest the camera comes, in star separations, while following the
+/// barycenter, so both stars stay in frame.
+pub const BARYCENTER_FRAMING: f32 = 2.5;
+/// Speed of light for the lensing overlay, chosen so a default star's
+/// 4GM/c² is about one unit and its lensing is visible at orbit scale.
+pub const LENSING_LIGHT_SPEED: f32 = 540.0;
+/// Impact parameter below which the deflection stops growing, the radius
+/// of a star's sphere; rays that close would hit the star.
+pub const LENSING_SOFTENING: f32 = 2.5;
+/// How far behind the stars the lensed starfield sits.
+pub const STARFIELD_DISTANCE: f32 = 150.0;
+/// Background stars along each side of the starfield grid.
+pub const STARFIELD_RESOLUTION: usize = 64;
+/// Half-width of the starfield over its distance from the camera, enough to
+/// fill a wide window at the default field of view.
+pub const STARFIELD_EXTENT: f32 = 0.8;
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Simulation Definition
@@ -36,7 +76,7 @@ pub struct BinarySpiralSimulation;
 
 impl Simulation for BinarySpiralSimulation {
     fn id(&self) -> &'static str {
-        "binary_spiral"
+        SIMULATION_ID
     }
 
     fn name(&self) -> &'static str {
@@ -75,6 +115,33 @@ impl Simulation for BinarySpiralSimulation {
                 step: Some(100.0),
                 unit: None,
             },
+            ParameterDef::Enum {
+                id: "emission_mode",
+                name: "Emission Mode",
+                description: "Emit a steady stream, or periodic bursts that expand as spherical shells",
+                options: &["Continuous", "Burst"],
+                default_index: 0,
+            },
+            ParameterDef::Int {
+                id: "burst_interval",
+                name: "Burst Interval",
+                description: "Frames between bursts",
+                min: 5,
+                max: 120,
+                default: DEFAULT_BURST_INTERVAL as i32,
+                step: None,
+                logarithmic: false,
+            },
+            ParameterDef::Int {
+                id: "burst_count",
+                name: "Burst Size",
+                description: "Particles per source in each burst",
+                min: 500,
+                max: 20_000,
+                default: DEFAULT_BURST_COUNT as i32,
+                step: Some(500),
+                logarithmic: false,
+            },
             ParameterDef::Float {
                 id: "particle_speed",
                 name: "Particle Speed",
@@ -85,6 +152,42 @@ impl Simulation for BinarySpiralSimulation {
                 step: Some(0.1),
                 unit: None,
             },
+            ParameterDef::Bool {
+                id: "physical_orbit",
+                name: "Physical Orbit",
+                description: "Integrate the stars as gravitating point masses",
+                default: false,
+            },
+            ParameterDef::Float {
+                id: "mass_a",
+                name: "Mass A",
+                description: "Mass of the purple star in physical orbit mode",
+                min: 10.0,
+                max: 500.0,
+                default: 100.0,
+                step: Some(10.0),
+                unit: None,
+            },
+            ParameterDef::Float {
+                id: "mass_b",
+                name: "Mass B",
+                description: "Mass of the orange star in physical orbit mode",
+                min: 10.0,
+                max: 500.0,
+                default: 100.0,
+                step: Some(10.0),
+                unit: None,
+            },
+            ParameterDef::Float {
+                id: "initial_separation",
+                name: "Initial Separation",
+                description: "Distance between the stars when a physical orbit starts",
+                min: 10.0,
+                max: 100.0,
+                default: 40.0,
+                step: Some(1.0),
+                unit: None,
+            },
             ParameterDef::Bool {
                 id: "paused",
                 name: "Paused",
@@ -117,20 +220,35 @@ pub struct BinarySpiralPlugin;
 
 impl Plugin for BinarySpiralPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CameraControllerPlugin>() {
+            app.add_plugins(CameraControllerPlugin);
+        }
+        if !app.is_plugin_added::<AxesPlugin>() {
+            app.add_plugins(AxesPlugin);
+        }
+        if !app.is_plugin_added::<AdaptiveQualityPlugin>() {
+            app.add_plugins(AdaptiveQualityPlugin);
+        }
+
         app.init_resource::<BinarySpiralConfig>()
             .init_resource::<ParticlePool>()
             .init_resource::<DragState>()
             .init_resource::<RandomDirections>()
             .register_type::<OrbitalSource>()
-            .add_systems(Startup, setup_scene)
-            .add_systems(
-                Update,
+            .add_simulation_setup(SIMULATION_ID, setup_scene)
+            .add_simulation_cleanup(SIMULATION_ID, hide_axes)
+            .add_simulation_systems(
+                SIMULATION_ID,
                 (
                     handle_mouse_input,
                     update_orbital_sources,
+                    follow_camera_target.after(update_orbital_sources),
                     emit_particles,
                     update_particles,
                     sync_particle_mesh,
+                    update_potential_floor,
+                    update_lensed_starfield,
+                    update_stereo_cameras,
                     render_ui,
                 ),
             );
@@ -1,9 +1,11 @@
 //! Resources for the Binary Spiral simulation
 
 use bevy::prelude::*;
+use ez_renderer::viewport::ViewportSide;
 use rand::Rng;
 
-use super::{DEFAULT_EMISSION_RATE, DEFAULT_PARTICLE_LIFE, MAX_PARTICLES};
+use super::components::StereoEye;
+use super::{DEFAULT_EMISSION_RATE, DEFAULT_EYE_SEPARATION, DEFAULT_ORBIT_RADIUS, DEFAULT_PARTICLE_LIFE, MAX_PARTICLES};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Configuration
@@ -13,11 +15,31 @@ use super::{DEFAULT_EMISSION_RATE, DEFAULT_PARTICLE_LIFE, MAX_PARTICLES};
 pub struct BinarySpiralConfig {
     pub orbit_speed: f32,
     pub emission_rate: usize,
+    pub emission_mode: EmissionMode,
     pub particle_speed: f32,
     pub particle_life: u32,
     pub paused: bool,
     pub show_grid: bool,
     pub show_orbit_ring: bool,
+    /// Draw particles as short streaks from their previous position.
+    pub show_trails: bool,
+    /// Color the floor by the stars' gravitational potential.
+    pub show_potential: bool,
+    /// Draw a background starfield bent around the stars by gravitational
+    /// lensing.
+    pub show_lensing: bool,
+    /// Integrate the stars under mutual gravity instead of a fixed circle.
+    pub physical_orbit: bool,
+    pub mass_a: f32,
+    pub mass_b: f32,
+    /// Separation used when a physical orbit is (re)started.
+    pub initial_separation: f32,
+    /// Render a stereo pair side by side instead of a single view.
+    pub stereo: StereoMode,
+    /// Distance between the two stereo eyes.
+    pub eye_separation: f32,
+    /// What the camera's focus tracks.
+    pub camera_follow: CameraFollow,
 }
 
 impl Default for BinarySpiralConfig {
@@ -25,11 +47,91 @@ impl Default for BinarySpiralConfig {
         Self {
             orbit_speed: 1.5,
             emission_rate: DEFAULT_EMISSION_RATE,
+            emission_mode: EmissionMode::Continuous,
             particle_speed: 2.0,
             particle_life: DEFAULT_PARTICLE_LIFE,
             paused: false,
             show_grid: true,
             show_orbit_ring: true,
+            show_trails: false,
+            show_potential: false,
+            show_lensing: false,
+            physical_orbit: false,
+            mass_a: 100.0,
+            mass_b: 100.0,
+            initial_separation: DEFAULT_ORBIT_RADIUS * 2.0,
+            stereo: StereoMode::Off,
+            eye_separation: DEFAULT_EYE_SEPARATION,
+            camera_follow: CameraFollow::Free,
+        }
+    }
+}
+
+/// How the stars release particles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmissionMode {
+    /// `emission_rate` particles per star every frame.
+    #[default]
+    Continuous,
+    /// `count` particles per star every `interval` frames, released from the
+    /// star's center so each burst expands as a clean spherical shell.
+    Burst { interval: u32, count: usize },
+}
+
+/// Side-by-side stereo layouts, for free-viewing without glasses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StereoMode {
+    #[default]
+    Off,
+    /// Left eye's view on the left, for parallel (wall-eyed) viewing.
+    Parallel,
+    /// Left eye's view on the right, for cross-eyed viewing.
+    CrossEyed,
+}
+
+impl StereoMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Parallel, Self::CrossEyed];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Parallel => "Parallel",
+            Self::CrossEyed => "Cross-Eyed",
+        }
+    }
+
+    /// Half of the window an eye's view goes in, or `None` when off.
+    pub fn viewport_side(self, eye: StereoEye) -> Option<ViewportSide> {
+        match (self, eye) {
+            (Self::Off, _) => None,
+            (Self::Parallel, StereoEye::Left) | (Self::CrossEyed, StereoEye::Right) => Some(ViewportSide::Left),
+            (Self::Parallel, StereoEye::Right) | (Self::CrossEyed, StereoEye::Left) => Some(ViewportSide::Right),
+        }
+    }
+}
+
+/// Reference frame the camera views the binary from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraFollow {
+    /// The focus stays wherever it was left.
+    #[default]
+    Free,
+    /// The focus rides on star A, so star B is seen orbiting it.
+    StarA,
+    StarB,
+    /// The focus sits on the center of mass, keeping both stars in frame.
+    Barycenter,
+}
+
+impl CameraFollow {
+    pub const ALL: [Self; 4] = [Self::Free, Self::StarA, Self::StarB, Self::Barycenter];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Free => "Free",
+            Self::StarA => "Follow Star A",
+            Self::StarB => "Follow Star B",
+            Self::Barycenter => "Barycenter",
         }
     }
 }
@@ -41,36 +143,83 @@ impl Default for BinarySpiralConfig {
 #[derive(Clone, Copy, Default)]
 pub struct Particle {
     pub position: Vec3,
+    pub prev_position: Vec3,
     pub velocity: Vec3,
     pub color: [f32; 3],
     pub life: u32,
     pub active: bool,
 }
 
+/// Fixed-capacity particle storage.
+///
+/// Dead slots are kept on a free list and reused first. When every slot is
+/// alive, emission is refused rather than recycling a live particle, which
+/// would make it vanish mid-flight.
 #[derive(Resource)]
 pub struct ParticlePool {
     pub particles: Vec<Particle>,
-    pub next_index: usize,
+    /// Indices of inactive particles.
+    free: Vec<usize>,
+    /// An emission was refused since the flag was last cleared.
+    pub saturated: bool,
 }
 
 impl Default for ParticlePool {
     fn default() -> Self {
-        Self {
-            particles: vec![Particle::default(); MAX_PARTICLES],
-            next_index: 0,
-        }
+        Self::with_capacity(MAX_PARTICLES)
     }
 }
 
 impl ParticlePool {
-    pub fn emit(&mut self, position: Vec3, velocity: Vec3, color: [f32; 3], life: u32) {
-        let p = &mut self.particles[self.next_index];
-        p.position = position;
-        p.velocity = velocity;
-        p.color = color;
-        p.life = life;
-        p.active = true;
-        self.next_index = (self.next_index + 1) % MAX_PARTICLES;
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            particles: vec![Particle::default(); capacity],
+            // Reversed so slots fill from index 0 upward.
+            free: (0..capacity).rev().collect(),
+            saturated: false,
+        }
+    }
+
+    /// Activates a free particle. Returns `false`, and marks the pool
+    /// saturated, if none is free.
+    pub fn emit(&mut self, position: Vec3, velocity: Vec3, color: [f32; 3], life: u32) -> bool {
+        let Some(index) = self.free.pop() else {
+            self.saturated = true;
+            return false;
+        };
+        self.particles[index] = Particle {
+            position,
+            prev_position: position,
+            velocity,
+            color,
+            life,
+            active: true,
+        };
+        true
+    }
+
+    /// Moves every live particle one frame and frees those whose life ran out.
+    pub fn advance(&mut self) {
+        for (index, p) in self.particles.iter_mut().enumerate() {
+            if p.active {
+                p.prev_position = p.position;
+                p.position += p.velocity;
+                p.life = p.life.saturating_sub(1);
+                if p.life == 0 {
+                    p.active = false;
+                    self.free.push(index);
+                }
+            }
+        }
+    }
+
+    /// Number of particles that can be emitted before the pool is full.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn alive(&self) -> usize {
+        self.particles.len() - self.free.len()
     }
 }
 
@@ -116,3 +265,27 @@ impl RandomDirections {
         self.directions[idx]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_pool_refuses_emission_instead_of_recycling() {
+        let mut pool = ParticlePool::with_capacity(3);
+        assert!(pool.emit(Vec3::ZERO, Vec3::X, [1.0; 3], 5));
+        assert!(pool.emit(Vec3::ZERO, Vec3::X, [1.0; 3], 1));
+        assert!(pool.emit(Vec3::ZERO, Vec3::X, [1.0; 3], 5));
+        assert_eq!(pool.available(), 0);
+
+        assert!(!pool.emit(Vec3::ZERO, Vec3::Y, [0.0; 3], 5));
+        assert!(pool.saturated);
+        assert!(pool.particles.iter().all(|p| p.velocity == Vec3::X), "a live particle was overwritten");
+
+        // The short-lived particle dies and frees its slot.
+        pool.advance();
+        assert_eq!(pool.alive(), 2);
+        assert!(pool.emit(Vec3::ZERO, Vec3::Y, [0.0; 3], 5));
+        assert_eq!(pool.particles[1].velocity, Vec3::Y);
+    }
+}
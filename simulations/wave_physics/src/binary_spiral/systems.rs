@@ -1,13 +1,23 @@
 //! Systems for the Binary Spiral simulation
 
 use bevy::prelude::*;
-use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
 use bevy::render::render_asset::RenderAssetUsages;
+use ez_core::prelude::*;
+use ez_physics::prelude::*;
+use ez_renderer::axes::ShowAxes;
+use ez_renderer::camera::{spawn_orbit_camera, OrbitCamera};
+use ez_renderer::materials::inferno;
+use ez_renderer::viewport::split_viewport_rect;
 use rand::Rng;
 
 use super::components::*;
 use super::resources::*;
-use super::{DEFAULT_ORBIT_RADIUS, MAX_PARTICLES};
+use super::{
+    BARYCENTER_FRAMING, DEFAULT_ORBIT_RADIUS, FLOOR_SIZE, LENSING_LIGHT_SPEED, LENSING_SOFTENING, MAX_PARTICLES, ORBIT_GRAVITY,
+    ORBIT_SOFTENING, ORBIT_SUBSTEPS, POTENTIAL_RESOLUTION, SIMULATION_ID, STARFIELD_DISTANCE, STARFIELD_EXTENT,
+    STARFIELD_RESOLUTION,
+};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Colors
@@ -28,10 +38,33 @@ pub fn setup_scene(
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     // Camera - positioned above looking down at an angle
-    commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 80.0, 60.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
+    let camera = spawn_orbit_camera(
+        &mut commands,
+        OrbitCamera {
+            distance: 100.0,
+            pitch: -0.8_f32.asin(),
+            ..default()
+        },
+    );
+    commands.entity(camera).insert(SimulationEntity(SIMULATION_ID)).with_children(|parent| {
+        // Stereo eyes, switched on by `update_stereo_cameras`; the right one
+        // draws second and must not clear the left one's half.
+        for (order, eye) in [StereoEye::Left, StereoEye::Right].into_iter().enumerate() {
+            parent.spawn((
+                Camera3dBundle {
+                    camera: Camera {
+                        order: order as isize + 1,
+                        is_active: false,
+                        clear_color: if order == 0 { ClearColorConfig::default() } else { ClearColorConfig::None },
+                        ..default()
+                    },
+                    ..default()
+                },
+                eye,
+            ));
+        }
     });
+    commands.insert_resource(ShowAxes { unit: "units" });
 
     // Ambient light
     commands.insert_resource(AmbientLight {
@@ -40,9 +73,8 @@ pub fn setup_scene(
     });
 
     // Grid floor
-    let grid_size = 300.0;
     let grid_divisions = 60;
-    let grid_mesh = create_grid_mesh(grid_size, grid_divisions);
+    let grid_mesh = create_grid_mesh(FLOOR_SIZE, grid_divisions);
     commands.spawn((
         PbrBundle {
             mesh: meshes.add(grid_mesh),
@@ -56,6 +88,42 @@ pub fn setup_scene(
             ..default()
         },
         GridFloor,
+        SimulationEntity(SIMULATION_ID),
+    ));
+
+    // Potential heatmap, just below the grid lines
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(create_floor_mesh(FLOOR_SIZE, POTENTIAL_RESOLUTION)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgba(1.0, 1.0, 1.0, 0.6),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            transform: Transform::from_xyz(0.0, -0.2, 0.0),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        PotentialFloor,
+        SimulationEntity(SIMULATION_ID),
+    ));
+
+    // Lensed background starfield, placed each frame
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(create_starfield_mesh()),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                alpha_mode: AlphaMode::Add,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        LensedStarfield,
+        SimulationEntity(SIMULATION_ID),
     ));
 
     // Orbit ring
@@ -72,6 +140,7 @@ pub fn setup_scene(
             ..default()
         },
         OrbitRing,
+        SimulationEntity(SIMULATION_ID),
     ));
 
     // Source A (Purple)
@@ -108,6 +177,24 @@ pub fn setup_scene(
             ..default()
         },
         ParticleCloud,
+        SimulationEntity(SIMULATION_ID),
+    ));
+
+    // Particle trails (shown instead of the point cloud when enabled)
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(create_trail_mesh()),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                alpha_mode: AlphaMode::Add,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ParticleTrails,
+        SimulationEntity(SIMULATION_ID),
     ));
 
     info!("Binary Spiral simulation initialized");
@@ -138,6 +225,7 @@ fn spawn_orbital_source(
                 ..default()
             },
             source,
+            SimulationEntity(SIMULATION_ID),
         ))
         .with_children(|parent| {
             // Glow sphere (larger, transparent)
@@ -181,6 +269,36 @@ fn create_grid_mesh(size: f32, divisions: u32) -> Mesh {
     mesh
 }
 
+/// Flat `resolution`² vertex grid with per-vertex colors, row-major from −x, −z.
+fn create_floor_mesh(size: f32, resolution: usize) -> Mesh {
+    let step = size / (resolution - 1) as f32;
+    let mut positions = Vec::with_capacity(resolution * resolution);
+    for row in 0..resolution {
+        for col in 0..resolution {
+            positions.push([-size / 2.0 + col as f32 * step, 0.0, -size / 2.0 + row as f32 * step]);
+        }
+    }
+
+    let mut indices = Vec::with_capacity((resolution - 1) * (resolution - 1) * 6);
+    for row in 0..resolution as u32 - 1 {
+        for col in 0..resolution as u32 - 1 {
+            let i = row * resolution as u32 + col;
+            let below = i + resolution as u32;
+            indices.extend_from_slice(&[i, below, i + 1, i + 1, below, below + 1]);
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 1.0, 0.0]; resolution * resolution]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[0.0, 0.0, 0.0, 1.0]; resolution * resolution]);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
 fn create_ring_mesh(radius: f32, segments: u32) -> Mesh {
     let mut positions = Vec::new();
 
@@ -212,6 +330,70 @@ fn create_particle_mesh() -> Mesh {
     mesh
 }
 
+fn create_starfield_mesh() -> Mesh {
+    let count = STARFIELD_RESOLUTION * STARFIELD_RESOLUTION;
+    // Each star's brightness is fixed; a hash of its index keeps the grid
+    // from looking uniform.
+    let colors: Vec<[f32; 4]> = (0..count)
+        .map(|i| {
+            let brightness = 0.3 + 0.7 * ((i as u32).wrapping_mul(2_654_435_761) >> 24) as f32 / 255.0;
+            [brightness, brightness, brightness, 1.0]
+        })
+        .collect();
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::PointList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[99999.0, 99999.0, 99999.0]; count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+fn create_trail_mesh() -> Mesh {
+    let positions: Vec<[f32; 3]> = vec![[99999.0, 99999.0, 99999.0]; MAX_PARTICLES * 2];
+    let colors: Vec<[f32; 4]> = vec![[1.0, 1.0, 1.0, 0.0]; MAX_PARTICLES * 2];
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::LineList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Stereo
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Switches between the orbit camera and the stereo eye pair, spacing the
+/// eyes and giving each its half of the window.
+///
+/// The eyes look parallel rather than toeing in, so there is no vertical
+/// disparity at the edges; the scene's center sits slightly in front of the
+/// screen plane.
+pub fn update_stereo_cameras(
+    config: Res<BinarySpiralConfig>,
+    windows: Query<&Window>,
+    mut main_cameras: Query<&mut Camera, (With<OrbitCamera>, Without<StereoEye>)>,
+    mut eyes: Query<(&mut Camera, &mut Transform, &StereoEye)>,
+) {
+    let Ok(window) = windows.get_single() else { return };
+    let stereo = config.stereo != StereoMode::Off;
+    for mut camera in main_cameras.iter_mut() {
+        camera.is_active = !stereo;
+    }
+    for (mut camera, mut transform, &eye) in eyes.iter_mut() {
+        camera.is_active = stereo;
+        camera.viewport = config
+            .stereo
+            .viewport_side(eye)
+            .map(|side| split_viewport_rect(window.physical_size(), 0.5, side));
+        transform.translation = Vec3::X * eye.sign() * config.eye_separation / 2.0;
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Mouse Input
 // ══════════════════════════════════════════════════════════════════════════════
@@ -219,7 +401,7 @@ fn create_particle_mesh() -> Mesh {
 pub fn handle_mouse_input(
     buttons: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
-    cameras: Query<(&Camera, &GlobalTransform)>,
+    cameras: Query<(&Camera, &GlobalTransform), With<OrbitCamera>>,
     sources: Query<(&OrbitalSource, &GlobalTransform)>,
     mut drag_state: ResMut<DragState>,
 ) {
@@ -264,6 +446,7 @@ pub fn update_orbital_sources(
     time: Res<Time>,
     config: Res<BinarySpiralConfig>,
     drag_state: Res<DragState>,
+    mut was_physical: Local<bool>,
     mut sources: Query<(&mut OrbitalSource, &mut Transform)>,
     mut ring_query: Query<&mut Transform, (With<OrbitRing>, Without<OrbitalSource>)>,
 ) {
@@ -274,17 +457,31 @@ pub fn update_orbital_sources(
     let dt = time.delta_seconds();
     let mut max_radius = DEFAULT_ORBIT_RADIUS;
 
-    for (mut source, mut transform) in sources.iter_mut() {
-        // Handle dragging
-        if drag_state.dragging_source == Some(source.index) {
-            let dist = (drag_state.drag_target.x.powi(2) + drag_state.drag_target.z.powi(2)).sqrt();
-            source.radius += (dist - source.radius) * 0.2;
-            source.radius = source.radius.max(5.0);
+    if config.physical_orbit {
+        let mut bodies: Vec<_> = sources.iter_mut().collect();
+        bodies.sort_by_key(|(source, _)| source.index);
+        if let [(a, _), (b, _)] = bodies.as_mut_slice() {
+            if !*was_physical {
+                reset_physical_orbit(&config, a, b);
+            }
+            step_physical_orbit(&config, &drag_state, a, b, dt);
         }
+    } else {
+        for (mut source, _) in sources.iter_mut() {
+            // Handle dragging
+            if drag_state.dragging_source == Some(source.index) {
+                let dist = (drag_state.drag_target.x.powi(2) + drag_state.drag_target.z.powi(2)).sqrt();
+                source.radius += (dist - source.radius) * 0.2;
+                source.radius = source.radius.max(5.0);
+            }
 
-        // Update angle
-        source.angle += config.orbit_speed * dt;
+            // Update angle
+            source.angle += config.orbit_speed * dt;
+        }
+    }
+    *was_physical = config.physical_orbit;
 
+    for (mut source, mut transform) in sources.iter_mut() {
         // Calculate new position
         let new_pos = source.current_position();
 
@@ -305,13 +502,133 @@ pub fn update_orbital_sources(
     }
 }
 
+/// Mass-weighted center of the two stars. Circular orbits have no masses, so
+/// there both weigh the same.
+pub fn barycenter(config: &BinarySpiralConfig, a: Vec3, b: Vec3) -> Vec3 {
+    if config.physical_orbit {
+        (a * config.mass_a + b * config.mass_b) / (config.mass_a + config.mass_b)
+    } else {
+        (a + b) / 2.0
+    }
+}
+
+/// Moves the camera focus onto the star or barycenter chosen by
+/// [`BinarySpiralConfig::camera_follow`].
+///
+/// Switching modes eases over like any other camera move, but from then on
+/// the view is carried along with the followed point, so it stays fixed in
+/// frame instead of trailing behind the smoothing.
+pub fn follow_camera_target(
+    config: Res<BinarySpiralConfig>,
+    sources: Query<(&OrbitalSource, &Transform)>,
+    mut cameras: Query<&mut OrbitCamera>,
+    mut followed: Local<Option<(CameraFollow, Vec3)>>,
+) {
+    let mut stars: Vec<_> = sources.iter().collect();
+    stars.sort_by_key(|(source, _)| source.index);
+    let [(_, a), (_, b)] = stars.as_slice() else { return };
+    let (a, b) = (a.translation, b.translation);
+
+    let point = match config.camera_follow {
+        CameraFollow::Free => {
+            *followed = None;
+            return;
+        }
+        CameraFollow::StarA => a,
+        CameraFollow::StarB => b,
+        CameraFollow::Barycenter => barycenter(&config, a, b),
+    };
+    let moved = match *followed {
+        Some((mode, last)) if mode == config.camera_follow => point - last,
+        _ => Vec3::ZERO,
+    };
+    *followed = Some((config.camera_follow, point));
+
+    for mut camera in cameras.iter_mut() {
+        camera.focus = point;
+        if config.camera_follow == CameraFollow::Barycenter {
+            camera.distance = camera.distance.max(a.distance(b) * BARYCENTER_FRAMING);
+        }
+        if let Some(current) = camera.current.as_mut() {
+            current.focus += moved;
+        }
+    }
+}
+
+/// Places both stars on a circular orbit about their barycenter (at the
+/// origin) with the configured masses and initial separation.
+pub fn reset_physical_orbit(
+    config: &BinarySpiralConfig,
+    a: &mut OrbitalSource,
+    b: &mut OrbitalSource,
+) {
+    let total_mass = config.mass_a + config.mass_b;
+    let separation = config.initial_separation;
+    let relative_speed = (ORBIT_GRAVITY * total_mass / separation).sqrt();
+
+    a.set_position(Vec3::X * separation * config.mass_b / total_mass);
+    b.set_position(Vec3::NEG_X * separation * config.mass_a / total_mass);
+    a.orbit_velocity = Vec3::Z * relative_speed * config.mass_b / total_mass;
+    b.orbit_velocity = Vec3::NEG_Z * relative_speed * config.mass_a / total_mass;
+}
+
+/// Advances the two-body system by `dt` using semi-implicit Euler.
+///
+/// A dragged star is pinned to the cursor and given the circular-orbit
+/// velocity relative to its companion, so releasing it starts a new (usually
+/// eccentric) orbit. The center-of-mass velocity is removed afterwards so the
+/// pair does not drift out of view.
+fn step_physical_orbit(
+    config: &BinarySpiralConfig,
+    drag_state: &DragState,
+    a: &mut OrbitalSource,
+    b: &mut OrbitalSource,
+    dt: f32,
+) {
+    let total_mass = config.mass_a + config.mass_b;
+
+    if let Some(index) = drag_state.dragging_source {
+        let (dragged, companion) = if index == a.index { (&mut *a, &*b) } else { (&mut *b, &*a) };
+        let target = Vec3::new(drag_state.drag_target.x, 0.0, drag_state.drag_target.z);
+        let offset = target - companion.current_position();
+        let separation = offset.length().max(5.0);
+        let tangent = offset.cross(Vec3::Y).normalize_or_zero();
+        dragged.set_position(target);
+        dragged.orbit_velocity =
+            companion.orbit_velocity + tangent * (ORBIT_GRAVITY * total_mass / separation).sqrt();
+
+        let momentum = a.orbit_velocity * config.mass_a + b.orbit_velocity * config.mass_b;
+        let com_velocity = momentum / total_mass;
+        a.orbit_velocity -= com_velocity;
+        b.orbit_velocity -= com_velocity;
+        return;
+    }
+
+    let sub_dt = dt / ORBIT_SUBSTEPS as f32;
+    for _ in 0..ORBIT_SUBSTEPS {
+        let mut pos_a = a.current_position();
+        let mut pos_b = b.current_position();
+        let force =
+            gravitational_force_softened(config.mass_a, config.mass_b, pos_a, pos_b, ORBIT_GRAVITY, ORBIT_SOFTENING);
+
+        semi_implicit_euler(&mut pos_a, &mut a.orbit_velocity, force / config.mass_a, sub_dt);
+        semi_implicit_euler(&mut pos_b, &mut b.orbit_velocity, -force / config.mass_b, sub_dt);
+
+        a.set_position(pos_a);
+        b.set_position(pos_b);
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Particle Emission
 // ══════════════════════════════════════════════════════════════════════════════
 
+/// Emits each star's particles for this frame, thinned by adaptive quality
+/// on slow devices.
 pub fn emit_particles(
     config: Res<BinarySpiralConfig>,
-    sources: Query<&OrbitalSource>,
+    quality: Res<AdaptiveQuality>,
+    mut sources: Query<&mut OrbitalSource>,
     random_dirs: Res<RandomDirections>,
     mut pool: ResMut<ParticlePool>,
 ) {
@@ -320,8 +637,22 @@ pub fn emit_particles(
     }
 
     let mut rng = rand::thread_rng();
+    pool.saturated = false;
+
+    for mut source in sources.iter_mut() {
+        let (count, max_jitter) = match config.emission_mode {
+            EmissionMode::Continuous => (config.emission_rate, 1.5),
+            EmissionMode::Burst { interval, count } => {
+                if source.frames_to_burst > 0 {
+                    source.frames_to_burst -= 1;
+                    continue;
+                }
+                source.frames_to_burst = interval.saturating_sub(1);
+                (count, 0.0)
+            }
+        };
+        let count = quality.scale(count);
 
-    for source in sources.iter() {
         let vel_dir = source.velocity.normalize_or_zero();
         let speed = source.velocity.length();
         let intensity = (speed / 3.0).min(1.0);
@@ -330,10 +661,10 @@ pub fn emit_particles(
         let base_g = source.base_color.to_srgba().green;
         let base_b = source.base_color.to_srgba().blue;
 
-        for _ in 0..config.emission_rate {
+        for _ in 0..count {
             // Spherical jitter for initial position
             let jitter_dir = random_dirs.get(&mut rng);
-            let jitter_r: f32 = rng.gen_range(0.0..1.5);
+            let jitter_r = rng.gen::<f32>() * max_jitter;
             let jitter = jitter_dir * jitter_r;
 
             let pos = source.current_position() + jitter;
@@ -360,7 +691,9 @@ pub fn emit_particles(
                 color[2] = lerp(color[2], COLOR_BACK[2], t);
             }
 
-            pool.emit(pos, vel, color, config.particle_life);
+            if !pool.emit(pos, vel, color, config.particle_life) {
+                break;
+            }
         }
     }
 }
@@ -379,29 +712,43 @@ pub fn update_particles(config: Res<BinarySpiralConfig>, mut pool: ResMut<Partic
         return;
     }
 
-    for p in pool.particles.iter_mut() {
-        if p.active {
-            p.position += p.velocity;
-            p.life = p.life.saturating_sub(1);
-            if p.life == 0 {
-                p.active = false;
-            }
-        }
-    }
+    pool.advance();
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Sync Particle Mesh
 // ══════════════════════════════════════════════════════════════════════════════
 
+type ParticleMeshQuery<'w, 's, F> = Query<'w, 's, (&'static Handle<Mesh>, &'static mut Visibility), F>;
+
 pub fn sync_particle_mesh(
+    config: Res<BinarySpiralConfig>,
     pool: Res<ParticlePool>,
     mut meshes: ResMut<Assets<Mesh>>,
-    cloud_query: Query<&Handle<Mesh>, With<ParticleCloud>>,
+    mut cloud_query: ParticleMeshQuery<With<ParticleCloud>>,
+    mut trail_query: ParticleMeshQuery<(With<ParticleTrails>, Without<ParticleCloud>)>,
 ) {
-    let Ok(mesh_handle) = cloud_query.get_single() else { return };
-    let Some(mesh) = meshes.get_mut(mesh_handle) else { return };
+    let Ok((cloud_handle, mut cloud_visibility)) = cloud_query.get_single_mut() else { return };
+    let Ok((trail_handle, mut trail_visibility)) = trail_query.get_single_mut() else { return };
+
+    let (visible, hidden) = if config.show_trails {
+        (&mut trail_visibility, &mut cloud_visibility)
+    } else {
+        (&mut cloud_visibility, &mut trail_visibility)
+    };
+    visible.set_if_neq(Visibility::Inherited);
+    hidden.set_if_neq(Visibility::Hidden);
+
+    if config.show_trails {
+        if let Some(mesh) = meshes.get_mut(trail_handle) {
+            write_trail_mesh(mesh, &pool, config.particle_life);
+        }
+    } else if let Some(mesh) = meshes.get_mut(cloud_handle) {
+        write_point_mesh(mesh, &pool);
+    }
+}
 
+fn write_point_mesh(mesh: &mut Mesh, pool: &ParticlePool) {
     // Update positions
     if let Some(VertexAttributeValues::Float32x3(positions)) =
         mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
@@ -426,3 +773,186 @@ pub fn sync_particle_mesh(
         }
     }
 }
+
+/// Writes one segment per particle, from its previous to current position,
+/// fading with remaining life.
+fn write_trail_mesh(mesh: &mut Mesh, pool: &ParticlePool, particle_life: u32) {
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for (i, p) in pool.particles.iter().enumerate() {
+            if p.active {
+                positions[i * 2] = p.prev_position.to_array();
+                positions[i * 2 + 1] = p.position.to_array();
+            } else {
+                positions[i * 2] = [99999.0, 99999.0, 99999.0];
+                positions[i * 2 + 1] = [99999.0, 99999.0, 99999.0];
+            }
+        }
+    }
+
+    if let Some(VertexAttributeValues::Float32x4(colors)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+    {
+        let max_life = particle_life.max(1) as f32;
+        for (i, p) in pool.particles.iter().enumerate() {
+            if p.active {
+                let alpha = 0.8 * (p.life as f32 / max_life).min(1.0);
+                let [r, g, b] = p.color;
+                colors[i * 2] = [r, g, b, alpha * 0.25];
+                colors[i * 2 + 1] = [r, g, b, alpha];
+            }
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Potential Floor
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Colors the floor by the stars' softened gravitational potential.
+///
+/// The well depth is shown on a log scale, so the broad 1/r slope far from
+/// the stars is visible alongside the deep wells at their centers. Uses the
+/// configured masses in both orbit modes.
+pub fn update_potential_floor(
+    config: Res<BinarySpiralConfig>,
+    sources: Query<&OrbitalSource>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut floor: Query<(&Handle<Mesh>, &mut Visibility), With<PotentialFloor>>,
+) {
+    let Ok((handle, mut visibility)) = floor.get_single_mut() else { return };
+    let shown = if config.show_potential { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != shown {
+        *visibility = shown;
+    }
+    if !config.show_potential {
+        return;
+    }
+
+    let bodies: Vec<(Vec3, f32)> = sources
+        .iter()
+        .map(|source| (source.current_position(), if source.index == 0 { config.mass_a } else { config.mass_b }))
+        .collect();
+    let step = FLOOR_SIZE / (POTENTIAL_RESOLUTION - 1) as f32;
+    let depths: Vec<f32> = (0..POTENTIAL_RESOLUTION * POTENTIAL_RESOLUTION)
+        .map(|i| {
+            let (row, col) = (i / POTENTIAL_RESOLUTION, i % POTENTIAL_RESOLUTION);
+            let point = Vec3::new(-FLOOR_SIZE / 2.0 + col as f32 * step, 0.0, -FLOOR_SIZE / 2.0 + row as f32 * step);
+            let potential = gravitational_potential_softened(point, &bodies, ORBIT_GRAVITY, ORBIT_SOFTENING);
+            (-potential).max(f32::MIN_POSITIVE).ln()
+        })
+        .collect();
+
+    let (shallowest, deepest) = depths.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &d| (lo.min(d), hi.max(d)));
+    let range = (deepest - shallowest).max(f32::EPSILON);
+
+    let Some(mesh) = meshes.get_mut(handle) else { return };
+    if let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+        for (color, depth) in colors.iter_mut().zip(&depths) {
+            *color = inferno((depth - shallowest) / range).to_linear().to_f32_array();
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Gravitational Lensing
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Apparent position of a background star at `star` seen from `eye` past
+/// point masses `lenses` (position, mass), to first order in the weak-field
+/// deflection angle 4GM/(c²b).
+///
+/// Each lens between the eye and the star bends the straight ray at its
+/// depth by that angle, and the bend carried the rest of the way to the
+/// star pushes the image away from the lens. Lenses add up independently.
+pub fn lensed_position(eye: Vec3, star: Vec3, lenses: &[(Vec3, f32)]) -> Vec3 {
+    let Some(direction) = (star - eye).try_normalize() else { return star };
+    let star_depth = (star - eye).dot(direction);
+    let schwarzschild_scale = 4.0 * ORBIT_GRAVITY / (LENSING_LIGHT_SPEED * LENSING_LIGHT_SPEED);
+
+    lenses
+        .iter()
+        .filter_map(|&(position, mass)| {
+            let lens_depth = (position - eye).dot(direction);
+            if lens_depth <= 0.0 || lens_depth >= star_depth {
+                return None;
+            }
+            let impact = eye + direction * lens_depth - position;
+            let b = impact.length();
+            let deflection = schwarzschild_scale * mass * b / (b * b + LENSING_SOFTENING * LENSING_SOFTENING);
+            Some(impact.normalize_or_zero() * deflection * (star_depth - lens_depth))
+        })
+        .fold(star, |image, shift| image + shift)
+}
+
+/// Lays a grid of background stars on a plane [`STARFIELD_DISTANCE`]
+/// behind the stars, facing the camera, and draws each where the binary's
+/// lensing makes it appear.
+///
+/// The grid turns with the camera so it always fills the background.
+pub fn update_lensed_starfield(
+    config: Res<BinarySpiralConfig>,
+    sources: Query<&OrbitalSource>,
+    cameras: Query<&Transform, With<OrbitCamera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut starfield: Query<(&Handle<Mesh>, &mut Visibility), With<LensedStarfield>>,
+) {
+    let Ok((handle, mut visibility)) = starfield.get_single_mut() else { return };
+    let shown = if config.show_lensing { Visibility::Inherited } else { Visibility::Hidden };
+    visibility.set_if_neq(shown);
+    if !config.show_lensing {
+        return;
+    }
+    let Ok(camera) = cameras.get_single() else { return };
+
+    let lenses: Vec<(Vec3, f32)> = sources
+        .iter()
+        .map(|source| (source.current_position(), if source.index == 0 { config.mass_a } else { config.mass_b }))
+        .collect();
+    let eye = camera.translation;
+    let (forward, right, up) = (*camera.forward(), *camera.right(), *camera.up());
+    let nearest = lenses.iter().map(|(position, _)| (*position - eye).dot(forward)).fold(0.0, f32::max);
+    let depth = nearest + STARFIELD_DISTANCE;
+    let step = 2.0 * STARFIELD_EXTENT * depth / (STARFIELD_RESOLUTION - 1) as f32;
+    let corner = eye + forward * depth - (right + up) * STARFIELD_EXTENT * depth;
+
+    let Some(mesh) = meshes.get_mut(handle) else { return };
+    if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+        for (i, position) in positions.iter_mut().enumerate() {
+            let (row, col) = (i / STARFIELD_RESOLUTION, i % STARFIELD_RESOLUTION);
+            let star = corner + (right * col as f32 + up * row as f32) * step;
+            *position = lensed_position(eye, star, &lenses).to_array();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lensing_pushes_images_away_from_the_mass_by_4gm_over_c2b() {
+        let eye = Vec3::new(0.0, 0.0, -100.0);
+        let lens = [(Vec3::ZERO, 100.0)];
+        let scale = 4.0 * ORBIT_GRAVITY * 100.0 / (LENSING_LIGHT_SPEED * LENSING_LIGHT_SPEED);
+
+        // A star behind the lens 20 units off axis: the ray passes the lens at
+        // b ≈ 10 and travels about another 100 units.
+        let image = lensed_position(eye, Vec3::new(20.0, 0.0, 100.0), &lens);
+        let b = 10.0;
+        let expected = scale * b / (b * b + LENSING_SOFTENING * LENSING_SOFTENING) * 100.0;
+        let shift = image.x - 20.0;
+        assert!((shift / expected - 1.0).abs() < 0.02, "shift {shift} vs {expected}");
+        assert!(image.y == 0.0);
+
+        // Stars on the near side, or with no lens between, are unmoved.
+        let near = Vec3::new(20.0, 0.0, -50.0);
+        assert_eq!(lensed_position(eye, near, &lens), near);
+
+        // Equal masses either side of the line of sight cancel.
+        let pair = [(Vec3::new(-10.0, 0.0, 0.0), 100.0), (Vec3::new(10.0, 0.0, 0.0), 100.0)];
+        let behind = Vec3::new(0.0, 0.0, 100.0);
+        assert!(lensed_position(eye, behind, &pair).distance(behind) < 1e-4);
+    }
+}
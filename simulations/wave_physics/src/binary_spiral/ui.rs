@@ -2,9 +2,15 @@
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
+use ez_core::quality::AdaptiveQuality;
+use ez_core::units::format_si;
+use ez_renderer::camera::OrbitCamera;
+use ez_ui::widgets::adaptive_quality_controls;
 
+use super::components::*;
 use super::resources::*;
-use super::ParticlePool;
+use super::systems::reset_physical_orbit;
+use super::{ParticlePool, DEFAULT_BURST_COUNT, DEFAULT_BURST_INTERVAL};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // UI Rendering
@@ -14,6 +20,9 @@ pub fn render_ui(
     mut contexts: EguiContexts,
     mut config: ResMut<BinarySpiralConfig>,
     pool: Res<ParticlePool>,
+    mut quality: ResMut<AdaptiveQuality>,
+    mut sources: Query<&mut OrbitalSource>,
+    mut cameras: Query<&mut OrbitCamera>,
 ) {
     let ctx = contexts.ctx_mut();
 
@@ -29,7 +38,7 @@ pub fn render_ui(
                 ui.colored_label(egui::Color32::from_rgb(255, 221, 0), "📖 Instructions");
                 ui.label("• Brightness = Particle Density");
                 ui.label("• Drag stars to change orbit radius");
-                ui.label("• Use mouse to rotate view");
+                ui.label("• In physical mode, drag a star to start a new orbit");
             });
 
             ui.add_space(10.0);
@@ -49,30 +58,91 @@ pub fn render_ui(
 
             ui.add_space(5.0);
 
-            // Orbit Speed
-            ui.horizontal(|ui| {
-                ui.label("Orbit Speed");
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!("{:.1}", config.orbit_speed));
+            ui.checkbox(&mut config.physical_orbit, "Physical Orbit (two-body gravity)");
+
+            ui.add_space(5.0);
+
+            if config.physical_orbit {
+                // Masses
+                ui.horizontal(|ui| {
+                    ui.label("Mass A");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{:.0}", config.mass_a));
+                    });
                 });
-            });
-            ui.add(egui::Slider::new(&mut config.orbit_speed, 0.1..=4.0).show_value(false));
+                ui.add(egui::Slider::new(&mut config.mass_a, 10.0..=500.0).show_value(false));
+
+                ui.horizontal(|ui| {
+                    ui.label("Mass B");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{:.0}", config.mass_b));
+                    });
+                });
+                ui.add(egui::Slider::new(&mut config.mass_b, 10.0..=500.0).show_value(false));
+
+                // Initial Separation
+                ui.horizontal(|ui| {
+                    ui.label("Initial Separation");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{:.0}", config.initial_separation));
+                    });
+                });
+                ui.add(egui::Slider::new(&mut config.initial_separation, 10.0..=100.0).show_value(false));
+
+                if ui.button("↺ Reset Orbit").clicked() {
+                    let mut bodies: Vec<_> = sources.iter_mut().collect();
+                    bodies.sort_by_key(|source| source.index);
+                    if let [a, b] = bodies.as_mut_slice() {
+                        reset_physical_orbit(&config, a, b);
+                    }
+                }
+            } else {
+                // Orbit Speed
+                ui.horizontal(|ui| {
+                    ui.label("Orbit Speed");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(format!("{:.1}", config.orbit_speed));
+                    });
+                });
+                ui.add(egui::Slider::new(&mut config.orbit_speed, 0.1..=4.0).show_value(false));
+            }
 
             ui.add_space(5.0);
 
-            // Emission Rate
-            let mut emission_f32 = config.emission_rate as f32;
+            // Emission
             ui.horizontal(|ui| {
-                ui.label("Emission Rate");
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!("{}", config.emission_rate));
-                });
+                ui.label("Emission");
+                let burst = EmissionMode::Burst { interval: DEFAULT_BURST_INTERVAL, count: DEFAULT_BURST_COUNT };
+                let is_burst = matches!(config.emission_mode, EmissionMode::Burst { .. });
+                if ui.selectable_label(!is_burst, "Continuous").clicked() {
+                    config.emission_mode = EmissionMode::Continuous;
+                }
+                if ui.selectable_label(is_burst, "Burst").clicked() && !is_burst {
+                    config.emission_mode = burst;
+                }
             });
-            if ui
-                .add(egui::Slider::new(&mut emission_f32, 200.0..=2000.0).show_value(false))
-                .changed()
-            {
-                config.emission_rate = emission_f32 as usize;
+
+            match config.emission_mode {
+                EmissionMode::Continuous => {
+                    let mut emission_f32 = config.emission_rate as f32;
+                    ui.horizontal(|ui| {
+                        ui.label("Emission Rate");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(format!("{}", config.emission_rate));
+                        });
+                    });
+                    if ui
+                        .add(egui::Slider::new(&mut emission_f32, 200.0..=2000.0).show_value(false))
+                        .changed()
+                    {
+                        config.emission_rate = emission_f32 as usize;
+                    }
+                }
+                EmissionMode::Burst { mut interval, mut count } => {
+                    ui.add(egui::Slider::new(&mut interval, 5..=120).text("Interval (frames)"));
+                    ui.add(egui::Slider::new(&mut count, 500..=20_000).step_by(500.0).text("Burst Size"));
+                    config.emission_mode = EmissionMode::Burst { interval, count };
+                }
             }
 
             ui.add_space(5.0);
@@ -93,14 +163,65 @@ pub fn render_ui(
             ui.heading("Display");
             ui.checkbox(&mut config.show_grid, "Show Grid");
             ui.checkbox(&mut config.show_orbit_ring, "Show Orbit Ring");
+            ui.checkbox(&mut config.show_trails, "Show Trails");
+            ui.checkbox(&mut config.show_potential, "Show Potential Φ")
+                .on_hover_text("Color the floor by gravitational potential (log depth, brighter is deeper)");
+            ui.checkbox(&mut config.show_lensing, "Show Lensing")
+                .on_hover_text("Bend a background starfield by each star's deflection 4GM/(c²b)");
+
+            ui.horizontal(|ui| {
+                ui.label("Stereo 3D");
+                egui::ComboBox::from_id_source("stereo_mode")
+                    .selected_text(config.stereo.label())
+                    .show_ui(ui, |ui| {
+                        for mode in StereoMode::ALL {
+                            ui.selectable_value(&mut config.stereo, mode, mode.label());
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Two views side by side; relax your eyes (parallel) or cross them until the views merge");
+            if config.stereo != StereoMode::Off {
+                ui.add(egui::Slider::new(&mut config.eye_separation, 0.5..=10.0).text("Eye Separation"))
+                    .on_hover_text("Wider exaggerates depth but is harder to fuse");
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Camera:");
+                egui::ComboBox::from_id_source("camera_follow")
+                    .selected_text(config.camera_follow.label())
+                    .show_ui(ui, |ui| {
+                        for mode in CameraFollow::ALL {
+                            ui.selectable_value(&mut config.camera_follow, mode, mode.label());
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Ride along with one star to watch its companion's relative orbit");
+
+            if let Ok(mut camera) = cameras.get_single_mut() {
+                ui.checkbox(&mut camera.auto_rotate, "Auto-Rotate (demo mode)");
+                if camera.auto_rotate {
+                    ui.add(
+                        egui::Slider::new(&mut camera.auto_rotate_speed, 0.05..=1.0)
+                            .text("Rotate Speed (rad/s)"),
+                    );
+                }
+            }
 
             ui.add_space(10.0);
             ui.separator();
 
             // Statistics
             ui.heading("Statistics");
-            let active_count = pool.particles.iter().filter(|p| p.active).count();
-            ui.label(format!("Active Particles: {}", active_count));
+            ui.label(format!("Active Particles: {}", format_si(pool.alive() as f64)));
+            if pool.saturated {
+                ui.colored_label(egui::Color32::from_rgb(255, 140, 60), "⚠ Pool saturated")
+                    .on_hover_text("Every particle slot is in use, so some emission is skipped; lower the rate or lifetime");
+            } else {
+                ui.small(format!("Free slots: {}", pool.available()));
+            }
+            adaptive_quality_controls(ui, &mut quality);
 
             ui.add_space(10.0);
 
@@ -0,0 +1,134 @@
+//! Boundary conditions for the FDTD solver
+//!
+//! The stencil only updates interior cells; after each step the tank's
+//! [`BoundaryCondition`] fills in the one-cell border around them. The
+//! built-in conditions are listed in [`BoundaryMode`], which the UI and the
+//! saved settings refer to them by.
+
+use super::resources::BoundaryMode;
+
+/// What happens to waves at the edges of the tank.
+pub trait BoundaryCondition: Send + Sync + 'static {
+    /// Display name; for the built-ins, their [`BoundaryMode::label`].
+    fn name(&self) -> &'static str;
+
+    /// Sets the border cells of a freshly stepped row-major `width` ×
+    /// `height` field from its interior.
+    fn apply(&self, next: &mut [f32], width: usize, height: usize);
+
+    /// Whether the tank's absorbing layer (`pml_width`) is in effect.
+    fn absorbs(&self) -> bool {
+        false
+    }
+}
+
+/// Edges held at zero inside a damping layer, so outgoing waves fade as in
+/// an open tank.
+pub struct Absorbing;
+
+impl BoundaryCondition for Absorbing {
+    fn name(&self) -> &'static str {
+        BoundaryMode::Absorbing.label()
+    }
+
+    fn apply(&self, next: &mut [f32], width: usize, height: usize) {
+        next[..width].fill(0.0);
+        next[(height - 1) * width..].fill(0.0);
+        for row in next.chunks_mut(width) {
+            row[0] = 0.0;
+            row[width - 1] = 0.0;
+        }
+    }
+
+    fn absorbs(&self) -> bool {
+        true
+    }
+}
+
+/// Hard walls: each edge cell copies its inward neighbour (a zero-gradient
+/// Neumann boundary), so waves reflect without inverting.
+pub struct Reflecting;
+
+impl BoundaryCondition for Reflecting {
+    fn name(&self) -> &'static str {
+        BoundaryMode::Reflecting.label()
+    }
+
+    fn apply(&self, next: &mut [f32], width: usize, height: usize) {
+        for row in next.chunks_mut(width) {
+            row[0] = row[1];
+            row[width - 1] = row[width - 2];
+        }
+        next.copy_within(width..2 * width, 0);
+        next.copy_within((height - 2) * width..(height - 1) * width, (height - 1) * width);
+    }
+}
+
+/// Opposite edges joined, so a wave leaving one side comes back in on the
+/// other, as on a torus. Each border cell mirrors the interior cell next
+/// to the opposite border.
+pub struct Periodic;
+
+impl BoundaryCondition for Periodic {
+    fn name(&self) -> &'static str {
+        BoundaryMode::Periodic.label()
+    }
+
+    fn apply(&self, next: &mut [f32], width: usize, height: usize) {
+        for row in next.chunks_mut(width) {
+            row[0] = row[width - 2];
+            row[width - 1] = row[1];
+        }
+        next.copy_within((height - 2) * width..(height - 1) * width, 0);
+        next.copy_within(width..2 * width, (height - 1) * width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::resources::WaveField;
+    use super::*;
+
+    /// A 4 × 3 field with distinct values, 0 to 11 in row-major order.
+    fn ramp() -> Vec<f32> {
+        (0..12).map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn test_each_boundary_against_a_known_field() {
+        let mut field = ramp();
+        Absorbing.apply(&mut field, 4, 3);
+        assert_eq!(field, [0.0, 0.0, 0.0, 0.0, 0.0, 5.0, 6.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+        let mut field = ramp();
+        Reflecting.apply(&mut field, 4, 3);
+        assert_eq!(field, [5.0, 5.0, 6.0, 6.0, 5.0, 5.0, 6.0, 6.0, 5.0, 5.0, 6.0, 6.0]);
+
+        let mut field = ramp();
+        Periodic.apply(&mut field, 4, 3);
+        assert_eq!(field, [6.0, 5.0, 6.0, 5.0, 6.0, 5.0, 6.0, 5.0, 6.0, 5.0, 6.0, 5.0]);
+    }
+
+    #[test]
+    fn test_periodic_pulse_wraps_around() {
+        // A pulse near the right edge, moving right, with no damping.
+        let mut field = WaveField::new(64, 8);
+        for y in 0..8 {
+            for x in 0..64 {
+                let bump = |center: f32| (-((x as f32 - center) / 3.0).powi(2)).exp();
+                let i = field.idx(x, y);
+                field.previous[i] = bump(55.5);
+                field.current[i] = bump(56.0);
+            }
+        }
+        for _ in 0..30 {
+            field.step(0.25, 1.0, 0, &Periodic);
+        }
+
+        // At half a cell per step it has crossed the seam into the left side.
+        let row = &field.current[4 * 64..5 * 64];
+        let peak = (1..63).max_by(|&a, &b| row[a].total_cmp(&row[b])).expect("interior cells");
+        assert!((4..12).contains(&peak), "pulse peak at x = {peak}");
+        assert!(row[peak] > 0.5);
+    }
+}
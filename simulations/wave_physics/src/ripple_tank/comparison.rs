@@ -0,0 +1,192 @@
+//! Side-by-side comparison of the tank with and without its slits
+//!
+//! With [`UIState::compare_without_slits`] on, the window is split through
+//! [`SplitViewportConfig`]: the left half shows the tank as built, the right
+//! half a second field driven by the same sources with every single and
+//! double slit left out, so the diffraction the slits cause stands out.
+
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use ez_core::registry::SimulationEntity;
+use ez_renderer::camera::Pan2dCamera;
+use ez_renderer::viewport::{SplitCameraKind, SplitViewport, SplitViewportConfig, ViewportSide};
+
+use super::{
+    components::*, physics::*, resources::*, INSPECTOR_PANEL_WIDTH, SIMULATION_ID, TANK_HEIGHT, TANK_WIDTH, TOOLBOX_PANEL_WIDTH,
+    TOP_BAR_HEIGHT,
+};
+
+/// The slit-free field drawn on the right of the comparison, stepped by
+/// [`update_wave_field`] alongside the tank's own.
+#[derive(Resource, Default)]
+pub struct SlitComparison {
+    /// `None` while the comparison is off.
+    pub field: Option<WaveField>,
+}
+
+/// Sprite drawing the [`SlitComparison`] field.
+#[derive(Component)]
+pub struct ComparisonVisual {
+    pub texture: Handle<Image>,
+}
+
+/// A field with the tank's waves and no obstacles, which
+/// [`rasterize_slit_comparison`] stamps before the next step.
+fn copy_waves(wave_field: &WaveField) -> WaveField {
+    let mut field = wave_field.empty_like();
+    field.current.clone_from(&wave_field.current);
+    field.previous.clone_from(&wave_field.previous);
+    field
+}
+
+/// Projection scale that fits the whole tank into one half of a `window`
+/// sized window, less its share of the side panels.
+pub fn comparison_scale(window: Vec2) -> f32 {
+    let half_width = (window.x - (TOOLBOX_PANEL_WIDTH + INSPECTOR_PANEL_WIDTH)) / 2.0;
+    let height = window.y - TOP_BAR_HEIGHT;
+    let padding = 1.1;
+    (TANK_WIDTH / half_width.max(100.0)).max(TANK_HEIGHT / height.max(100.0)) * padding
+}
+
+/// Opens or closes the comparison when the checkbox changes, and keeps
+/// slits on the left half only.
+///
+/// Opening it splits the window with 2D cameras starting where the tank's
+/// own camera is, and starts the slit-free field from the waves already in
+/// the tank.
+#[allow(clippy::too_many_arguments)]
+pub fn sync_slit_comparison(
+    mut commands: Commands,
+    ui_state: Res<UIState>,
+    wave_field: Res<WaveField>,
+    mut comparison: ResMut<SlitComparison>,
+    split: Option<ResMut<SplitViewportConfig>>,
+    windows: Query<&Window>,
+    own_camera: Query<&Transform, (With<Pan2dCamera>, Without<SplitViewport>)>,
+    mut images: ResMut<Assets<Image>>,
+    tank_visuals: Query<Entity, With<WaveFieldVisual>>,
+    comparison_visuals: Query<Entity, With<ComparisonVisual>>,
+    obstacles: Query<(Entity, &Obstacle, Has<ViewportSide>)>,
+) {
+    // Without the split viewport plugin there is nowhere to show it.
+    let Some(mut split) = split else { return };
+    let comparing = ui_state.compare_without_slits;
+
+    if comparing && comparison.field.is_none() {
+        let Ok(window) = windows.get_single() else { return };
+        split.enabled = true;
+        split.cameras = SplitCameraKind::Camera2d { scale: comparison_scale(window.size()) };
+        split.camera_transform = own_camera.get_single().copied().unwrap_or(Transform::from_xyz(0.0, 0.0, 100.0));
+
+        let texture = images.add(wave_field_image(&wave_field));
+        commands.spawn((
+            SpriteBundle {
+                texture: texture.clone(),
+                transform: Transform::from_scale(Vec3::splat(wave_field.cell_size)),
+                ..default()
+            },
+            ComparisonVisual { texture },
+            ViewportSide::Right,
+            SimulationEntity(SIMULATION_ID),
+        ));
+        for entity in tank_visuals.iter() {
+            commands.entity(entity).insert(ViewportSide::Left);
+        }
+        comparison.field = Some(copy_waves(&wave_field));
+    } else if !comparing && comparison.field.is_some() {
+        split.enabled = false;
+        for entity in comparison_visuals.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for entity in tank_visuals.iter() {
+            commands.entity(entity).remove::<(ViewportSide, RenderLayers)>();
+        }
+        comparison.field = None;
+    }
+
+    // Slits placed or retyped while comparing move sides too.
+    for (entity, obstacle, routed) in obstacles.iter() {
+        let left_only = comparing && obstacle.is_slit();
+        if left_only && !routed {
+            commands.entity(entity).insert(ViewportSide::Left);
+        } else if !left_only && routed {
+            commands.entity(entity).remove::<(ViewportSide, RenderLayers)>();
+        }
+    }
+}
+
+/// Stamps every obstacle but the slits into the comparison field.
+///
+/// A new grid size restarts the comparison from the tank's waves, and
+/// clearing the tank clears it too.
+pub fn rasterize_slit_comparison(
+    wave_field: Res<WaveField>,
+    mut comparison: ResMut<SlitComparison>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    masks: Query<(&Transform, &ImageMaskObstacle)>,
+    sources: Query<(&Transform, &WaveSource)>,
+) {
+    let Some(field) = comparison.field.as_mut() else { return };
+    if field.current.len() != wave_field.current.len() {
+        *field = copy_waves(&wave_field);
+    } else if wave_field.current.iter().chain(&wave_field.previous).all(|&value| value == 0.0) {
+        field.clear();
+    }
+    stamp_scene(field, obstacles.iter().filter(|(_, obstacle)| !obstacle.is_slit()), &masks, &sources);
+}
+
+/// Draws the comparison field with the tank's color scheme.
+pub fn update_comparison_visualization(
+    comparison: Res<SlitComparison>,
+    config: Res<RippleTankConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut visuals: Query<(&mut Transform, &ComparisonVisual)>,
+) {
+    let (Some(field), Ok((mut transform, visual))) = (comparison.field.as_ref(), visuals.get_single_mut()) else { return };
+    let Some(image) = images.get_mut(&visual.texture) else { return };
+
+    if image.width() as usize != field.width || image.height() as usize != field.height {
+        image.resize(bevy::render::render_resource::Extent3d {
+            width: field.width as u32,
+            height: field.height as u32,
+            depth_or_array_layers: 1,
+        });
+        transform.scale = Vec3::splat(field.cell_size);
+    }
+    write_wave_field_rgba(field, config.color_scheme, &mut image.data);
+    if config.show_boundary {
+        tint_absorbing_layer(field, config.absorbing_width(), &mut image.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_comparison_field_leaves_out_the_slits() {
+        let mut world = World::new();
+        world.insert_resource(WaveField::new(64, 40));
+        world.insert_resource(SlitComparison { field: Some(WaveField::new(64, 40)) });
+        // Columns 37..=57 hold the slit barrier, 10..=14 the reflector.
+        world.spawn((
+            Transform::from_xyz(30.0, 0.0, 0.0),
+            Obstacle { obstacle_type: ObstacleType::DoubleSlit, width: 40.0, height: 4.0, ..default() },
+        ));
+        world.spawn((Transform::from_xyz(-40.0, 0.0, 0.0), Obstacle { width: 8.0, height: 8.0, ..default() }));
+        world.run_system_once(rasterize_obstacles);
+        world.run_system_once(rasterize_slit_comparison);
+
+        let walls = |field: &WaveField, columns: std::ops::Range<usize>| {
+            let cells = (0..field.height).flat_map(|y| columns.clone().map(move |x| (x, y)));
+            cells.filter(|&(x, y)| field.obstacle_map[field.idx(x, y)] == 0.0).count()
+        };
+        let tank = world.resource::<WaveField>();
+        let comparison = world.resource::<SlitComparison>().field.as_ref().unwrap();
+        assert!(walls(tank, 24..64) > 0);
+        assert_eq!(walls(comparison, 24..64), 0, "the slit barrier is only in the tank");
+        assert!(walls(tank, 0..24) > 0);
+        assert_eq!(walls(comparison, 0..24), walls(tank, 0..24), "other obstacles are in both");
+    }
+}
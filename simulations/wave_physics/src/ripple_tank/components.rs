@@ -1,8 +1,9 @@
 //! Components for the Ripple Tank simulation
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use super::MAX_PROBE_HISTORY;
+use super::{fringes::Fringe, APODIZATION_SIGMA, MAX_PROBE_HISTORY};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Scene Object Marker
@@ -12,14 +13,19 @@ use super::MAX_PROBE_HISTORY;
 pub struct SceneObject {
     pub id: u32,
     pub selected: bool,
+    /// Locked objects can't be moved, and clicks pass through them.
     pub locked: bool,
 }
 
+/// Objects with the same group id are selected, and so moved, together.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneGroup(pub u32);
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Wave Sources
 // ══════════════════════════════════════════════════════════════════════════════
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum WaveSourceType {
     Point,
     Line,
@@ -34,7 +40,21 @@ pub struct WaveSource {
     pub amplitude: f32,
     pub phase: f32,
     pub enabled: bool,
+    /// Silences this source, like a mixer channel's mute.
+    pub muted: bool,
+    /// While any source is soloed, only soloed sources emit.
+    pub solo: bool,
     pub waveform: Waveform,
+    /// Amplitude taper across a `Line` or `PhasedArray` aperture.
+    pub apodization: Apodization,
+    /// Direction a `PhasedArray` beams in, in radians off broadside;
+    /// positive steers toward +x.
+    pub steering_angle: f32,
+    /// Automatic chirp that sets `frequency` from the simulation clock.
+    pub sweep: Option<FrequencySweep>,
+    /// Oscillator phase in `[0, 2π)`, advanced by `2π·f·dt` each FDTD step so
+    /// changing frequency or waveform doesn't jump.
+    pub clock_phase: f32,
 }
 
 impl Default for WaveSource {
@@ -45,12 +65,146 @@ impl Default for WaveSource {
             amplitude: 1.0,
             phase: 0.0,
             enabled: true,
+            muted: false,
+            solo: false,
             waveform: Waveform::Sine,
+            apodization: Apodization::Uniform,
+            steering_angle: 0.0,
+            sweep: None,
+            clock_phase: 0.0,
+        }
+    }
+}
+
+impl WaveSource {
+    /// Aligns the oscillator with one that has run at the current frequency
+    /// since `time` zero, keeping sources coherent with each other.
+    pub fn reset_clock(&mut self, time: f32) {
+        self.clock_phase = (2.0 * std::f32::consts::PI * self.frequency * time).rem_euclid(2.0 * std::f32::consts::PI);
+    }
+
+    /// Whether this source emits, given whether any source is soloed.
+    pub fn is_audible(&self, any_solo: bool) -> bool {
+        self.enabled && !self.muted && (self.solo || !any_solo)
+    }
+
+    /// Output of the waveform at the current clock, shifted by `offset` rad.
+    pub fn sample(&self, offset: f32) -> f32 {
+        let phase = self.clock_phase + self.phase + offset;
+        match self.waveform {
+            Waveform::Sine => self.amplitude * phase.sin(),
+            Waveform::Square => self.amplitude * phase.sin().signum(),
+            Waveform::Pulse => {
+                if Self::in_pulse(phase) { self.amplitude } else { 0.0 }
+            }
+        }
+    }
+
+    /// Whether a `Pulse` source is in the on part of its cycle.
+    pub fn is_pulse_high(&self) -> bool {
+        self.waveform == Waveform::Pulse && Self::in_pulse(self.clock_phase + self.phase)
+    }
+
+    /// Pulses are on for the first tenth of each cycle.
+    fn in_pulse(phase: f32) -> bool {
+        (phase / (2.0 * std::f32::consts::PI)).rem_euclid(1.0) < 0.1
+    }
+}
+
+/// How a [`FrequencySweep`] moves between its end frequencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
+pub enum SweepMode {
+    /// Equal hertz per second.
+    #[default]
+    Linear,
+    /// Equal octaves per second, spending as long on each octave.
+    Logarithmic,
+}
+
+/// A chirp from `start` to `end` Hz over `duration` simulated seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct FrequencySweep {
+    pub mode: SweepMode,
+    pub start: f32,
+    pub end: f32,
+    pub duration: f32,
+    /// Start over from `start` after reaching `end`, rather than holding.
+    pub looping: bool,
+    /// Simulation time the sweep began, or `None` to begin at the next step.
+    #[serde(skip)]
+    pub started: Option<f32>,
+}
+
+impl Default for FrequencySweep {
+    fn default() -> Self {
+        Self { mode: SweepMode::Linear, start: 1.0, end: 5.0, duration: 10.0, looping: true, started: None }
+    }
+}
+
+impl FrequencySweep {
+    /// Fraction of the way through the current sweep at `time`, from 0 to 1.
+    pub fn progress(&self, time: f32) -> f32 {
+        let elapsed = (time - self.started.unwrap_or(time)).max(0.0) / self.duration.max(f32::EPSILON);
+        if self.looping {
+            elapsed.fract()
+        } else {
+            elapsed.min(1.0)
+        }
+    }
+
+    /// Frequency the sweep has reached at `time`.
+    pub fn frequency_at(&self, time: f32) -> f32 {
+        let progress = self.progress(time);
+        match self.mode {
+            SweepMode::Linear => self.start + (self.end - self.start) * progress,
+            SweepMode::Logarithmic => {
+                let start = self.start.max(f32::EPSILON);
+                start * (self.end.max(f32::EPSILON) / start).powf(progress)
+            }
+        }
+    }
+}
+
+/// Amplitude envelope across an extended source.
+///
+/// A uniform aperture's hard ends diffract like a slit's edges; tapering
+/// them to zero leaves a cleaner, collimated beam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
+pub enum Apodization {
+    #[default]
+    Uniform,
+    /// Gaussian with σ of [`APODIZATION_SIGMA`] half-widths.
+    Gaussian,
+    /// Hann window, `½(1 + cos πt)`.
+    RaisedCosine,
+}
+
+impl Apodization {
+    pub const ALL: [Self; 3] = [Self::Uniform, Self::Gaussian, Self::RaisedCosine];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Uniform => "Uniform",
+            Self::Gaussian => "Gaussian",
+            Self::RaisedCosine => "Raised Cosine",
+        }
+    }
+
+    /// Relative amplitude of element `i` of `count` spread across the aperture.
+    ///
+    /// Elements sit at their centers, `t = 2(i + ½)/count − 1`, so even the
+    /// outermost ones keep a little amplitude.
+    pub fn weight(self, i: usize, count: usize) -> f32 {
+        let t = 2.0 * (i as f32 + 0.5) / count.max(1) as f32 - 1.0;
+        match self {
+            Self::Uniform => 1.0,
+            Self::Gaussian => (-0.5 * (t / APODIZATION_SIGMA).powi(2)).exp(),
+            Self::RaisedCosine => 0.5 * (1.0 + (std::f32::consts::PI * t).cos()),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
 pub enum Waveform {
     #[default]
     Sine,
@@ -60,11 +214,89 @@ pub enum Waveform {
 
 #[derive(Component, Reflect)]
 pub struct MovingSource {
+    /// Velocity for `Linear`; its length is the travel speed for `Custom`.
     pub velocity: Vec2,
     pub path: MovementPath,
+    /// Orbit center for `Circular`.
+    pub center: Vec2,
+    pub radius: f32,
+    /// Signed angular speed in rad/s (positive is counter-clockwise).
+    pub angular_speed: f32,
+    /// Current orbit angle in radians.
+    pub angle: f32,
+    /// Closed loop of points followed by `Custom`.
+    pub waypoints: Vec<Vec2>,
+    /// Distance travelled along the waypoint loop.
+    pub progress: f32,
+}
+
+impl Default for MovingSource {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::new(50.0, 0.0),
+            path: MovementPath::Linear,
+            center: Vec2::ZERO,
+            radius: 80.0,
+            angular_speed: 1.0,
+            angle: 0.0,
+            waypoints: Vec::new(),
+            progress: 0.0,
+        }
+    }
+}
+
+impl MovingSource {
+    /// Places the orbit center so that `position` lies on the circle at the
+    /// current angle.
+    pub fn center_orbit_on(&mut self, position: Vec2) {
+        self.center = position - self.radius * Vec2::from_angle(self.angle);
+    }
+
+    /// Instantaneous velocity along the active path, in world units per second.
+    pub fn current_velocity(&self) -> Vec2 {
+        match self.path {
+            MovementPath::Linear => self.velocity,
+            MovementPath::Circular => {
+                self.angular_speed * self.radius * Vec2::from_angle(self.angle).perp()
+            }
+            MovementPath::Custom => {
+                let speed = self.velocity.length();
+                let here = self.waypoint_position_at(self.progress);
+                let ahead = self.waypoint_position_at(self.progress + 0.5);
+                match (here, ahead) {
+                    (Some(here), Some(ahead)) => (ahead - here).normalize_or_zero() * speed,
+                    _ => Vec2::ZERO,
+                }
+            }
+        }
+    }
+
+    /// Point at the current progress along the closed waypoint loop.
+    pub fn waypoint_position(&self) -> Option<Vec2> {
+        self.waypoint_position_at(self.progress)
+    }
+
+    fn waypoint_position_at(&self, progress: f32) -> Option<Vec2> {
+        let (&first, _) = self.waypoints.split_first()?;
+        let segments = self.waypoints.iter().zip(self.waypoints.iter().skip(1).chain([&first]));
+        let total: f32 = segments.clone().map(|(a, b)| a.distance(*b)).sum();
+        if total <= f32::EPSILON {
+            return Some(first);
+        }
+
+        let mut remaining = progress.rem_euclid(total);
+        for (&a, &b) in segments {
+            let length = a.distance(b);
+            if remaining <= length && length > 0.0 {
+                return Some(a.lerp(b, remaining / length));
+            }
+            remaining -= length;
+        }
+        Some(first)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
 pub enum MovementPath {
     #[default]
     Linear,
@@ -76,12 +308,14 @@ pub enum MovementPath {
 // Obstacles
 // ══════════════════════════════════════════════════════════════════════════════
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum ObstacleType {
     Reflector,
     SingleSlit,
     DoubleSlit,
     RefractionBlock,
+    /// A GRIN lens: the index falls off parabolically from its axis.
+    GradedIndex,
 }
 
 #[derive(Component, Reflect)]
@@ -93,6 +327,13 @@ pub struct Obstacle {
     pub slit_width: f32,
     pub slit_separation: f32,
     pub refractive_index: f32,
+    /// Dispersion coefficient `k` in Hz², giving `n(f) = n₀ + k/f²`.
+    pub dispersion: f32,
+    /// How much of a wave a reflector stops, from 0 (transparent) to 1 (a
+    /// hard wall). Below 1 the wall attenuates the field instead of zeroing it.
+    pub reflectivity: f32,
+    /// How far a graded-index lens's index drops from its axis to its edges.
+    pub index_gradient: f32,
 }
 
 impl Default for Obstacle {
@@ -105,6 +346,74 @@ impl Default for Obstacle {
             slit_width: 10.0,
             slit_separation: 30.0,
             refractive_index: 1.5,
+            dispersion: 0.0,
+            reflectivity: 1.0,
+            index_gradient: 0.4,
+        }
+    }
+}
+
+impl Obstacle {
+    /// Whether this is a single or double slit barrier.
+    pub fn is_slit(&self) -> bool {
+        matches!(self.obstacle_type, ObstacleType::SingleSlit | ObstacleType::DoubleSlit)
+    }
+
+    /// Whether this block's index depends on frequency.
+    pub fn is_dispersive(&self) -> bool {
+        self.dispersion > 0.0 && matches!(self.obstacle_type, ObstacleType::RefractionBlock | ObstacleType::GradedIndex)
+    }
+
+    /// Refractive index seen by a wave of the given frequency.
+    pub fn refractive_index_at(&self, frequency: f32) -> f32 {
+        if frequency > 0.0 {
+            self.refractive_index + self.dispersion / (frequency * frequency)
+        } else {
+            self.refractive_index
+        }
+    }
+
+    /// Index of a graded-index lens at `offset` half-widths from its axis,
+    /// `n(x) = n₀ − Δn·x²`, never below 1.
+    ///
+    /// The axis runs through the center across the lens's thickness, so
+    /// waves crossing it are slowed most in the middle and converge.
+    pub fn graded_index(&self, axis_index: f32, offset: f32) -> f32 {
+        (axis_index - self.index_gradient * offset * offset).max(1.0)
+    }
+
+    /// Thin-lens focal length of a graded-index lens, `f = w² / (8·Δn·t)`
+    /// for width `w` and thickness `t`, measured from its center.
+    pub fn graded_focal_length(&self) -> Option<f32> {
+        (self.index_gradient > 0.0 && self.height > 0.0)
+            .then(|| self.width * self.width / (8.0 * self.index_gradient * self.height))
+    }
+}
+
+/// Walls traced from an image: `walls` holds one flag per pixel, row-major
+/// from the top row, and the image is stretched to `width` world units
+/// keeping its aspect ratio.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ImageMaskObstacle {
+    pub columns: usize,
+    pub rows: usize,
+    pub walls: Vec<bool>,
+    pub width: f32,
+}
+
+impl ImageMaskObstacle {
+    pub fn height(&self) -> f32 {
+        self.width * self.rows as f32 / self.columns.max(1) as f32
+    }
+
+    pub fn is_wall(&self, column: usize, row: usize) -> bool {
+        column < self.columns && self.walls.get(row * self.columns + column).copied().unwrap_or(false)
+    }
+
+    /// Swaps walls and openings, for images drawn as light shapes on dark.
+    pub fn invert(&mut self) {
+        for wall in &mut self.walls {
+            *wall = !*wall;
         }
     }
 }
@@ -119,6 +428,11 @@ pub struct Probe {
     pub color: Color,
     #[reflect(ignore)]
     pub history: Vec<f32>,
+    /// Simulation time the pulse timed by [`TimeOfFlight`] first reached
+    /// its threshold here.
+    ///
+    /// [`TimeOfFlight`]: super::resources::TimeOfFlight
+    pub arrival: Option<f32>,
 }
 
 impl Probe {
@@ -127,14 +441,115 @@ impl Probe {
             label: label.to_string(),
             color,
             history: Vec::with_capacity(MAX_PROBE_HISTORY),
+            arrival: None,
         }
     }
 }
 
+/// A measuring line; `start` and `end` are offsets from the entity's position.
 #[derive(Component, Reflect)]
 pub struct Ruler {
     pub start: Vec2,
     pub end: Vec2,
+    /// Holding Shift while dragging an end snaps the line to
+    /// [`RULER_SNAP_DEGREES`](super::RULER_SNAP_DEGREES) steps.
+    pub snap_angle: bool,
+}
+
+impl Ruler {
+    /// World-space endpoints for a ruler centered at `center`.
+    pub fn world_endpoints(&self, center: Vec2) -> (Vec2, Vec2) {
+        (center + self.start, center + self.end)
+    }
+
+    pub fn length(&self) -> f32 {
+        (self.end - self.start).length()
+    }
+
+    /// Angle of the line above the horizontal in degrees, in (−90, 90].
+    pub fn angle_degrees(&self) -> f32 {
+        let angle = (self.end - self.start).to_angle().to_degrees();
+        if angle > 90.0 {
+            angle - 180.0
+        } else if angle <= -90.0 {
+            angle + 180.0
+        } else {
+            angle
+        }
+    }
+
+    pub fn endpoint(&self, end: RulerEnd) -> Vec2 {
+        match end {
+            RulerEnd::Start => self.start,
+            RulerEnd::End => self.end,
+        }
+    }
+}
+
+/// Turns a [`Ruler`] into a detector screen that records the time-averaged
+/// intensity along its length.
+#[derive(Component, Reflect, Default)]
+pub struct Screen {
+    /// Intensity ⟨u²⟩ from start to end, one sample every `sample_spacing`.
+    #[reflect(ignore)]
+    pub profile: Vec<f32>,
+    pub sample_spacing: f32,
+    #[reflect(ignore)]
+    pub fringes: Vec<Fringe>,
+    /// Bright fringes expected from d·sinθ = mλ, as (m, distance from start).
+    #[reflect(ignore)]
+    pub predicted: Vec<(i32, f32)>,
+}
+
+/// A measurement path of straight segments that samples the field along
+/// its whole length, for tracing curved paths such as a wavefront.
+#[derive(Component, Reflect, Default)]
+pub struct PolyProbe {
+    /// Vertices relative to the entity's position, in the order placed.
+    pub points: Vec<Vec2>,
+    /// Field value every `sample_spacing` units of arc length from the
+    /// first vertex.
+    #[reflect(ignore)]
+    pub samples: Vec<f32>,
+    pub sample_spacing: f32,
+}
+
+impl PolyProbe {
+    /// Vertices in world space for a path anchored at `center`.
+    pub fn world_points(&self, center: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        self.points.iter().map(move |&point| center + point)
+    }
+
+    /// Total length of the path.
+    pub fn arc_length(&self) -> f32 {
+        self.points.windows(2).map(|segment| segment[0].distance(segment[1])).sum()
+    }
+
+    /// Points every `spacing` units of arc length along the path, starting at
+    /// the first vertex.
+    pub fn sample_points(&self, center: Vec2, spacing: f32) -> Vec<Vec2> {
+        let Some(&first) = self.points.first() else { return Vec::new() };
+        let spacing = spacing.max(f32::EPSILON);
+        let mut samples = vec![center + first];
+        let mut next = spacing;
+        let mut travelled = 0.0;
+        for segment in self.points.windows(2) {
+            let length = segment[0].distance(segment[1]);
+            while next <= travelled + length {
+                samples.push(center + segment[0].lerp(segment[1], (next - travelled) / length));
+                next += spacing;
+            }
+            travelled += length;
+        }
+        samples
+    }
+}
+
+/// One of the two draggable ends of a [`Ruler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulerEnd {
+    Start,
+    End,
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
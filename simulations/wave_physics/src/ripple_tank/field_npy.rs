@@ -0,0 +1,73 @@
+//! NumPy `.npy` export of the wave field
+
+use super::{file_io::save_file, resources::WaveField};
+
+pub const FIELD_NPY_FILE_NAME: &str = "ripple_tank_field.npy";
+
+// ══════════════════════════════════════════════════════════════════════════════
+// NPY Export
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Encodes a row-major `f32` array in NPY format version 1.0.
+///
+/// The header is padded with spaces so the data starts on a 64-byte
+/// boundary, as `numpy.save` does.
+pub fn encode_npy_f32(values: &[f32], shape: &[usize]) -> Vec<u8> {
+    debug_assert_eq!(values.len(), shape.iter().product::<usize>(), "npy: shape doesn't match data");
+
+    let dims: Vec<String> = shape.iter().map(|dim| dim.to_string()).collect();
+    // A 1-tuple needs its trailing comma.
+    let shape = if dims.len() == 1 { format!("({},)", dims[0]) } else { format!("({})", dims.join(", ")) };
+    let mut header = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': {shape}, }}");
+
+    const PREAMBLE: usize = 10; // magic, version, header length
+    let unpadded = PREAMBLE + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    header.push('\n');
+
+    let mut npy = Vec::with_capacity(PREAMBLE + header.len() + values.len() * 4);
+    npy.extend_from_slice(b"\x93NUMPY\x01\x00");
+    npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    npy.extend_from_slice(header.as_bytes());
+    for value in values {
+        npy.extend_from_slice(&value.to_le_bytes());
+    }
+    npy
+}
+
+/// Encodes the current field with shape `(height, width)`.
+///
+/// Row 0 is the bottom of the tank, so plot with `origin="lower"`.
+pub fn field_npy(wave_field: &WaveField) -> Vec<u8> {
+    encode_npy_f32(&wave_field.current, &[wave_field.height, wave_field.width])
+}
+
+/// Saves the current field as `.npy` (a download in the browser).
+pub fn export_field_npy(wave_field: &WaveField) {
+    save_file(FIELD_NPY_FILE_NAME, "application/octet-stream", &field_npy(wave_field));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npy_header_and_data_layout() {
+        let values = [1.0, -2.0, 0.5, 3.0, 4.0, 5.0];
+        let npy = encode_npy_f32(&values, &[2, 3]);
+
+        assert_eq!(&npy[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+
+        let header = std::str::from_utf8(&npy[10..10 + header_len]).expect("ASCII header");
+        assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert!(header.ends_with('\n'));
+
+        let data: Vec<f32> = npy[10 + header_len..]
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+            .collect();
+        assert_eq!(data, values);
+    }
+}
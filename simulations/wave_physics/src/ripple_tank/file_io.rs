@@ -0,0 +1,108 @@
+//! File access for saving and loading
+//!
+//! Native builds read and write the working directory; web builds trigger a
+//! browser download or open a file picker.
+
+use bevy::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Receives the contents of a file opened with [`open_file`].
+///
+/// Browsers deliver file contents asynchronously, so callers poll
+/// [`PendingFile::take`] from a system.
+#[derive(Clone, Default)]
+pub struct PendingFile(Arc<Mutex<Option<Vec<u8>>>>);
+
+impl PendingFile {
+    pub fn take(&self) -> Option<Vec<u8>> {
+        self.0.lock().expect("pending file lock poisoned").take()
+    }
+
+    fn set(&self, bytes: Vec<u8>) {
+        *self.0.lock().expect("pending file lock poisoned") = Some(bytes);
+    }
+}
+
+/// Writes a file to the working directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(name: &str, _mime: &str, bytes: &[u8]) {
+    match std::fs::write(name, bytes) {
+        Ok(()) => info!("Saved {}", name),
+        Err(err) => error!("Failed to save {}: {}", name, err),
+    }
+}
+
+/// Offers a file as a browser download.
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(name: &str, mime: &str, bytes: &[u8]) {
+    use base64::Engine;
+    use wasm_bindgen::JsCast;
+
+    let url = format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    );
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else { return };
+    let Ok(element) = document.create_element("a") else { return };
+    let Ok(anchor) = element.dyn_into::<web_sys::HtmlAnchorElement>() else { return };
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+}
+
+/// Reads a file from the working directory into `pending`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_file(name: &str, _accept: &str, pending: &PendingFile) {
+    match std::fs::read(name) {
+        Ok(bytes) => pending.set(bytes),
+        Err(err) => error!("Failed to open {}: {}", name, err),
+    }
+}
+
+/// Opens a browser file picker; the chosen file arrives in `pending`.
+#[cfg(target_arch = "wasm32")]
+pub fn open_file(_name: &str, accept: &str, pending: &PendingFile) {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else { return };
+    let Ok(element) = document.create_element("input") else { return };
+    let Ok(input) = element.dyn_into::<web_sys::HtmlInputElement>() else { return };
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let pending = pending.clone();
+    let on_change = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+        let Some(input) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+        else {
+            return;
+        };
+        let Some(file) = input.files().and_then(|files| files.get(0)) else { return };
+        let Ok(reader) = web_sys::FileReader::new() else { return };
+
+        let pending = pending.clone();
+        let on_load = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+            let Some(reader) = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::FileReader>().ok())
+            else {
+                return;
+            };
+            if let Ok(result) = reader.result() {
+                pending.set(js_sys::Uint8Array::new(&result).to_vec());
+            }
+        });
+        reader.set_onload(Some(on_load.as_ref().unchecked_ref()));
+        on_load.forget();
+
+        if let Err(err) = reader.read_as_array_buffer(&file) {
+            error!("Failed to read file: {:?}", err);
+        }
+    });
+    input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+    on_change.forget();
+    input.click();
+}
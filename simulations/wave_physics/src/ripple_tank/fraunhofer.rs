@@ -0,0 +1,122 @@
+//! Far-field (Fraunhofer) diffraction of a slit barrier
+//!
+//! Far enough from a barrier, its diffraction pattern is the squared Fourier
+//! transform of the aperture function, the transmission along the barrier.
+//! Reading that function off the rasterized obstacle map and transforming
+//! it gives the pattern the FDTD field only settles into beyond the
+//! Fraunhofer distance D²/λ, so the two can be compared side by side.
+
+use bevy::prelude::*;
+use ez_core::math::fft_in_place;
+
+use super::{components::Obstacle, resources::WaveField, FAR_FIELD_FFT_SIZE};
+
+/// A diffraction pattern against the sine of the angle off the barrier's
+/// normal, normalized to a peak of 1.
+#[derive(Debug, Clone, Default)]
+pub struct FarField {
+    pub sin_theta: Vec<f32>,
+    pub intensity: Vec<f32>,
+}
+
+/// Transmission at each cell along a barrier centered at `position`: 1
+/// where an opening runs through its whole thickness, 0 where a wall
+/// blocks. Samples are one cell apart.
+pub fn aperture_function(wave_field: &WaveField, position: Vec2, obstacle: &Obstacle) -> Vec<f32> {
+    let cell_size = wave_field.cell_size;
+    let along = Vec2::from_angle(obstacle.rotation) * cell_size;
+    let across = along.perp();
+    let half_w = (obstacle.width / cell_size / 2.0) as i32;
+    let half_h = (obstacle.height / cell_size / 2.0) as i32;
+    (-half_w..=half_w)
+        .map(|dx| {
+            let open = (-half_h..=half_h)
+                .all(|dy| wave_field.obstacle_at(position + along * dx as f32 + across * dy as f32) > 0.0);
+            if open { 1.0 } else { 0.0 }
+        })
+        .collect()
+}
+
+/// Width D spanned by the openings of an aperture sampled every `spacing`
+/// units, from the first open sample to the last.
+pub fn aperture_extent(aperture: &[f32], spacing: f32) -> f32 {
+    let first = aperture.iter().position(|&value| value > 0.0);
+    let last = aperture.iter().rposition(|&value| value > 0.0);
+    match (first, last) {
+        (Some(first), Some(last)) => (last - first + 1) as f32 * spacing,
+        _ => 0.0,
+    }
+}
+
+/// Distance D²/λ beyond which a barrier's pattern is in the far field.
+pub fn fraunhofer_distance(aperture: &[f32], spacing: f32, wavelength: f32) -> f32 {
+    aperture_extent(aperture, spacing).powi(2) / wavelength.max(f32::EPSILON)
+}
+
+/// |FFT|² of an aperture sampled every `spacing` units, zero-padded to
+/// [`FAR_FIELD_FFT_SIZE`].
+///
+/// Spatial frequency bin k maps to sinθ = λ·k / (N·spacing); only the bins
+/// with |sinθ| ≤ 1 propagate, and they are returned in order of sinθ.
+pub fn far_field(aperture: &[f32], spacing: f32, wavelength: f32) -> FarField {
+    let size = FAR_FIELD_FFT_SIZE;
+    let mut re = vec![0.0; size];
+    let mut im = vec![0.0; size];
+    let count = aperture.len().min(size);
+    re[..count].copy_from_slice(&aperture[..count]);
+    fft_in_place(&mut re, &mut im);
+
+    let mut pattern: Vec<(f32, f32)> = (0..size)
+        .map(|k| {
+            let frequency = if k < size / 2 { k as f32 } else { k as f32 - size as f32 };
+            (wavelength * frequency / (size as f32 * spacing), re[k] * re[k] + im[k] * im[k])
+        })
+        .filter(|(sin_theta, _)| sin_theta.abs() <= 1.0)
+        .collect();
+    pattern.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let peak = pattern.iter().map(|(_, intensity)| *intensity).fold(0.0f32, f32::max).max(f32::EPSILON);
+    FarField {
+        sin_theta: pattern.iter().map(|(sin_theta, _)| *sin_theta).collect(),
+        intensity: pattern.iter().map(|(_, intensity)| intensity / peak).collect(),
+    }
+}
+
+/// Simulated ⟨u²⟩ on an arc of `radius` around `center`, at each angle in
+/// `sin_theta` off the unit vector `forward`, positive toward the unit
+/// vector `along`; normalized to a peak of 1.
+pub fn near_field_profile(wave_field: &WaveField, center: Vec2, forward: Vec2, along: Vec2, radius: f32, sin_theta: &[f32]) -> Vec<f32> {
+    let profile: Vec<f32> = sin_theta
+        .iter()
+        .map(|&sin| {
+            let cos = (1.0 - sin * sin).max(0.0).sqrt();
+            wave_field.intensity_at(center + (forward * cos + along * sin) * radius)
+        })
+        .collect();
+    let peak = profile.iter().copied().fold(0.0f32, f32::max).max(f32::EPSILON);
+    profile.iter().map(|value| value / peak).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_slit_far_field_has_first_minimum_at_lambda_over_a() {
+        // A 20-unit slit in 1-unit cells, λ = 5: minima at sinθ = ±0.25.
+        let aperture: Vec<f32> = (0..100).map(|i| if (40..60).contains(&i) { 1.0 } else { 0.0 }).collect();
+        assert_eq!(aperture_extent(&aperture, 1.0), 20.0);
+        assert_eq!(fraunhofer_distance(&aperture, 1.0, 5.0), 80.0);
+
+        let pattern = far_field(&aperture, 1.0, 5.0);
+        let at = |sin: f32| {
+            let i = pattern.sin_theta.iter().position(|&s| s >= sin).expect("in range");
+            pattern.intensity[i]
+        };
+        assert_eq!(at(0.0), 1.0);
+        assert!(at(0.25) < 0.01, "first minimum: {}", at(0.25));
+        // The first side lobe of sinc² is about 4.7% of the peak.
+        assert!((at(0.3575) - 0.047).abs() < 0.01, "side lobe: {}", at(0.3575));
+        assert!(pattern.sin_theta.iter().all(|sin| sin.abs() <= 1.0));
+    }
+}
@@ -0,0 +1,154 @@
+//! Ring buffer of wave field snapshots for scrubbing back through a run
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use super::{components::*, resources::*, HISTORY_CAPACITY, HISTORY_INTERVAL};
+
+/// The two time levels the FDTD stencil needs, at one moment.
+pub struct FieldSnapshot {
+    pub time: f32,
+    pub current: Vec<f32>,
+    pub previous: Vec<f32>,
+}
+
+/// Snapshots of the wave field taken every `interval` simulated seconds,
+/// oldest first, keeping at most `capacity`.
+///
+/// See [`HISTORY_CAPACITY`] for the memory this costs at each grid size.
+#[derive(Resource)]
+pub struct FieldHistory {
+    pub snapshots: VecDeque<FieldSnapshot>,
+    /// Simulated seconds between snapshots.
+    pub interval: f32,
+    pub capacity: usize,
+    /// Snapshot the scrubber asked for, restored by [`update_field_history`].
+    pub restore_request: Option<usize>,
+    /// Snapshot last restored, while the tank stays paused on it.
+    pub restored: Option<usize>,
+}
+
+impl Default for FieldHistory {
+    fn default() -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+            interval: HISTORY_INTERVAL,
+            capacity: HISTORY_CAPACITY,
+            restore_request: None,
+            restored: None,
+        }
+    }
+}
+
+impl FieldHistory {
+    /// Records the field at `time` if `interval` has passed since the last
+    /// snapshot.
+    ///
+    /// Snapshots later than `time` are dropped first, since stepping back or
+    /// resuming from a restored snapshot starts a new timeline; so is the
+    /// whole history when the grid size changed.
+    pub fn record(&mut self, wave_field: &WaveField, time: f32) {
+        if self.snapshots.front().is_some_and(|snapshot| snapshot.current.len() != wave_field.current.len()) {
+            self.snapshots.clear();
+        }
+        while self.snapshots.back().is_some_and(|snapshot| snapshot.time > time + 1e-4) {
+            self.snapshots.pop_back();
+        }
+        if self.snapshots.back().is_some_and(|snapshot| time - snapshot.time < self.interval - 1e-4) {
+            return;
+        }
+
+        let capacity = self.capacity.max(1);
+        while self.snapshots.len() > capacity {
+            self.snapshots.pop_front();
+        }
+        // Reuse the oldest snapshot's buffers once the ring is full.
+        let mut snapshot = if self.snapshots.len() == capacity {
+            self.snapshots.pop_front().expect("ring is full")
+        } else {
+            FieldSnapshot { time, current: Vec::new(), previous: Vec::new() }
+        };
+        snapshot.time = time;
+        snapshot.current.clone_from(&wave_field.current);
+        snapshot.previous.clone_from(&wave_field.previous);
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Copies snapshot `index` back into the field, returning its time.
+    pub fn restore(&self, index: usize, wave_field: &mut WaveField) -> Option<f32> {
+        let snapshot = self.snapshots.get(index)?;
+        if snapshot.current.len() != wave_field.current.len() {
+            return None;
+        }
+        wave_field.current.copy_from_slice(&snapshot.current);
+        wave_field.previous.copy_from_slice(&snapshot.previous);
+        Some(snapshot.time)
+    }
+
+    /// Bytes held by the stored snapshots.
+    pub fn memory_bytes(&self) -> usize {
+        self.snapshots
+            .iter()
+            .map(|snapshot| (snapshot.current.len() + snapshot.previous.len()) * std::mem::size_of::<f32>())
+            .sum()
+    }
+}
+
+/// Records a snapshot when one is due, or restores the one the scrubber
+/// picked.
+///
+/// Restoring pauses the tank and rewinds the simulation clock, and every
+/// source's oscillator with it, so sources resume in phase with the
+/// restored waves. Moving sources stay where they are, and probe histories
+/// and the time-averaged intensity are left as they were.
+pub fn update_field_history(
+    mut history: ResMut<FieldHistory>,
+    mut wave_field: ResMut<WaveField>,
+    mut config: ResMut<RippleTankConfig>,
+    mut sources: Query<&mut WaveSource>,
+) {
+    if let Some(index) = history.restore_request.take() {
+        if let Some(time) = history.restore(index, &mut wave_field) {
+            config.paused = true;
+            config.accumulated_time = time;
+            for mut source in sources.iter_mut() {
+                source.reset_clock(time);
+            }
+            history.restored = Some(index);
+        }
+        return;
+    }
+
+    if config.paused {
+        return;
+    }
+    history.restored = None;
+    history.record(&wave_field, config.accumulated_time);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_a_capped_ring_and_restores() {
+        let mut history = FieldHistory { interval: 0.5, capacity: 3, ..default() };
+        let mut field = WaveField::new(4, 4);
+        for step in 0..10 {
+            field.current.fill(step as f32);
+            history.record(&field, step as f32 * 0.25);
+        }
+        // Every other step is due; only the last three are kept.
+        let times: Vec<f32> = history.snapshots.iter().map(|snapshot| snapshot.time).collect();
+        assert_eq!(times, vec![1.0, 1.5, 2.0]);
+
+        assert_eq!(history.restore(1, &mut field), Some(1.5));
+        assert!(field.current.iter().all(|&value| value == 6.0));
+
+        // Running on from the restored snapshot drops the later ones.
+        history.record(&field, 1.75);
+        assert_eq!(history.snapshots.len(), 2);
+        assert_eq!(history.memory_bytes(), 2 * 2 * 16 * 4);
+    }
+}
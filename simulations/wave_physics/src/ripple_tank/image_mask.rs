@@ -0,0 +1,140 @@
+//! Obstacles imported from an image
+//!
+//! Dark, opaque pixels of a PNG become wall cells, so letters, logos or
+//! gratings too intricate to build from rectangles can be diffracted
+//! through. The mask is scaled to a world-space width and stamped onto the
+//! obstacle map each frame alongside the regular obstacles.
+
+use bevy::prelude::*;
+use image::imageops::FilterType;
+
+use super::{components::*, file_io::*, resources::*, spawn::spawn_image_mask, MAX_MASK_SIDE};
+
+pub const MASK_FILE_NAME: &str = "ripple_tank_mask.png";
+
+/// Luma below which an opaque pixel is a wall.
+const WALL_LUMA: u8 = 128;
+/// Alpha below which a pixel is open, whatever its color.
+const OPAQUE_ALPHA: u8 = 128;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Decoding
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Decodes an image into a mask of its dark, opaque pixels, shrunk to at
+/// most [`MAX_MASK_SIDE`] pixels a side.
+pub fn decode_mask(bytes: &[u8], width: f32) -> Result<ImageMaskObstacle, image::ImageError> {
+    let mut image = image::load_from_memory(bytes)?;
+    if image.width().max(image.height()) > MAX_MASK_SIDE {
+        image = image.resize(MAX_MASK_SIDE, MAX_MASK_SIDE, FilterType::Triangle);
+    }
+    let pixels = image.to_luma_alpha8();
+    Ok(ImageMaskObstacle {
+        columns: pixels.width() as usize,
+        rows: pixels.height() as usize,
+        walls: pixels.pixels().map(|pixel| pixel.0[1] >= OPAQUE_ALPHA && pixel.0[0] < WALL_LUMA).collect(),
+        width,
+    })
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Rasterization
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Marks the grid cells under a mask centered at `position` as walls.
+pub fn stamp_image_mask(wave_field: &mut WaveField, position: Vec2, mask: &ImageMaskObstacle) {
+    let size = Vec2::new(mask.width, mask.height());
+    if mask.columns == 0 || mask.rows == 0 || size.min_element() <= 0.0 {
+        return;
+    }
+    let corner = position - size / 2.0;
+    let low = wave_field.world_to_grid(corner).max(Vec2::ZERO);
+    let high = wave_field.world_to_grid(corner + size).min(Vec2::new(wave_field.width as f32, wave_field.height as f32));
+
+    for y in low.y as usize..high.y.ceil() as usize {
+        for x in low.x as usize..high.x.ceil() as usize {
+            let center = wave_field.cell_center(x, y);
+            let uv = (center - corner) / size;
+            if uv.x < 0.0 || uv.y < 0.0 || uv.x >= 1.0 || uv.y >= 1.0 {
+                continue;
+            }
+            // Image rows run top-down.
+            let column = (uv.x * mask.columns as f32) as usize;
+            let row = ((1.0 - uv.y) * mask.rows as f32) as usize;
+            if mask.is_wall(column, row.min(mask.rows - 1)) {
+                let idx = wave_field.idx(x, y);
+                wave_field.obstacle_map[idx] = 0.0;
+            }
+        }
+    }
+}
+
+/// Keeps each mask's outline sprite the size of the mask.
+pub fn sync_image_mask_sprites(mut masks: Query<(&mut Sprite, &ImageMaskObstacle), Changed<ImageMaskObstacle>>) {
+    for (mut sprite, mask) in masks.iter_mut() {
+        sprite.custom_size = Some(Vec2::new(mask.width, mask.height()));
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Import
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Mask image contents waiting to be turned into an obstacle.
+#[derive(Resource, Default)]
+pub struct PendingMaskImport(pub PendingFile);
+
+pub fn request_mask_import(pending: &PendingMaskImport) {
+    open_file(MASK_FILE_NAME, ".png,image/png", &pending.0);
+}
+
+/// Spawns an imported mask in the middle of the tank and selects it.
+pub fn apply_imported_mask(
+    mut commands: Commands,
+    pending: Res<PendingMaskImport>,
+    mut object_id: ResMut<ObjectIdCounter>,
+    mut ui_state: ResMut<UIState>,
+) {
+    let Some(bytes) = pending.0.take() else { return };
+    match decode_mask(&bytes, super::DEFAULT_MASK_WIDTH) {
+        Ok(mask) => {
+            info!("Imported a {}×{} obstacle mask", mask.columns, mask.rows);
+            let entity = spawn_image_mask(&mut commands, &mut object_id, Vec2::ZERO, mask);
+            ui_state.select(Some(entity));
+        }
+        Err(err) => error!("Failed to read mask image: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+    #[test]
+    fn test_dark_pixels_of_a_png_become_walls() {
+        // 4 × 2 RGBA: the top row black except a transparent pixel, the
+        // bottom row white except one black pixel.
+        let black = [0, 0, 0, 255];
+        let white = [255, 255, 255, 255];
+        let clear = [0, 0, 0, 0];
+        let pixels = [black, black, clear, black, white, black, white, white].concat();
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png).write_image(&pixels, 4, 2, ExtendedColorType::Rgba8).expect("encodes");
+
+        let mask = decode_mask(&png, 40.0).expect("decodes");
+        assert_eq!((mask.columns, mask.rows), (4, 2));
+        assert_eq!(mask.walls, [true, true, false, true, false, true, false, false]);
+        assert_eq!(mask.height(), 20.0);
+
+        // Stamped over a field of 10-unit cells, each pixel covers one cell.
+        let mut wave_field = WaveField::new(8, 8);
+        wave_field.cell_size = 10.0;
+        stamp_image_mask(&mut wave_field, Vec2::ZERO, &mask);
+        let walls: Vec<(usize, usize)> = (0..8)
+            .flat_map(|y| (0..8).map(move |x| (x, y)))
+            .filter(|&(x, y)| wave_field.obstacle_map[wave_field.idx(x, y)] == 0.0)
+            .collect();
+        assert_eq!(walls, [(3, 3), (2, 4), (3, 4), (5, 4)]);
+    }
+}
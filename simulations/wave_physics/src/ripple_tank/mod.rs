@@ -3,39 +3,186 @@
 //! A comprehensive 2D water wave simulation featuring:
 //! - FDTD wave equation solver
 //! - Draggable wave sources (point, line, phased array, moving)
-//! - Obstacles (reflectors, slits, refraction media)
-//! - Measurement tools (oscilloscope probes, rulers)
+//! - Obstacles (reflectors, slits, refraction media, masks imported from images)
+//! - Measurement tools (oscilloscope probes, rulers, fringe screens)
+//! - Split-screen comparison of the tank with and without its slits
 //! - Real-time data visualization
 
+mod boundary;
+mod comparison;
 mod components;
+mod dataset;
+mod field_npy;
+mod fraunhofer;
+mod file_io;
+mod fringes;
+mod history;
+mod image_mask;
+mod overlays;
 mod physics;
+mod probe_csv;
 mod resources;
+mod scene;
+mod snapshot;
 mod spawn;
 mod ui;
+mod undo;
 
+pub use boundary::*;
+pub use comparison::*;
 pub use components::*;
+pub use fraunhofer::*;
+pub use dataset::*;
+pub use field_npy::*;
 pub use physics::*;
+pub use file_io::*;
+pub use fringes::*;
+pub use history::*;
+pub use image_mask::*;
+pub use overlays::*;
+pub use probe_csv::*;
 pub use resources::*;
+pub use scene::*;
+pub use snapshot::*;
 pub use spawn::*;
 pub use ui::*;
+pub use undo::*;
 
 use bevy::prelude::*;
 use ez_core::prelude::*;
+use ez_physics::timestep::FixedTimestep;
+use ez_renderer::camera::CameraControllerPlugin;
+use ez_renderer::vector_field::VectorFieldPlugin;
+use ez_renderer::viewport::SplitViewportPlugin;
+use ez_ui::shortcuts::ShortcutsOverlayPlugin;
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Constants
 // ══════════════════════════════════════════════════════════════════════════════
 
+pub const SIMULATION_ID: &str = "ripple_tank";
+/// Default grid resolution in cells.
 pub const GRID_WIDTH: usize = 640;
 pub const GRID_HEIGHT: usize = 400;
+/// Cell size in world units at the default resolution.
 pub const GRID_SCALE: f32 = 2.0;
+/// World-space extent of the tank, independent of resolution.
+pub const TANK_WIDTH: f32 = GRID_WIDTH as f32 * GRID_SCALE;
+pub const TANK_HEIGHT: f32 = GRID_HEIGHT as f32 * GRID_SCALE;
+/// Selectable grid heights in cells; the width follows the tank's aspect ratio.
+pub const GRID_SIZE_OPTIONS: [usize; 3] = [200, 400, 800];
+/// Largest Courant number per FDTD step (the 2D stability limit is 1/√2).
+pub const MAX_COURANT: f32 = 0.7;
+/// Courant number above which the 2D leapfrog update diverges.
+pub const CFL_LIMIT: f32 = std::f32::consts::FRAC_1_SQRT_2;
+/// Field values are clamped to ±this, so a diverging solve saturates
+/// instead of overflowing.
+pub const AMPLITUDE_CLAMP: f32 = 5.0;
+/// Cells at the clamp in one step that mark the solver as unstable; a few
+/// can be reached honestly where many sources add up.
+pub const UNSTABLE_CLAMPED_CELLS: usize = 64;
+/// Simulated seconds per FDTD step.
+pub const FDTD_STEP: f32 = 1.0 / 60.0;
+/// Most FDTD steps run in one frame; time beyond that is dropped. Adaptive
+/// quality lowers this on slow devices, down to one step.
+pub const MAX_STEPS_PER_FRAME: u32 = 4;
 pub const MAX_PROBE_HISTORY: usize = 512;
+pub const SPECTRUM_SIZE: usize = 512;
+pub const MIN_SPECTRUM_SAMPLES: usize = 64;
+/// Spectra kept in the spectrogram's scrolling history.
+pub const SPECTROGRAM_ROWS: usize = 120;
+/// Simulated seconds between spectrogram rows.
+pub const SPECTROGRAM_HOP: f32 = 0.25;
+/// Magnitudes this far below the spectrogram's peak are drawn black.
+pub const SPECTROGRAM_FLOOR_DB: f32 = -60.0;
+/// Weight of each new step in the time-averaged intensity.
+pub const INTENSITY_AVERAGING: f32 = 0.02;
+pub const DEFAULT_PML_WIDTH: usize = 16;
+/// Damping coefficient at the outermost cell of the absorbing layer.
+pub const PML_MAX_DAMPING: f32 = 0.5;
+/// World-space width an imported image mask starts at.
+pub const DEFAULT_MASK_WIDTH: f32 = 200.0;
+/// Largest side, in pixels, an imported mask is kept at; bigger images are
+/// scaled down on import.
+pub const MAX_MASK_SIDE: u32 = 256;
+/// Opacity of the red boundary tint where damping is strongest.
+pub const BOUNDARY_TINT_OPACITY: f32 = 0.6;
+/// Field samples below this fraction of the peak along a ruler are ignored
+/// when counting zero crossings.
+pub const ZERO_CROSSING_THRESHOLD: f32 = 0.05;
+/// Spacing in cells between gradient arrows.
+pub const GRADIENT_ARROW_SPACING: usize = 12;
+/// Gradients weaker than this (field units per cell) get no arrow.
+pub const GRADIENT_MIN_MAGNITUDE: f32 = 0.005;
+/// Flux arrows weaker than this fraction of the strongest are skipped.
+pub const FLUX_MIN_FRACTION: f32 = 0.02;
+/// Simulated seconds between field history snapshots.
+pub const HISTORY_INTERVAL: f32 = 0.25;
+/// Field history snapshots kept, 5 s at the default interval. Each holds
+/// two f32 time levels: 0.5 MB at 200 cells high (320 × 200), 2 MB at 400
+/// and 8 MB at 800, so a full ring costs 10, 40 or 160 MB.
+pub const HISTORY_CAPACITY: usize = 20;
+/// Width σ of the Gaussian apodization, as a fraction of the aperture's
+/// half-length; the ends get exp(−1/2σ²) ≈ 4% of the center's amplitude.
+pub const APODIZATION_SIGMA: f32 = 0.4;
+/// Spacing of phased-array elements in default-resolution cells.
+pub const PHASED_ARRAY_SPACING: f32 = 8.0;
+/// Defaults for headless dataset runs: the most FDTD steps per sample, and
+/// the relative change between successive intensity averages below which a
+/// run counts as steady.
+pub const DATASET_MAX_STEPS: usize = 6000;
+pub const DATASET_TOLERANCE: f32 = 0.01;
+/// Shortest window, in FDTD steps, that dataset intensity is averaged over;
+/// it is lengthened to a whole number of source periods.
+pub const DATASET_WINDOW: usize = 200;
+/// Scene edits kept for undo.
+pub const UNDO_DEPTH: usize = 100;
+/// Wavefront circles drawn around each point source.
+pub const HUYGENS_RINGS: usize = 6;
 
 // UI Constants
 pub const TOOLBOX_PANEL_WIDTH: f32 = 180.0;
 pub const INSPECTOR_PANEL_WIDTH: f32 = 220.0;
 pub const DATA_PANEL_HEIGHT: f32 = 150.0;
 pub const TOP_BAR_HEIGHT: f32 = 40.0; // Approximate height for calculation
+/// Width of the minimap inset in points.
+pub const MINIMAP_WIDTH: f32 = 192.0;
+/// Key and mouse bindings listed in the F1 help overlay.
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("Space", "Play / pause"),
+    ("C", "Clear waves"),
+    ("G", "Toggle grid"),
+    ("P", "Save a PNG snapshot"),
+    ("M", "Show or hide the minimap"),
+    ("Arrow keys", "Nudge the selected object 1 unit (Shift: 10)"),
+    ("Ctrl + Z", "Undo the last scene edit"),
+    ("Ctrl + Shift + Z", "Redo"),
+    ("Left click", "Place the selected tool, or select and drag"),
+    ("Shift + click", "Add to or remove from the selection"),
+    ("Right click", "Deselect / finish waypoints or a poly probe"),
+    ("Shift + drag ruler end", "Snap the ruler angle (when enabled)"),
+    ("Middle drag", "Pan the view"),
+    ("Scroll", "Zoom toward the cursor"),
+    ("F1", "Show or hide this help"),
+];
+/// Distance an arrow key moves the selected object, in world units.
+pub const NUDGE_STEP: f32 = 1.0;
+/// Distance Shift + an arrow key moves it.
+pub const NUDGE_STEP_LARGE: f32 = 10.0;
+/// Pick radius of a ruler's endpoint handles in world units.
+pub const RULER_HANDLE_RADIUS: f32 = 8.0;
+/// Angle step for Shift-snapped ruler ends.
+pub const RULER_SNAP_DEGREES: f32 = 15.0;
+/// Maxima dimmer than this fraction of a screen's brightest are not fringes.
+pub const FRINGE_THRESHOLD: f32 = 0.1;
+/// Samples the aperture is zero-padded to for its far-field FFT.
+pub const FAR_FIELD_FFT_SIZE: usize = 2048;
+/// Starting radius of the arc the simulated pattern is sampled on.
+pub const FAR_FIELD_RADIUS: f32 = 200.0;
+/// Default |u| at which a timed pulse counts as having reached a probe.
+pub const ARRIVAL_THRESHOLD: f32 = 0.05;
+/// Seconds a setting must hold still before it is written to disk.
+pub const SETTINGS_SAVE_DELAY: f32 = 0.5;
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Simulation Definition
@@ -45,7 +192,7 @@ pub struct RippleTankSimulation;
 
 impl Simulation for RippleTankSimulation {
     fn id(&self) -> &'static str {
-        "ripple_tank"
+        SIMULATION_ID
     }
 
     fn name(&self) -> &'static str {
@@ -84,6 +231,23 @@ impl Simulation for RippleTankSimulation {
                 step: Some(0.001),
                 unit: None,
             },
+            ParameterDef::Int {
+                id: "pml_width",
+                name: "Absorbing Border",
+                description: "Width of the absorbing boundary layer in cells (0 = reflective edges)",
+                min: 0,
+                max: 64,
+                default: DEFAULT_PML_WIDTH as i32,
+                step: None,
+                logarithmic: false,
+            },
+            ParameterDef::Enum {
+                id: "boundary_mode",
+                name: "Boundary",
+                description: "Absorbing edges for an open tank, reflecting walls for standing waves, or periodic edges that wrap around",
+                options: &["Absorbing", "Reflecting", "Periodic"],
+                default_index: 0,
+            },
             ParameterDef::Float {
                 id: "time_scale",
                 name: "Time Scale",
@@ -94,6 +258,13 @@ impl Simulation for RippleTankSimulation {
                 step: Some(0.1),
                 unit: None,
             },
+            ParameterDef::Enum {
+                id: "color_scheme",
+                name: "Color Scheme",
+                description: "Colormap used to display the wave field",
+                options: &["Deep Ocean", "Scientific", "Phase Color", "Grayscale", "Intensity"],
+                default_index: 0,
+            },
             ParameterDef::Bool {
                 id: "paused",
                 name: "Paused",
@@ -103,6 +274,38 @@ impl Simulation for RippleTankSimulation {
         ]
     }
 
+    fn presets(&self) -> Vec<ParameterPreset> {
+        vec![
+            (
+                "Double Slit Classic",
+                vec![
+                    ("wave_speed", ParameterValue::Float(1.0)),
+                    ("damping", ParameterValue::Float(0.999)),
+                    ("color_scheme", ParameterValue::Enum(1)),
+                    ("paused", ParameterValue::Bool(false)),
+                ],
+            ),
+            (
+                "Interference Intensity",
+                vec![
+                    ("damping", ParameterValue::Float(1.0)),
+                    ("time_scale", ParameterValue::Float(1.5)),
+                    ("color_scheme", ParameterValue::Enum(4)),
+                    ("paused", ParameterValue::Bool(false)),
+                ],
+            ),
+            (
+                "Slow Motion Study",
+                vec![
+                    ("wave_speed", ParameterValue::Float(0.5)),
+                    ("time_scale", ParameterValue::Float(0.3)),
+                    ("color_scheme", ParameterValue::Enum(3)),
+                    ("paused", ParameterValue::Bool(true)),
+                ],
+            ),
+        ]
+    }
+
     fn build_plugin(&self) -> Box<dyn Fn(&mut App) + Send + Sync> {
         Box::new(|app| {
             app.add_plugins(RippleTankPlugin);
@@ -114,7 +317,16 @@ impl Simulation for RippleTankSimulation {
     }
 
     fn tags(&self) -> &'static [&'static str] {
-        &["waves", "interference", "diffraction", "ripple", "huygen", "doppler"]
+        &["waves", "interference", "diffraction", "ripple", "huygen", "doppler", "standing", "resonance"]
+    }
+
+    fn references(&self) -> &'static [&'static str] {
+        &[
+            "K. S. Yee, \"Numerical solution of initial boundary value problems involving Maxwell's equations \
+             in isotropic media,\" IEEE Trans. Antennas Propag. 14(3), 302–307 (1966)",
+            "A. Taflove and S. C. Hagness, Computational Electrodynamics: The Finite-Difference Time-Domain \
+             Method, 3rd ed., Artech House (2005)",
+        ]
     }
 }
 
@@ -126,32 +338,93 @@ pub struct RippleTankPlugin;
 
 impl Plugin for RippleTankPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<CameraControllerPlugin>() {
+            app.add_plugins(CameraControllerPlugin);
+        }
+        if !app.is_plugin_added::<ShortcutsOverlayPlugin>() {
+            app.add_plugins(ShortcutsOverlayPlugin);
+        }
+        if !app.is_plugin_added::<VectorFieldPlugin>() {
+            app.add_plugins(VectorFieldPlugin);
+        }
+        if !app.is_plugin_added::<AdaptiveQualityPlugin>() {
+            app.add_plugins(AdaptiveQualityPlugin);
+        }
+        if !app.is_plugin_added::<DataRecorderPlugin>() {
+            app.add_plugins(DataRecorderPlugin);
+        }
+        if !app.is_plugin_added::<SplitViewportPlugin>() {
+            app.add_plugins(SplitViewportPlugin);
+        }
+
         app.init_resource::<WaveField>()
+            .init_resource::<DispersiveFields>()
+            .init_resource::<SlitComparison>()
             .init_resource::<RippleTankConfig>()
             .init_resource::<UIState>()
             .init_resource::<SimulationStats>()
+            .init_resource::<Spectrogram>()
+            .init_resource::<FieldHistory>()
+            .init_resource::<UndoHistory>()
+            .init_resource::<TimeOfFlight>()
+            .insert_resource(FixedTimestep::new(FDTD_STEP, MAX_STEPS_PER_FRAME))
             .init_resource::<ObjectIdCounter>()
+            .init_resource::<PendingSceneLoad>()
+            .init_resource::<PendingMaskImport>()
             .register_type::<WaveSource>()
             .register_type::<Obstacle>()
             .register_type::<Probe>()
-            .add_systems(Startup, setup_scene)
-            .add_systems(
-                Update,
+            .register_type::<Screen>()
+            .register_type::<PolyProbe>()
+            .add_simulation_setup(SIMULATION_ID, setup_scene)
+            .add_simulation_cleanup(SIMULATION_ID, teardown_scene)
+            .add_simulation_systems(
+                SIMULATION_ID,
                 (
-                    handle_keyboard_input,
+                    (handle_keyboard_input, nudge_selected_object),
                     handle_mouse_input,
-                    update_moving_sources,
-                    rasterize_obstacles,
-                    apply_wave_sources,
+                    (apply_loaded_scene, apply_imported_mask),
+                    apply_grid_size,
+                    (sync_obstacle_sprites, sync_image_mask_sprites),
+                    sync_ruler_sprites,
+                    // Bands copy the media the tank was just stamped with.
+                    (rasterize_obstacles, update_dispersive_fields).chain(),
                     update_wave_field,
-                    update_probes,
+                    step_wave_field_backward,
+                    update_field_history.after(update_wave_field).after(step_wave_field_backward),
                     update_wave_visualization,
+                    draw_snell_overlay,
+                    draw_ruler_overlay,
+                    (update_gradient_field, update_flux_field),
+                    draw_huygens_overlay,
+                    draw_split_divider,
+                    (draw_screen_overlay, draw_poly_probe_overlay, draw_selection_overlay),
                     update_stats,
+                    update_spectrogram,
+                    (update_screens, update_poly_probes),
+                ),
+            )
+            .add_simulation_systems(
+                SIMULATION_ID,
+                (
+                    persist_settings,
                     render_top_bar_ui,
                     render_toolbox_ui,
                     render_inspector_ui,
                     render_data_panel_ui,
+                    render_far_field_ui,
+                    // Placed in what the panels leave of the screen.
+                    render_minimap_ui.after(render_inspector_ui).after(render_data_panel_ui),
                     fit_camera_to_viewport,
+                    update_undo_history.after(render_inspector_ui).after(render_data_panel_ui).after(handle_mouse_input),
+                ),
+            )
+            .add_simulation_systems(
+                SIMULATION_ID,
+                (
+                    sync_slit_comparison.after(apply_grid_size),
+                    rasterize_slit_comparison.after(sync_slit_comparison).after(rasterize_obstacles).before(update_wave_field),
+                    update_comparison_visualization.after(update_wave_field),
                 ),
             );
     }
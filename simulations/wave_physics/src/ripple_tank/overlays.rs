@@ -0,0 +1,369 @@
+//! Gizmo overlays drawn on top of the wave field
+
+use bevy::prelude::*;
+use ez_renderer::vector_field::VectorField;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use super::{
+    components::*, nearest_enabled_source, physics::split_column, resources::*, wave_speed_world, FLUX_MIN_FRACTION, GRADIENT_ARROW_SPACING, GRADIENT_MIN_MAGNITUDE, HUYGENS_RINGS,
+    RULER_HANDLE_RADIUS,
+};
+
+/// Length of the ray drawn after it leaves a refraction block.
+const SNELL_RAY_LENGTH: f32 = 200.0;
+/// Length of the surface normal drawn at the entry point.
+const SNELL_NORMAL_LENGTH: f32 = 30.0;
+/// Radius of the ring around each selected object.
+const SELECTION_RING_RADIUS: f32 = 18.0;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Snell Overlay
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Refracts a unit `direction` crossing a surface from index `n1` into `n2`.
+///
+/// `normal` is the unit surface normal on the incoming side. Returns `None`
+/// on total internal reflection, when `n1/n2 · sin θ₁` exceeds 1.
+pub fn snell_refract(direction: Vec2, normal: Vec2, n1: f32, n2: f32) -> Option<Vec2> {
+    let cos_incident = -normal.dot(direction);
+    let ratio = n1 / n2;
+    let sin2_refracted = ratio * ratio * (1.0 - cos_incident * cos_incident);
+    if sin2_refracted > 1.0 {
+        return None;
+    }
+    Some(ratio * direction + (ratio * cos_incident - (1.0 - sin2_refracted).sqrt()) * normal)
+}
+
+/// Mirrors a direction about a surface with unit `normal`.
+pub fn reflect(direction: Vec2, normal: Vec2) -> Vec2 {
+    direction - 2.0 * direction.dot(normal) * normal
+}
+
+/// Where a ray crosses an axis-aligned box centered on the origin.
+struct BoxCrossing {
+    t_enter: f32,
+    /// Outward normal of the face the ray enters through.
+    enter_normal: Vec2,
+    t_exit: f32,
+    /// Outward normal of the face the ray leaves through.
+    exit_normal: Vec2,
+}
+
+fn ray_box(origin: Vec2, direction: Vec2, half_size: Vec2) -> Option<BoxCrossing> {
+    let mut crossing = BoxCrossing {
+        t_enter: f32::NEG_INFINITY,
+        enter_normal: Vec2::ZERO,
+        t_exit: f32::INFINITY,
+        exit_normal: Vec2::ZERO,
+    };
+
+    for (axis, unit) in [(0, Vec2::X), (1, Vec2::Y)] {
+        let (o, d, h) = (origin[axis], direction[axis], half_size[axis]);
+        if d.abs() < f32::EPSILON {
+            if o.abs() > h {
+                return None;
+            }
+            continue;
+        }
+        let (near, far) = if d > 0.0 { (-h, h) } else { (h, -h) };
+        let t_near = (near - o) / d;
+        let t_far = (far - o) / d;
+        if t_near > crossing.t_enter {
+            crossing.t_enter = t_near;
+            crossing.enter_normal = -d.signum() * unit;
+        }
+        if t_far < crossing.t_exit {
+            crossing.t_exit = t_far;
+            crossing.exit_normal = d.signum() * unit;
+        }
+    }
+
+    (crossing.t_enter <= crossing.t_exit && crossing.t_exit > 0.0).then_some(crossing)
+}
+
+/// Draws the Snell's-law prediction for a ray from the nearest source
+/// through each refraction block.
+///
+/// The ray refracts on entry, crosses the block, and refracts out again, or
+/// reflects (drawn in red) where total internal reflection occurs.
+pub fn draw_snell_overlay(
+    config: Res<RippleTankConfig>,
+    blocks: Query<(&Transform, &Obstacle)>,
+    sources: Query<(&Transform, &WaveSource)>,
+    mut gizmos: Gizmos,
+) {
+    if !config.show_snell_overlay {
+        return;
+    }
+
+    for (transform, block) in blocks.iter() {
+        if block.obstacle_type != ObstacleType::RefractionBlock {
+            continue;
+        }
+        let center = transform.translation.truncate();
+        let Some((source, wave_source)) = nearest_enabled_source(&sources, center) else { continue };
+        let refractive_index = block.refractive_index_at(wave_source.frequency);
+
+        let to_world = Vec2::from_angle(block.rotation);
+        let to_local = Vec2::from_angle(-block.rotation);
+        let world = |local: Vec2| center + to_world.rotate(local);
+
+        let half_size = Vec2::new(block.width, block.height) / 2.0;
+        let origin = to_local.rotate(source - center);
+        let incident = to_local.rotate((center - source).normalize_or_zero());
+
+        let Some(entry) = ray_box(origin, incident, half_size) else { continue };
+        if entry.t_enter <= 0.0 {
+            // The source sits inside the block.
+            continue;
+        }
+        let entry_point = origin + incident * entry.t_enter;
+        let Some(inside) = snell_refract(incident, entry.enter_normal, 1.0, refractive_index) else {
+            continue;
+        };
+
+        let Some(exit) = ray_box(entry_point, inside, half_size) else { continue };
+        let exit_point = entry_point + inside * exit.t_exit;
+
+        gizmos.line_2d(source, world(entry_point), Color::WHITE);
+        gizmos.line_2d(
+            world(entry_point - entry.enter_normal * SNELL_NORMAL_LENGTH),
+            world(entry_point + entry.enter_normal * SNELL_NORMAL_LENGTH),
+            Color::srgba(0.7, 0.7, 0.7, 0.6),
+        );
+        gizmos.line_2d(world(entry_point), world(exit_point), Color::srgb(0.2, 1.0, 1.0));
+
+        match snell_refract(inside, -exit.exit_normal, refractive_index, 1.0) {
+            Some(outgoing) => gizmos.line_2d(
+                world(exit_point),
+                world(exit_point + outgoing * SNELL_RAY_LENGTH),
+                Color::srgb(1.0, 0.9, 0.2),
+            ),
+            None => gizmos.line_2d(
+                world(exit_point),
+                world(exit_point + reflect(inside, exit.exit_normal) * SNELL_RAY_LENGTH),
+                Color::srgb(1.0, 0.3, 0.3),
+            ),
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Ruler Overlay
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Draws each ruler's endpoint handles and ticks where the field crosses zero.
+pub fn draw_ruler_overlay(
+    wave_field: Res<WaveField>,
+    rulers: Query<(&Transform, &Ruler), Without<Screen>>,
+    mut gizmos: Gizmos,
+) {
+    for (transform, ruler) in rulers.iter() {
+        let (start, end) = ruler.world_endpoints(transform.translation.truncate());
+        for handle in [start, end] {
+            gizmos.circle_2d(handle, RULER_HANDLE_RADIUS, Color::srgb(1.0, 1.0, 0.3));
+        }
+
+        let direction = (end - start).normalize_or_zero();
+        let tick = direction.perp() * 6.0;
+        for distance in wave_field.zero_crossings(start, end) {
+            let point = start + direction * distance;
+            gizmos.line_2d(point - tick, point + tick, Color::WHITE);
+        }
+    }
+}
+
+/// Draws each poly probe's path with a dot at every vertex.
+pub fn draw_poly_probe_overlay(probes: Query<(&Transform, &PolyProbe)>, mut gizmos: Gizmos) {
+    let color = Color::srgb(1.0, 0.4, 1.0);
+    for (transform, probe) in probes.iter() {
+        let center = transform.translation.truncate();
+        gizmos.linestrip_2d(probe.world_points(center), color);
+        for point in probe.world_points(center) {
+            gizmos.circle_2d(point, 3.0, color);
+        }
+    }
+}
+
+/// Rings every selected object: yellow, or grey while locked.
+pub fn draw_selection_overlay(ui_state: Res<UIState>, objects: Query<(&Transform, &SceneObject)>, mut gizmos: Gizmos) {
+    for (transform, object) in objects.iter_many(&ui_state.selection) {
+        let color = if object.locked { Color::srgb(0.6, 0.6, 0.6) } else { Color::srgb(1.0, 0.85, 0.2) };
+        gizmos.circle_2d(transform.translation.truncate(), SELECTION_RING_RADIUS, color);
+    }
+}
+
+/// Draws each screen's handles, a tick at every bright fringe found, and a
+/// longer cyan tick where d·sinθ = mλ predicts one.
+pub fn draw_screen_overlay(screens: Query<(&Transform, &Ruler, &Screen)>, mut gizmos: Gizmos) {
+    for (transform, ruler, screen) in screens.iter() {
+        let (start, end) = ruler.world_endpoints(transform.translation.truncate());
+        for handle in [start, end] {
+            gizmos.circle_2d(handle, RULER_HANDLE_RADIUS, Color::srgb(0.9, 0.9, 1.0));
+        }
+
+        let direction = (end - start).normalize_or_zero();
+        let normal = direction.perp();
+        for fringe in &screen.fringes {
+            let point = start + direction * fringe.distance;
+            gizmos.line_2d(point - normal * 6.0, point + normal * 6.0, Color::WHITE);
+        }
+        for &(_, distance) in &screen.predicted {
+            let point = start + direction * distance;
+            gizmos.line_2d(point - normal * 12.0, point - normal * 4.0, Color::srgb(0.2, 1.0, 1.0));
+            gizmos.line_2d(point + normal * 4.0, point + normal * 12.0, Color::srgb(0.2, 1.0, 1.0));
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Gradient Overlay
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Fills the shared [`VectorField`] with the wave field's gradient on a
+/// coarse lattice, scaled so the steepest arrow spans one lattice spacing.
+pub fn update_gradient_field(
+    config: Res<RippleTankConfig>,
+    wave_field: Res<WaveField>,
+    mut vector_field: ResMut<VectorField>,
+) {
+    if !config.show_gradient {
+        if !config.show_flux && !vector_field.is_empty() {
+            vector_field.clear();
+        }
+        return;
+    }
+
+    vector_field.clear();
+    let (width, height) = (wave_field.width, wave_field.height);
+    let half_size = Vec2::new(width as f32, height as f32) / 2.0;
+    let spacing = GRADIENT_ARROW_SPACING;
+    for y in (spacing / 2..height - 1).step_by(spacing) {
+        for x in (spacing / 2..width - 1).step_by(spacing) {
+            let field = &wave_field.current;
+            let gradient = Vec2::new(
+                field[wave_field.idx(x + 1, y)] - field[wave_field.idx(x - 1, y)],
+                field[wave_field.idx(x, y + 1)] - field[wave_field.idx(x, y - 1)],
+            ) / 2.0;
+            let origin = (Vec2::new(x as f32, y as f32) + 0.5 - half_size) * wave_field.cell_size;
+            vector_field.push(origin.extend(1.0), gradient.extend(0.0));
+        }
+    }
+
+    let max_magnitude = vector_field.max_magnitude().max(GRADIENT_MIN_MAGNITUDE);
+    vector_field.scale = spacing as f32 * wave_field.cell_size / max_magnitude;
+    vector_field.min_magnitude = GRADIENT_MIN_MAGNITUDE;
+}
+
+/// Fills the shared [`VectorField`] with the energy flux averaged over each
+/// cell of the arrow lattice, scaled so the strongest arrow spans one
+/// lattice spacing.
+///
+/// The flux pulses with the waves, so averaging over a block of cells
+/// keeps the arrows from blinking out at every crest and trough.
+pub fn update_flux_field(config: Res<RippleTankConfig>, wave_field: Res<WaveField>, mut vector_field: ResMut<VectorField>) {
+    if !config.show_flux {
+        if !config.show_gradient && !vector_field.is_empty() {
+            vector_field.clear();
+        }
+        return;
+    }
+
+    vector_field.clear();
+    let (width, height) = (wave_field.width, wave_field.height);
+    let half_size = Vec2::new(width as f32, height as f32) / 2.0;
+    let spacing = GRADIENT_ARROW_SPACING;
+    for y0 in (1..height - 1).step_by(spacing) {
+        let y1 = (y0 + spacing).min(height - 1);
+        for x0 in (1..width - 1).step_by(spacing) {
+            let x1 = (x0 + spacing).min(width - 1);
+            let mut flux = Vec2::ZERO;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    flux += wave_field.energy_flux(x, y);
+                }
+            }
+            flux /= ((x1 - x0) * (y1 - y0)) as f32;
+            let center = Vec2::new((x0 + x1) as f32, (y0 + y1) as f32) / 2.0;
+            vector_field.push(((center - half_size) * wave_field.cell_size).extend(1.0), flux.extend(0.0));
+        }
+    }
+
+    let max_magnitude = vector_field.max_magnitude();
+    if max_magnitude <= 0.0 {
+        vector_field.clear();
+        return;
+    }
+    vector_field.scale = spacing as f32 * wave_field.cell_size / max_magnitude;
+    vector_field.min_magnitude = max_magnitude * FLUX_MIN_FRACTION;
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Split View Divider
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Draws the line between the two halves of the split color-scheme view.
+pub fn draw_split_divider(config: Res<RippleTankConfig>, wave_field: Res<WaveField>, mut gizmos: Gizmos) {
+    if !config.split_view {
+        return;
+    }
+
+    let column = split_column(wave_field.width, config.split_position);
+    let x = (column as f32 - wave_field.width as f32 / 2.0) * wave_field.cell_size;
+    let half_height = wave_field.height as f32 * wave_field.cell_size / 2.0;
+    gizmos.line_2d(Vec2::new(x, -half_height), Vec2::new(x, half_height), Color::WHITE);
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Huygens Overlay
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Draws the crests expanding from each point source, one wavelength apart.
+///
+/// A crest leaves the source whenever its phase passes π/2 and travels at
+/// the wave speed, so the innermost radius follows the source's clock and
+/// the circles stay on the crests in the field. Outer circles fade out.
+pub fn draw_huygens_overlay(
+    config: Res<RippleTankConfig>,
+    stats: Res<SimulationStats>,
+    sources: Query<(&Transform, &WaveSource)>,
+    mut gizmos: Gizmos,
+) {
+    if !config.show_huygens || stats.sample_interval <= 0.0 {
+        return;
+    }
+
+    let wave_speed = wave_speed_world(&config, stats.sample_interval);
+    let any_solo = sources.iter().any(|(_, source)| source.solo);
+    for (transform, source) in sources.iter() {
+        if !source.is_audible(any_solo) || source.source_type != WaveSourceType::Point || source.frequency <= 0.0 {
+            continue;
+        }
+        let center = transform.translation.truncate();
+        let wavelength = wave_speed / source.frequency;
+        let since_crest = (source.clock_phase + source.phase - FRAC_PI_2).rem_euclid(TAU) / TAU;
+        for ring in 0..HUYGENS_RINGS {
+            let radius = (since_crest + ring as f32) * wavelength;
+            let alpha = 0.8 * (1.0 - radius / (HUYGENS_RINGS as f32 * wavelength));
+            gizmos.circle_2d(center, radius, Color::srgba(1.0, 1.0, 1.0, alpha));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snell_angles_and_total_internal_reflection() {
+        // 30° incidence from air into glass: sin θ₂ = sin 30° / 1.5
+        let incident = Vec2::new(30f32.to_radians().sin(), -30f32.to_radians().cos());
+        let refracted = snell_refract(incident, Vec2::Y, 1.0, 1.5).expect("air to glass refracts");
+        assert!((refracted.x - 0.5 / 1.5).abs() < 1e-5);
+        assert!((refracted.length() - 1.0).abs() < 1e-5);
+
+        // 60° from glass into air exceeds the critical angle (41.8°).
+        let steep = Vec2::new(60f32.to_radians().sin(), -60f32.to_radians().cos());
+        assert_eq!(snell_refract(steep, Vec2::Y, 1.5, 1.0), None);
+    }
+}
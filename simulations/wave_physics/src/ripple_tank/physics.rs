@@ -1,10 +1,21 @@
 //! Physics systems for wave propagation using FDTD method
 
 use bevy::prelude::*;
+use ez_core::math::magnitude_spectrum;
+use ez_core::quality::AdaptiveQuality;
+use ez_core::recording::DataRecorder;
+use ez_core::registry::SimulationEntity;
+use ez_physics::timestep::FixedTimestep;
+use ez_renderer::camera::Pan2dCamera;
+use ez_renderer::materials::{inferno, turbo};
+use ez_renderer::vector_field::VectorField;
+use ez_renderer::viewport::{camera_under_cursor, SplitViewportConfig};
 use std::f32::consts::PI;
 
 use super::{
-    components::*, resources::*, GRID_SCALE, GRID_WIDTH, GRID_HEIGHT, MAX_PROBE_HISTORY,
+    comparison::SlitComparison, components::*, fringes::*, image_mask::stamp_image_mask, resources::*, undo::*, BoundaryCondition, BOUNDARY_TINT_OPACITY, FDTD_STEP, GRID_SCALE, INTENSITY_AVERAGING, MAX_COURANT, MAX_PROBE_HISTORY, MAX_STEPS_PER_FRAME,
+    MIN_SPECTRUM_SAMPLES, PHASED_ARRAY_SPACING, PML_MAX_DAMPING, RULER_HANDLE_RADIUS, RULER_SNAP_DEGREES, SIMULATION_ID, SPECTROGRAM_HOP, SPECTRUM_SIZE, TANK_HEIGHT, TANK_WIDTH,
+    UNSTABLE_CLAMPED_CELLS,
 };
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -14,44 +25,34 @@ use super::{
 pub fn setup_scene(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
+    wave_field: Res<WaveField>,
 ) {
     // 2D orthographic camera
-    commands.spawn(Camera2dBundle {
-        transform: Transform::from_xyz(0.0, 0.0, 100.0),
-        projection: OrthographicProjection {
-            scale: 1.5,
+    commands.spawn((
+        Camera2dBundle {
+            transform: Transform::from_xyz(0.0, 0.0, 100.0),
+            projection: OrthographicProjection {
+                scale: 1.5,
+                ..default()
+            },
             ..default()
         },
-        ..default()
-    });
+        Pan2dCamera::default(),
+        SimulationEntity(SIMULATION_ID),
+    ));
 
     // Create wave field visualization texture
-    let size = bevy::render::render_resource::Extent3d {
-        width: GRID_WIDTH as u32,
-        height: GRID_HEIGHT as u32,
-        depth_or_array_layers: 1,
-    };
-
-    let mut image = Image::new_fill(
-        size,
-        bevy::render::render_resource::TextureDimension::D2,
-        &[0, 50, 100, 255],
-        bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
-        bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD
-            | bevy::render::render_asset::RenderAssetUsages::MAIN_WORLD,
-    );
-    image.sampler = bevy::render::texture::ImageSampler::nearest();
-
-    let image_handle = images.add(image);
+    let image_handle = images.add(wave_field_image(&wave_field));
 
     // Spawn wave field sprite
     commands.spawn((
         SpriteBundle {
             texture: image_handle.clone(),
-            transform: Transform::from_scale(Vec3::splat(GRID_SCALE)),
+            transform: Transform::from_scale(Vec3::splat(wave_field.cell_size)),
             ..default()
         },
         WaveFieldVisual { texture: image_handle },
+        SimulationEntity(SIMULATION_ID),
     ));
 
     // Spawn default point source
@@ -67,7 +68,59 @@ pub fn setup_scene(
         },
         WaveSource::default(),
         SceneObject { id: 0, selected: false, locked: false },
+        SimulationEntity(SIMULATION_ID),
     ));
+    commands.insert_resource(UndoHistory::default());
+    commands.insert_resource(TimeOfFlight::default());
+}
+
+/// Texture a wave field is drawn into, one pixel per cell.
+pub fn wave_field_image(wave_field: &WaveField) -> Image {
+    let size = bevy::render::render_resource::Extent3d {
+        width: wave_field.width as u32,
+        height: wave_field.height as u32,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image::new_fill(
+        size,
+        bevy::render::render_resource::TextureDimension::D2,
+        &[0, 50, 100, 255],
+        bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
+        bevy::render::render_asset::RenderAssetUsages::RENDER_WORLD
+            | bevy::render::render_asset::RenderAssetUsages::MAIN_WORLD,
+    );
+    image.sampler = bevy::render::texture::ImageSampler::nearest();
+    image
+}
+
+/// Forgets the entities the UI was pointing at, which are despawned on
+/// leaving the simulation, so re-entering starts with nothing selected.
+/// The shared arrows are cleared too, or they would keep drawing over the
+/// next simulation, and a slit comparison is closed so the next simulation
+/// gets the whole window.
+pub fn teardown_scene(
+    mut ui_state: ResMut<UIState>,
+    mut wave_field: ResMut<WaveField>,
+    mut dispersive: ResMut<DispersiveFields>,
+    mut comparison: ResMut<SlitComparison>,
+    split: Option<ResMut<SplitViewportConfig>>,
+    mut vector_field: ResMut<VectorField>,
+) {
+    ui_state.select(None);
+    ui_state.dragging = None;
+    ui_state.dragging_ruler_end = None;
+    ui_state.placing_waypoints = false;
+    ui_state.placing_poly_probe = None;
+    wave_field.clear();
+    dispersive.bands.clear();
+    if comparison.field.take().is_some() {
+        ui_state.compare_without_slits = false;
+        if let Some(mut split) = split {
+            split.enabled = false;
+        }
+    }
+    vector_field.clear();
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -78,7 +131,19 @@ pub fn handle_keyboard_input(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut config: ResMut<RippleTankConfig>,
     mut wave_field: ResMut<WaveField>,
+    mut ui_state: ResMut<UIState>,
+    mut undo: ResMut<UndoHistory>,
+    mut contexts: bevy_egui::EguiContexts,
 ) {
+    // Keys typed into a text field belong to it.
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    if ctrl && keyboard.just_pressed(KeyCode::KeyZ) {
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        undo.request = Some(if shift { UndoRequest::Redo } else { UndoRequest::Undo });
+    }
     if keyboard.just_pressed(KeyCode::Space) {
         config.paused = !config.paused;
     }
@@ -88,214 +153,551 @@ pub fn handle_keyboard_input(
     if keyboard.just_pressed(KeyCode::KeyG) {
         config.show_grid = !config.show_grid;
     }
+    if keyboard.just_pressed(KeyCode::KeyP) {
+        super::snapshot::export_snapshot(&wave_field, config.color_scheme);
+    }
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        ui_state.show_minimap = !ui_state.show_minimap;
+    }
 }
 
+/// Moves the selected objects by [`NUDGE_STEP`] per arrow key press, or
+/// [`NUDGE_STEP_LARGE`] with Shift. Locked objects stay put.
+///
+/// [`NUDGE_STEP`]: super::NUDGE_STEP
+/// [`NUDGE_STEP_LARGE`]: super::NUDGE_STEP_LARGE
+pub fn nudge_selected_object(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_state: Res<UIState>,
+    mut objects: Query<(&SceneObject, &mut Transform)>,
+    mut contexts: bevy_egui::EguiContexts,
+) {
+    let direction: Vec2 = [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keyboard.just_pressed(*key))
+    .map(|(_, direction)| direction)
+    .sum();
+    if direction == Vec2::ZERO || contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let step = if shift { super::NUDGE_STEP_LARGE } else { super::NUDGE_STEP };
+    let mut objects = objects.iter_many_mut(&ui_state.selection);
+    while let Some((object, mut transform)) = objects.fetch_next() {
+        if !object.locked {
+            transform.translation += (direction * step).extend(0.0);
+        }
+    }
+}
+
+/// `entity` and every other object in its group.
+pub fn group_members(entity: Entity, groups: &Query<(Entity, &SceneGroup)>) -> Vec<Entity> {
+    match groups.get(entity) {
+        Ok((_, group)) => groups.iter().filter(|(_, other)| *other == group).map(|(member, _)| member).collect(),
+        Err(_) => vec![entity],
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn handle_mouse_input(
     mouse_button: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut ui_state: ResMut<UIState>,
     mut commands: Commands,
     mut object_id: ResMut<ObjectIdCounter>,
     mut scene_objects: Query<(Entity, &mut Transform, &SceneObject)>,
+    mut moving: Query<&mut MovingSource>,
+    mut rulers: Query<(Entity, &mut Ruler)>,
+    mut poly_probes: Query<&mut PolyProbe>,
+    groups: Query<(Entity, &SceneGroup)>,
     mut contexts: bevy_egui::EguiContexts,
 ) {
     if contexts.ctx_mut().is_pointer_over_area() {
         return;
     }
+    ui_state.selection.retain(|&entity| scene_objects.contains(entity));
 
     let Ok(window) = windows.get_single() else { return };
-    let Ok((camera, camera_transform)) = camera_q.get_single() else { return };
     let Some(cursor_pos) = window.cursor_position() else { return };
+    // Either half of a slit comparison maps the cursor to the same tank.
+    let Some((camera, camera_transform)) = camera_under_cursor(camera_q.iter(), cursor_pos) else { return };
     let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else { return };
 
+    if ui_state.placing_waypoints {
+        let selected = ui_state.selected_entity.and_then(|entity| moving.get_mut(entity).ok());
+        match selected {
+            Some(mut source) => {
+                if mouse_button.just_pressed(MouseButton::Left) {
+                    source.waypoints.push(world_pos);
+                }
+                if mouse_button.just_pressed(MouseButton::Right) {
+                    ui_state.placing_waypoints = false;
+                }
+                return;
+            }
+            None => ui_state.placing_waypoints = false,
+        }
+    }
+
+    if let Some(entity) = ui_state.placing_poly_probe {
+        let anchor = scene_objects.get(entity).map(|(_, transform, _)| transform.translation.truncate());
+        match (anchor, poly_probes.get_mut(entity)) {
+            (Ok(anchor), Ok(mut probe)) => {
+                if mouse_button.just_pressed(MouseButton::Left) {
+                    probe.points.push(world_pos - anchor);
+                }
+                if mouse_button.just_pressed(MouseButton::Right) {
+                    ui_state.placing_poly_probe = None;
+                }
+                return;
+            }
+            _ => ui_state.placing_poly_probe = None,
+        }
+    }
+
     if mouse_button.just_pressed(MouseButton::Left) {
         match ui_state.selected_tool {
             ToolType::Select => {
-                let mut found = None;
-                for (entity, transform, _) in scene_objects.iter() {
-                    if transform.translation.truncate().distance(world_pos) < 15.0 {
-                        found = Some(entity);
-                        break;
+                // Locked objects yield clicks to unlocked ones, so ones behind
+                // them can be picked; on their own they can still be selected
+                // (to unlock them) but not dragged.
+                ui_state.dragging_ruler_end = rulers.iter().find_map(|(entity, ruler)| {
+                    let (_, transform, object) = scene_objects.get(entity).ok()?;
+                    if object.locked {
+                        return None;
                     }
+                    let center = transform.translation.truncate();
+                    [RulerEnd::Start, RulerEnd::End]
+                        .into_iter()
+                        .find(|&end| (center + ruler.endpoint(end)).distance(world_pos) < RULER_HANDLE_RADIUS)
+                        .map(|end| (entity, end))
+                });
+                if let Some((entity, _)) = ui_state.dragging_ruler_end {
+                    ui_state.select(Some(entity));
+                    return;
                 }
-                ui_state.selected_entity = found;
-                if let Some(entity) = found {
-                    ui_state.dragging = Some(entity);
-                    if let Ok((_, transform, _)) = scene_objects.get(entity) {
+
+                let found = scene_objects
+                    .iter()
+                    .filter(|(_, transform, _)| transform.translation.truncate().distance(world_pos) < 15.0)
+                    .min_by_key(|(_, _, object)| object.locked)
+                    .map(|(entity, _, _)| entity);
+                let members = found.map_or_else(Vec::new, |entity| group_members(entity, &groups));
+                let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+                match found {
+                    Some(entity) if shift && ui_state.selection.contains(&entity) => {
+                        ui_state.selection.retain(|selected| !members.contains(selected));
+                        ui_state.selected_entity = ui_state.selection.last().copied();
+                    }
+                    Some(entity) if shift => {
+                        let added: Vec<Entity> = members.into_iter().filter(|member| !ui_state.selection.contains(member)).collect();
+                        ui_state.selection.extend(added);
+                        ui_state.selected_entity = Some(entity);
+                    }
+                    // Clicking into a selection keeps it, to drag it as a whole.
+                    Some(entity) if ui_state.selection.contains(&entity) => ui_state.selected_entity = Some(entity),
+                    Some(entity) => {
+                        ui_state.selection = members;
+                        ui_state.selected_entity = Some(entity);
+                    }
+                    None if shift => {}
+                    None => ui_state.select(None),
+                }
+                if let Some(entity) = found.filter(|entity| ui_state.selection.contains(entity)) {
+                    let unlocked = |entity: Entity| scene_objects.get(entity).is_ok_and(|(_, _, object)| !object.locked);
+                    let anchor = drag_anchor(entity, &ui_state.selection, unlocked);
+                    ui_state.dragging = anchor;
+                    if let Some(Ok((_, transform, _))) = anchor.map(|anchor| scene_objects.get(anchor)) {
                         ui_state.drag_offset = transform.translation.truncate() - world_pos;
                     }
                 }
             }
-            ToolType::PointSource => super::spawn::spawn_point_source(&mut commands, &mut object_id, world_pos),
-            ToolType::LineSource => super::spawn::spawn_line_source(&mut commands, &mut object_id, world_pos),
-            ToolType::PhasedArray => super::spawn::spawn_phased_array(&mut commands, &mut object_id, world_pos),
-            ToolType::MovingSource => super::spawn::spawn_moving_source(&mut commands, &mut object_id, world_pos),
-            ToolType::Reflector => super::spawn::spawn_reflector(&mut commands, &mut object_id, world_pos),
-            ToolType::SingleSlit => super::spawn::spawn_single_slit(&mut commands, &mut object_id, world_pos),
-            ToolType::DoubleSlit => super::spawn::spawn_double_slit(&mut commands, &mut object_id, world_pos),
-            ToolType::RefractionBlock => super::spawn::spawn_refraction_block(&mut commands, &mut object_id, world_pos),
-            ToolType::Probe => super::spawn::spawn_probe(&mut commands, &mut object_id, world_pos),
-            ToolType::Ruler => super::spawn::spawn_ruler(&mut commands, &mut object_id, world_pos),
+            ToolType::PointSource => {
+                super::spawn::spawn_point_source(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::LineSource => {
+                super::spawn::spawn_line_source(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::PhasedArray => {
+                super::spawn::spawn_phased_array(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::MovingSource => {
+                super::spawn::spawn_moving_source(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::Reflector => {
+                super::spawn::spawn_reflector(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::SingleSlit => {
+                super::spawn::spawn_single_slit(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::DoubleSlit => {
+                super::spawn::spawn_double_slit(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::RefractionBlock => {
+                super::spawn::spawn_refraction_block(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::GradedIndexLens => {
+                super::spawn::spawn_graded_index_lens(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::Probe => {
+                super::spawn::spawn_probe(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::PolyProbe => {
+                let entity = super::spawn::spawn_poly_probe(&mut commands, &mut object_id, world_pos);
+                ui_state.select(Some(entity));
+                ui_state.placing_poly_probe = Some(entity);
+            }
+            ToolType::Ruler => {
+                super::spawn::spawn_ruler(&mut commands, &mut object_id, world_pos);
+            }
+            ToolType::Screen => {
+                super::spawn::spawn_screen(&mut commands, &mut object_id, world_pos);
+            }
         }
     }
 
     if mouse_button.pressed(MouseButton::Left) {
-        if let Some(entity) = ui_state.dragging {
-            if let Ok((_, mut transform, obj)) = scene_objects.get_mut(entity) {
+        if let Some((entity, end)) = ui_state.dragging_ruler_end {
+            if let (Ok((_, mut transform, obj)), Ok((_, mut ruler))) =
+                (scene_objects.get_mut(entity), rulers.get_mut(entity))
+            {
                 if !obj.locked {
-                    transform.translation.x = world_pos.x + ui_state.drag_offset.x;
-                    transform.translation.y = world_pos.y + ui_state.drag_offset.y;
+                    // Move one end and re-center the ruler between both ends.
+                    let (mut start, mut finish) = ruler.world_endpoints(transform.translation.truncate());
+                    let snap = ruler.snap_angle && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+                    let place = |fixed: Vec2| {
+                        if snap { fixed + snap_angle(world_pos - fixed, RULER_SNAP_DEGREES) } else { world_pos }
+                    };
+                    match end {
+                        RulerEnd::Start => start = place(finish),
+                        RulerEnd::End => finish = place(start),
+                    }
+                    let midpoint = (start + finish) / 2.0;
+                    transform.translation.x = midpoint.x;
+                    transform.translation.y = midpoint.y;
+                    ruler.start = start - midpoint;
+                    ruler.end = finish - midpoint;
                 }
             }
         }
+        if let Some(entity) = ui_state.dragging {
+            drag_selection(entity, world_pos + ui_state.drag_offset, &ui_state.selection, &mut scene_objects);
+        }
     }
 
     if mouse_button.just_released(MouseButton::Left) {
         ui_state.dragging = None;
+        ui_state.dragging_ruler_end = None;
     }
 
     if mouse_button.just_pressed(MouseButton::Right) {
-        ui_state.selected_entity = None;
+        ui_state.select(None);
     }
 }
 
+/// Object a drag started on `clicked` follows: the clicked object itself, or
+/// when it is locked, an unlocked member of the same selection.
+///
+/// A locked anchor never moves, so its offset to the cursor would be added
+/// to the rest of the selection again every frame.
+fn drag_anchor(clicked: Entity, selection: &[Entity], unlocked: impl Fn(Entity) -> bool) -> Option<Entity> {
+    if unlocked(clicked) {
+        return Some(clicked);
+    }
+    selection.iter().copied().find(|&entity| unlocked(entity))
+}
+
+/// Moves the unlocked members of `selection` by the step that takes
+/// `anchor` to `target`; the rest of the selection follows the dragged object.
+fn drag_selection(
+    anchor: Entity,
+    target: Vec2,
+    selection: &[Entity],
+    scene_objects: &mut Query<(Entity, &mut Transform, &SceneObject)>,
+) {
+    let Ok((_, transform, _)) = scene_objects.get(anchor) else { return };
+    let delta = (target - transform.translation.truncate()).extend(0.0);
+    let mut selected = scene_objects.iter_many_mut(selection);
+    while let Some((_, mut transform, obj)) = selected.fetch_next() {
+        if !obj.locked {
+            transform.translation += delta;
+        }
+    }
+}
+
+/// Rotates `offset` to the nearest multiple of `step_degrees`, keeping its length.
+pub fn snap_angle(offset: Vec2, step_degrees: f32) -> Vec2 {
+    let step = step_degrees.to_radians();
+    let angle = (offset.to_angle() / step).round() * step;
+    Vec2::from_angle(angle) * offset.length()
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Physics Update
 // ══════════════════════════════════════════════════════════════════════════════
 
-pub fn update_moving_sources(
-    mut sources: Query<(&mut Transform, &MovingSource)>,
-    config: Res<RippleTankConfig>,
-    time: Res<Time>,
-) {
-    if config.paused { return; }
-
-    let dt = time.delta_seconds() * config.time_scale;
-    let bounds_x = (GRID_WIDTH as f32 / 2.0) * GRID_SCALE;
-    let bounds_y = (GRID_HEIGHT as f32 / 2.0) * GRID_SCALE;
+/// Moves a source along its path by `dt` simulated seconds.
+fn advance_moving_source(transform: &mut Transform, moving: &mut MovingSource, dt: f32) {
+    let bounds_x = TANK_WIDTH / 2.0;
+    let bounds_y = TANK_HEIGHT / 2.0;
 
-    for (mut transform, moving) in sources.iter_mut() {
-        transform.translation.x += moving.velocity.x * dt;
-        transform.translation.y += moving.velocity.y * dt;
+    match moving.path {
+        MovementPath::Linear => {
+            transform.translation.x += moving.velocity.x * dt;
+            transform.translation.y += moving.velocity.y * dt;
 
-        if transform.translation.x.abs() > bounds_x {
-            transform.translation.x = -transform.translation.x.signum() * (bounds_x - 10.0);
+            if transform.translation.x.abs() > bounds_x {
+                transform.translation.x = -transform.translation.x.signum() * (bounds_x - 10.0);
+            }
+            if transform.translation.y.abs() > bounds_y {
+                transform.translation.y = -transform.translation.y.signum() * (bounds_y - 10.0);
+            }
+        }
+        MovementPath::Circular => {
+            moving.angle = (moving.angle + moving.angular_speed * dt).rem_euclid(2.0 * PI);
+            let position = moving.center + moving.radius * Vec2::from_angle(moving.angle);
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
         }
-        if transform.translation.y.abs() > bounds_y {
-            transform.translation.y = -transform.translation.y.signum() * (bounds_y - 10.0);
+        MovementPath::Custom => {
+            moving.progress += moving.velocity.length() * dt;
+            if let Some(position) = moving.waypoint_position() {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
         }
     }
 }
 
+/// The enabled source closest to `point`, with its position.
+pub fn nearest_enabled_source<'a>(
+    sources: impl IntoIterator<Item = (&'a Transform, &'a WaveSource)>,
+    point: Vec2,
+) -> Option<(Vec2, &'a WaveSource)> {
+    sources
+        .into_iter()
+        .filter(|(_, source)| source.enabled)
+        .map(|(transform, source)| (transform.translation.truncate(), source))
+        .min_by(|a, b| a.0.distance(point).total_cmp(&b.0.distance(point)))
+}
+
+/// Writes obstacles into the wave field's obstacle map.
+///
+/// A dispersive block takes its index at the frequency of the nearest
+/// enabled source, the one whose waves it mostly sees. While sources of
+/// several frequencies are on, [`update_dispersive_fields`] gives each its
+/// own band with the block at that source's index instead.
 pub fn rasterize_obstacles(
     mut wave_field: ResMut<WaveField>,
     obstacles: Query<(&Transform, &Obstacle)>,
+    masks: Query<(&Transform, &ImageMaskObstacle)>,
+    sources: Query<(&Transform, &WaveSource)>,
+) {
+    stamp_scene(&mut wave_field, obstacles.iter(), &masks, &sources);
+}
+
+/// Resets the field's media, then stamps every image mask and `obstacles`
+/// into it, as [`rasterize_obstacles`] does for the whole scene.
+pub(super) fn stamp_scene<'a>(
+    wave_field: &mut WaveField,
+    obstacles: impl IntoIterator<Item = (&'a Transform, &'a Obstacle)>,
+    masks: &Query<(&Transform, &ImageMaskObstacle)>,
+    sources: &Query<(&Transform, &WaveSource)>,
 ) {
     wave_field.clear_obstacles();
+    for (transform, mask) in masks.iter() {
+        stamp_image_mask(wave_field, transform.translation.truncate(), mask);
+    }
 
-    let half_width = wave_field.width as f32 / 2.0;
-    let half_height = wave_field.height as f32 / 2.0;
+    for (transform, obstacle) in obstacles {
+        let position = transform.translation.truncate();
+        let frequency = nearest_enabled_source(sources, position).map_or(0.0, |(_, source)| source.frequency);
+        stamp_obstacle(wave_field, position, obstacle, obstacle.refractive_index_at(frequency));
+    }
+}
 
-    for (transform, obstacle) in obstacles.iter() {
-        let center_x = (transform.translation.x / GRID_SCALE + half_width) as i32;
-        let center_y = (transform.translation.y / GRID_SCALE + half_height) as i32;
-        let half_w = (obstacle.width / GRID_SCALE / 2.0) as i32;
-        let half_h = (obstacle.height / GRID_SCALE / 2.0) as i32;
+/// Writes one obstacle centered at `position` into the field's obstacle
+/// and transmission maps, with refracting media at `refractive_index`.
+fn stamp_obstacle(wave_field: &mut WaveField, position: Vec2, obstacle: &Obstacle, refractive_index: f32) {
+    let width = wave_field.width as i32;
+    let height = wave_field.height as i32;
+    let cell_size = wave_field.cell_size;
 
-        let width = wave_field.width;
-        let height = wave_field.height;
+    let center = wave_field.world_to_grid(position);
+    let (center_x, center_y) = (center.x as i32, center.y as i32);
+    let half_w = (obstacle.width / cell_size / 2.0) as i32;
+    let half_h = (obstacle.height / cell_size / 2.0) as i32;
 
-        match obstacle.obstacle_type {
-            ObstacleType::Reflector => {
-                for dy in -half_h..=half_h {
-                    for dx in -half_w..=half_w {
-                        let x = (center_x + dx) as usize;
-                        let y = (center_y + dy) as usize;
-                        if x < width && y < height {
-                            let idx = y * width + x;
-                            wave_field.obstacle_map[idx] = 0.0;
-                        }
-                    }
-                }
-            }
-            ObstacleType::SingleSlit => {
-                let slit_half = (obstacle.slit_width / GRID_SCALE / 2.0) as i32;
-                for dy in -half_h..=half_h {
-                    for dx in -half_w..=half_w {
-                        if dx.abs() <= slit_half { continue; }
-                        let x = (center_x + dx) as usize;
-                        let y = (center_y + dy) as usize;
-                        if x < width && y < height {
-                            let idx = y * width + x;
-                            wave_field.obstacle_map[idx] = 0.0;
-                        }
-                    }
-                }
-            }
-            ObstacleType::DoubleSlit => {
-                let slit_half = (obstacle.slit_width / GRID_SCALE / 2.0) as i32;
-                let sep_half = (obstacle.slit_separation / GRID_SCALE / 2.0) as i32;
-                for dy in -half_h..=half_h {
-                    for dx in -half_w..=half_w {
-                        let in_slit1 = (dx - sep_half).abs() <= slit_half;
-                        let in_slit2 = (dx + sep_half).abs() <= slit_half;
-                        if in_slit1 || in_slit2 { continue; }
-                        let x = (center_x + dx) as usize;
-                        let y = (center_y + dy) as usize;
-                        if x < width && y < height {
-                            let idx = y * width + x;
-                            wave_field.obstacle_map[idx] = 0.0;
-                        }
-                    }
-                }
+    // Visit the rotated footprint's bounding square and map each cell
+    // back into the obstacle's local frame, so rotation leaves no holes.
+    let reach = ((half_w * half_w + half_h * half_h) as f32).sqrt().ceil() as i32;
+    let to_local = Vec2::from_angle(-obstacle.rotation);
+
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            let (x, y) = (center_x + dx, center_y + dy);
+            if x < 0 || y < 0 || x >= width || y >= height {
+                continue;
             }
-            ObstacleType::RefractionBlock => {
-                let speed_factor = 1.0 / obstacle.refractive_index;
-                for dy in -half_h..=half_h {
-                    for dx in -half_w..=half_w {
-                        let x = (center_x + dx) as usize;
-                        let y = (center_y + dy) as usize;
-                        if x < width && y < height {
-                            let idx = y * width + x;
-                            wave_field.obstacle_map[idx] = speed_factor;
-                        }
-                    }
+            let local = to_local.rotate(Vec2::new(dx as f32, dy as f32)).round();
+            let (local_x, local_y) = (local.x as i32, local.y as i32);
+            if let Some(value) = obstacle_cell_value(obstacle, refractive_index, local_x, local_y, half_w, half_h, cell_size) {
+                let idx = y as usize * wave_field.width + x as usize;
+                if obstacle.obstacle_type == ObstacleType::Reflector && obstacle.reflectivity < 1.0 {
+                    // A partial reflector damps the field rather than pinning it to zero,
+                    // spread so the whole thickness returns about `reflectivity` of the energy.
+                    let thickness = (2 * half_h + 1) as f32;
+                    wave_field.transmission[idx] = (1.0 - obstacle.reflectivity.max(0.0)).powf(0.5 / thickness);
+                } else {
+                    wave_field.obstacle_map[idx] = value;
                 }
             }
         }
     }
 }
 
-pub fn apply_wave_sources(
-    mut wave_field: ResMut<WaveField>,
-    sources: Query<(&Transform, &WaveSource)>,
-    config: Res<RippleTankConfig>,
+/// Keeps [`DispersiveFields`] matching the scene: a band per enabled source
+/// while a dispersive block meets sources of more than one frequency, none
+/// otherwise. Runs after [`rasterize_obstacles`].
+///
+/// Each band sees the tank's media with the dispersive blocks stamped again
+/// at its source's frequency, so each frequency refracts by its own index.
+/// Waves already in the tank, and those of bands whose source went away,
+/// carry on in the first band.
+pub fn update_dispersive_fields(
+    wave_field: Res<WaveField>,
+    mut dispersive: ResMut<DispersiveFields>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    sources: Query<(Entity, &WaveSource)>,
 ) {
-    if config.paused { return; }
+    let blocks: Vec<_> = obstacles.iter().filter(|(_, obstacle)| obstacle.is_dispersive()).collect();
+    let enabled: Vec<(Entity, f32)> =
+        sources.iter().filter(|(_, source)| source.enabled).map(|(entity, source)| (entity, source.frequency)).collect();
+    if blocks.is_empty() || !enabled.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+        // The tank's field already holds the sum.
+        dispersive.bands.clear();
+        return;
+    }
+    if !dispersive.sums_to(&wave_field) {
+        dispersive.bands.clear();
+    }
 
-    let half_width = wave_field.width as f32 / 2.0;
-    let half_height = wave_field.height as f32 / 2.0;
-    let t = config.accumulated_time;
+    let mut carried = Vec::new();
+    if dispersive.bands.is_empty() {
+        carried.push((wave_field.current.clone(), wave_field.previous.clone()));
+    }
+    dispersive.bands.retain_mut(|band| {
+        let kept = enabled.iter().any(|&(source, _)| source == band.source);
+        if !kept {
+            carried.push((std::mem::take(&mut band.field.current), std::mem::take(&mut band.field.previous)));
+        }
+        kept
+    });
+    for &(source, _) in &enabled {
+        if !dispersive.bands.iter().any(|band| band.source == source) {
+            dispersive.bands.push(FrequencyBand { source, field: wave_field.empty_like() });
+        }
+    }
+    let first = &mut dispersive.bands[0].field;
+    for (current, previous) in carried {
+        first.current.iter_mut().zip(current).for_each(|(sum, value)| *sum += value);
+        first.previous.iter_mut().zip(previous).for_each(|(sum, value)| *sum += value);
+    }
 
-    for (transform, source) in sources.iter() {
-        if !source.enabled { continue; }
+    for band in dispersive.bands.iter_mut() {
+        let frequency = enabled.iter().find(|&&(source, _)| source == band.source).map_or(0.0, |&(_, frequency)| frequency);
+        band.field.obstacle_map.clone_from(&wave_field.obstacle_map);
+        band.field.transmission.clone_from(&wave_field.transmission);
+        for (transform, obstacle) in &blocks {
+            let refractive_index = obstacle.refractive_index_at(frequency);
+            stamp_obstacle(&mut band.field, transform.translation.truncate(), obstacle, refractive_index);
+        }
+    }
+}
 
-        let grid_x = (transform.translation.x / GRID_SCALE + half_width) as usize;
-        let grid_y = (transform.translation.y / GRID_SCALE + half_height) as usize;
+/// Obstacle-map value for a cell at local offset `(dx, dy)` from an
+/// unrotated obstacle's center, or `None` if the cell is open.
+fn obstacle_cell_value(
+    obstacle: &Obstacle,
+    refractive_index: f32,
+    dx: i32,
+    dy: i32,
+    half_w: i32,
+    half_h: i32,
+    cell_size: f32,
+) -> Option<f32> {
+    if dx.abs() > half_w || dy.abs() > half_h {
+        return None;
+    }
 
-        let value = match source.waveform {
-            Waveform::Sine => source.amplitude * (2.0 * PI * source.frequency * t + source.phase).sin(),
-            Waveform::Square => {
-                let phase = (2.0 * PI * source.frequency * t + source.phase).sin();
-                source.amplitude * phase.signum()
-            }
-            Waveform::Pulse => {
-                let phase = (source.frequency * t + source.phase / (2.0 * PI)) % 1.0;
-                if phase < 0.1 { source.amplitude } else { 0.0 }
-            }
-        };
+    match obstacle.obstacle_type {
+        ObstacleType::Reflector => Some(0.0),
+        ObstacleType::SingleSlit => {
+            let slit_half = (obstacle.slit_width / cell_size / 2.0) as i32;
+            (dx.abs() > slit_half).then_some(0.0)
+        }
+        ObstacleType::DoubleSlit => {
+            let slit_half = (obstacle.slit_width / cell_size / 2.0) as i32;
+            let sep_half = (obstacle.slit_separation / cell_size / 2.0) as i32;
+            let in_slit1 = (dx - sep_half).abs() <= slit_half;
+            let in_slit2 = (dx + sep_half).abs() <= slit_half;
+            (!in_slit1 && !in_slit2).then_some(0.0)
+        }
+        ObstacleType::RefractionBlock => Some(1.0 / refractive_index),
+        ObstacleType::GradedIndex => {
+            let offset = dx as f32 / half_w.max(1) as f32;
+            Some(1.0 / obstacle.graded_index(refractive_index, offset))
+        }
+    }
+}
+
+/// Stretches and turns ruler sprites to span their endpoints.
+pub fn sync_ruler_sprites(mut rulers: Query<(&mut Transform, &mut Sprite, &Ruler), Changed<Ruler>>) {
+    for (mut transform, mut sprite, ruler) in rulers.iter_mut() {
+        let span = ruler.end - ruler.start;
+        transform.rotation = Quat::from_rotation_z(span.to_angle());
+        sprite.custom_size = Some(Vec2::new(span.length(), 5.0));
+    }
+}
+
+/// Keeps obstacle sprites matching the size and rotation that get rasterized.
+pub fn sync_obstacle_sprites(
+    mut obstacles: Query<(&mut Transform, &mut Sprite, &Obstacle), Changed<Obstacle>>,
+) {
+    for (mut transform, mut sprite, obstacle) in obstacles.iter_mut() {
+        transform.rotation = Quat::from_rotation_z(obstacle.rotation);
+        sprite.custom_size = Some(Vec2::new(obstacle.width, obstacle.height));
+    }
+}
+
+/// Drives the field with every enabled source's current output.
+///
+/// `wave_speed` in world units per second sets the wavelength that phased
+/// arrays steer with.
+pub fn apply_wave_sources<'a>(
+    wave_field: &mut WaveField,
+    sources: impl IntoIterator<Item = (&'a Transform, &'a WaveSource)>,
+    wave_speed: f32,
+) {
+    // Source footprints are sized in default-resolution cells.
+    let cells_per_default_cell = GRID_SCALE / wave_field.cell_size;
+
+    let sources: Vec<_> = sources.into_iter().collect();
+    let any_solo = sources.iter().any(|(_, source)| source.solo);
+    for (transform, source) in sources {
+        if !source.is_audible(any_solo) { continue; }
+
+        let grid = wave_field.world_to_grid(transform.translation.truncate());
+        let (grid_x, grid_y) = (grid.x as usize, grid.y as usize);
 
+        let value = source.sample(0.0);
         let width = wave_field.width;
         let height = wave_field.height;
 
@@ -307,23 +709,31 @@ pub fn apply_wave_sources(
                 }
             }
             WaveSourceType::Line => {
-                let half_len = 20usize;
+                let half_len = (20.0 * cells_per_default_cell) as usize;
                 for dx in 0..half_len * 2 {
                     let x = grid_x.saturating_sub(half_len) + dx;
                     if x < width && grid_y < height {
                         let idx = grid_y * width + x;
-                        wave_field.current[idx] = value;
+                        wave_field.current[idx] = value * source.apodization.weight(dx, half_len * 2);
                     }
                 }
             }
             WaveSourceType::PhasedArray { count } => {
-                let spacing = 8usize;
+                let spacing = ((PHASED_ARRAY_SPACING * cells_per_default_cell) as usize).max(1);
                 let total_w = (count as usize - 1) * spacing;
                 let start_x = grid_x.saturating_sub(total_w / 2);
+                let element_phase = steering_phase_step(
+                    spacing as f32 * wave_field.cell_size,
+                    source.frequency,
+                    wave_speed,
+                    source.steering_angle,
+                );
                 for i in 0..count as usize {
                     let x = start_x + i * spacing;
-                    let phase_offset = i as f32 * 0.2;
-                    let phased_value = source.amplitude * (2.0 * PI * source.frequency * t + source.phase + phase_offset).sin();
+                    let phase_offset = -(i as f32) * element_phase;
+                    let phased_value = source.amplitude
+                        * source.apodization.weight(i, count as usize)
+                        * (source.clock_phase + source.phase + phase_offset).sin();
                     if x < width && grid_y < height {
                         let idx = grid_y * width + x;
                         wave_field.current[idx] = phased_value;
@@ -334,66 +744,257 @@ pub fn apply_wave_sources(
     }
 }
 
+/// Phase lag between neighbouring array elements `spacing` apart that steers
+/// the main lobe `angle` radians off broadside: Δφ = (2π/λ)·d·sinθ.
+pub fn steering_phase_step(spacing: f32, frequency: f32, wave_speed: f32, angle: f32) -> f32 {
+    if wave_speed <= 0.0 {
+        return 0.0;
+    }
+    let wavelength = wave_speed / frequency.max(f32::EPSILON);
+    2.0 * PI / wavelength * spacing * angle.sin()
+}
+
+/// Appends the field value under each probe to its history, and marks the
+/// timed pulse as arrived at `time` where it first reaches the threshold.
+fn record_probes(
+    probes: &mut Query<(&Transform, &mut Probe), Without<WaveSource>>,
+    wave_field: &WaveField,
+    timer: &TimeOfFlight,
+    time: f32,
+) {
+    for (transform, mut probe) in probes.iter_mut() {
+        let value = wave_field.sample(transform.translation.truncate());
+        probe.history.push(value);
+        if probe.history.len() > MAX_PROBE_HISTORY {
+            probe.history.remove(0);
+        }
+        if timer.emitted.is_some() && probe.arrival.is_none() && value.abs() >= timer.threshold {
+            probe.arrival = Some(time);
+        }
+    }
+}
+
+/// Advances the tank by the fixed FDTD steps due this frame.
+///
+/// Each substep injects the sources, propagates the field, and advances
+/// the source oscillators and moving sources; each whole step then samples
+/// the probes and advances the simulation clock. A pulse starting restarts
+/// the [`TimeOfFlight`] stopwatch. Nothing advances while paused, so
+/// pausing and resuming never shifts one source relative to another.
+///
+/// With [`AdaptiveQuality`] the steps per frame shrink on slow devices, so
+/// the tank runs slower than real time instead of dragging the frame rate.
+#[allow(clippy::too_many_arguments)]
 pub fn update_wave_field(
     mut wave_field: ResMut<WaveField>,
     mut config: ResMut<RippleTankConfig>,
+    mut clock: ResMut<FixedTimestep>,
     time: Res<Time>,
+    quality: Option<Res<AdaptiveQuality>>,
+    mut timer: ResMut<TimeOfFlight>,
+    mut sources: Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>,
+    mut probes: Query<(&Transform, &mut Probe), Without<WaveSource>>,
+    mut dispersive: Option<ResMut<DispersiveFields>>,
+    mut comparison: Option<ResMut<SlitComparison>>,
 ) {
+    // New sources join the shared clock even if the tank is paused.
+    for (_, mut source, _) in sources.iter_mut() {
+        if source.is_added() {
+            source.reset_clock(config.accumulated_time);
+        }
+    }
+
     if config.paused { return; }
 
-    let dt = time.delta_seconds() * config.time_scale;
-    config.accumulated_time += dt;
+    clock.max_steps = quality.map_or(MAX_STEPS_PER_FRAME, |quality| quality.scale(MAX_STEPS_PER_FRAME as usize) as u32);
+    let steps = clock.advance(time.delta_seconds() * config.time_scale);
+    let dt = clock.dt;
 
-    let c = config.wave_speed;
-    let damping = config.damping;
-    let width = wave_field.width;
-    let height = wave_field.height;
-    let c2 = (c * 0.4).powi(2);
+    let plan = StepPlan::new(&config, &wave_field, fastest_moving_source(&sources), dt);
+    let wave_speed = wave_speed_world(&config, dt);
+
+    for _ in 0..steps {
+        apply_sweeps(&mut sources, config.accumulated_time);
+        let any_solo = sources.iter().any(|(_, source, _)| source.solo);
+        let pulse_high = sources.iter().any(|(_, source, _)| source.is_audible(any_solo) && source.is_pulse_high());
+        if timer.observe(pulse_high, config.accumulated_time) {
+            for (_, mut probe) in probes.iter_mut() {
+                probe.arrival = None;
+            }
+        }
+        for _ in 0..plan.substeps {
+            match dispersive.as_deref_mut().filter(|dispersive| !dispersive.bands.is_empty()) {
+                Some(dispersive) => {
+                    step_bands(&mut wave_field, dispersive, &sources, &plan, wave_speed, config.boundary.as_ref());
+                }
+                None => {
+                    let sources = sources.iter().map(|(transform, source, _)| (transform, source));
+                    apply_wave_sources(&mut wave_field, sources, wave_speed);
+                    wave_field.step(plan.c2, plan.damping, plan.pml_width, config.boundary.as_ref());
+                }
+            }
+            if let Some(field) = comparison.as_deref_mut().and_then(|comparison| comparison.field.as_mut()) {
+                apply_wave_sources(field, sources.iter().map(|(transform, source, _)| (transform, source)), wave_speed);
+                field.step(plan.c2, plan.damping, plan.pml_width, config.boundary.as_ref());
+            }
+            advance_sources(&mut sources, plan.substep_dt);
+        }
+        wave_field.accumulate_intensity(INTENSITY_AVERAGING);
+        if let Some(field) = comparison.as_deref_mut().and_then(|comparison| comparison.field.as_mut()) {
+            field.accumulate_intensity(INTENSITY_AVERAGING);
+        }
+        config.accumulated_time += dt;
+        record_probes(&mut probes, &wave_field, &timer, config.accumulated_time);
+    }
+}
 
-    let mut next = vec![0.0f32; width * height];
+/// Undoes one FDTD step when Step Back is pressed.
+///
+/// Runs each substep of [`update_wave_field`] in reverse: the stencil
+/// recovers the earlier field, and the sources are rewound and re-applied
+/// to restore the cells they had overwritten. Only allowed while paused
+/// and when [`RippleTankConfig::is_time_reversible`]. Probe samples from
+/// the undone step are dropped; the time-averaged intensity is left as is.
+pub fn step_wave_field_backward(
+    mut ui_state: ResMut<UIState>,
+    mut wave_field: ResMut<WaveField>,
+    mut config: ResMut<RippleTankConfig>,
+    clock: Res<FixedTimestep>,
+    mut sources: Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>,
+    mut probes: Query<&mut Probe>,
+) {
+    if !std::mem::take(&mut ui_state.step_back_requested) {
+        return;
+    }
+    if !config.paused || !config.is_time_reversible() || config.accumulated_time < clock.dt / 2.0 {
+        return;
+    }
 
-    for y in 1..height - 1 {
-        for x in 1..width - 1 {
-            let idx = y * width + x;
-            let obstacle = wave_field.obstacle_map[idx];
+    let plan = StepPlan::new(&config, &wave_field, fastest_moving_source(&sources), clock.dt);
+    let wave_speed = wave_speed_world(&config, clock.dt);
+    // Sweeps rewind to the frequency the undone step ran at.
+    apply_sweeps(&mut sources, config.accumulated_time - clock.dt);
+    for _ in 0..plan.substeps {
+        advance_sources(&mut sources, -plan.substep_dt);
+        wave_field.step_backward(plan.c2, config.boundary.as_ref());
 
-            if obstacle == 0.0 {
-                next[idx] = 0.0;
-                continue;
-            }
+        // Redo the end of the earlier substep on the recovered level: the
+        // sources as they were one substep before.
+        wave_field.swap_time_levels();
+        advance_sources(&mut sources, -plan.substep_dt);
+        apply_wave_sources(&mut wave_field, sources.iter().map(|(transform, source, _)| (transform, source)), wave_speed);
+        advance_sources(&mut sources, plan.substep_dt);
+        wave_field.swap_time_levels();
+    }
 
-            let laplacian = wave_field.current[idx - 1]
-                + wave_field.current[idx + 1]
-                + wave_field.current[idx - width]
-                + wave_field.current[idx + width]
-                - 4.0 * wave_field.current[idx];
+    for mut probe in probes.iter_mut() {
+        probe.history.pop();
+    }
+    config.accumulated_time = (config.accumulated_time - clock.dt).max(0.0);
+}
+
+/// How one FDTD step is split into substeps.
+pub(super) struct StepPlan {
+    pub(super) substeps: usize,
+    pub(super) substep_dt: f32,
+    pub(super) c2: f32,
+    pub(super) damping: f32,
+    pub(super) pml_width: usize,
+}
 
-            let effective_c2 = c2 * obstacle * obstacle;
-            next[idx] = damping * (2.0 * wave_field.current[idx] - wave_field.previous[idx] + effective_c2 * laplacian);
-            next[idx] = next[idx].clamp(-5.0, 5.0);
+impl StepPlan {
+    /// `fastest` is the speed of the fastest enabled moving source in world
+    /// units per second.
+    pub(super) fn new(config: &RippleTankConfig, wave_field: &WaveField, fastest: f32, dt: f32) -> Self {
+        // Cells travelled per step grow as the cells shrink; split each step
+        // into substeps so each stays under the CFL limit. Fast sources also
+        // need enough substeps to move at most one cell between emissions, or
+        // they would leave gaps along their path instead of a continuous wake.
+        let cells_per_default_cell = GRID_SCALE / wave_field.cell_size;
+        let courant = config.wave_speed * 0.4 * cells_per_default_cell;
+        let source_travel = fastest * dt / wave_field.cell_size;
+        let substeps = (courant / MAX_COURANT).max(source_travel).ceil().max(1.0);
+        Self {
+            substeps: substeps as usize,
+            substep_dt: dt / substeps,
+            c2: (courant / substeps).powi(2),
+            damping: config.damping.powf(1.0 / substeps),
+            pml_width: (config.absorbing_width() as f32 * cells_per_default_cell).round() as usize,
         }
     }
+}
 
-    // Absorbing boundaries
-    for x in 0..width {
-        next[x] *= 0.5;
-        next[(height - 1) * width + x] *= 0.5;
+/// Drives and steps each band of [`DispersiveFields`] with its own source
+/// only, then sets the tank's field to their sum.
+fn step_bands(
+    wave_field: &mut WaveField,
+    dispersive: &mut DispersiveFields,
+    sources: &Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>,
+    plan: &StepPlan,
+    wave_speed: f32,
+    boundary: &dyn BoundaryCondition,
+) {
+    let any_solo = sources.iter().any(|(_, source, _)| source.solo);
+    for band in dispersive.bands.iter_mut() {
+        let source = sources.get(band.source).ok().filter(|(_, source, _)| source.is_audible(any_solo));
+        apply_wave_sources(&mut band.field, source.map(|(transform, source, _)| (transform, source)), wave_speed);
+        band.field.step(plan.c2, plan.damping, plan.pml_width, boundary);
     }
-    for y in 0..height {
-        next[y * width] *= 0.5;
-        next[y * width + width - 1] *= 0.5;
+    wave_field.superpose(dispersive.bands.iter().map(|band| &band.field));
+}
+
+/// Speed of the fastest enabled moving source, or zero if none move.
+fn fastest_moving_source(sources: &Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>) -> f32 {
+    sources
+        .iter()
+        .filter(|(_, source, _)| source.enabled)
+        .filter_map(|(_, _, moving)| moving.map(|moving| moving.current_velocity().length()))
+        .fold(0.0, f32::max)
+}
+
+/// Sets each sweeping source's frequency for the step starting at `time`,
+/// starting any sweep that hasn't begun yet.
+fn apply_sweeps(sources: &mut Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>, time: f32) {
+    for (_, mut source, _) in sources.iter_mut() {
+        let Some(mut sweep) = source.sweep else { continue };
+        sweep.started.get_or_insert(time);
+        source.frequency = sweep.frequency_at(time);
+        source.sweep = Some(sweep);
     }
+}
 
-    let old_current = std::mem::replace(&mut wave_field.current, next);
-    wave_field.previous = old_current;
+/// Advances every source's oscillator, and moving sources along their
+/// paths, by `dt` (negative to rewind).
+fn advance_sources(sources: &mut Query<(&mut Transform, &mut WaveSource, Option<&mut MovingSource>)>, dt: f32) {
+    // Disabled sources keep time too, so re-enabling one stays coherent.
+    for (mut transform, mut source, moving) in sources.iter_mut() {
+        source.clock_phase = (source.clock_phase + 2.0 * PI * source.frequency * dt).rem_euclid(2.0 * PI);
+        if let Some(mut moving) = moving {
+            advance_moving_source(&mut transform, &mut moving, dt);
+        }
+    }
 }
 
-pub fn update_probes(mut probes: Query<(&Transform, &mut Probe)>, wave_field: Res<WaveField>) {
-    for (transform, mut probe) in probes.iter_mut() {
-        let value = wave_field.sample(transform.translation.truncate());
-        probe.history.push(value);
-        if probe.history.len() > MAX_PROBE_HISTORY {
-            probe.history.remove(0);
+/// Reallocates the wave field and its texture when the grid size changes.
+pub fn apply_grid_size(
+    config: Res<RippleTankConfig>,
+    mut wave_field: ResMut<WaveField>,
+    mut images: ResMut<Assets<Image>>,
+    mut visuals: Query<(&mut Transform, &WaveFieldVisual)>,
+) {
+    if config.grid_size == wave_field.height {
+        return;
+    }
+
+    *wave_field = WaveField::with_grid_size(config.grid_size);
+    for (mut transform, visual) in visuals.iter_mut() {
+        transform.scale = Vec3::splat(wave_field.cell_size);
+        if let Some(image) = images.get_mut(&visual.texture) {
+            image.resize(bevy::render::render_resource::Extent3d {
+                width: wave_field.width as u32,
+                height: wave_field.height as u32,
+                depth_or_array_layers: 1,
+            });
         }
     }
 }
@@ -407,50 +1008,110 @@ pub fn update_wave_visualization(
     let Ok(visual) = visual_query.get_single() else { return };
     let Some(image) = images.get_mut(&visual.texture) else { return };
 
-    for y in 0..wave_field.height {
-        for x in 0..wave_field.width {
+    if config.split_view {
+        let split_column = split_column(wave_field.width, config.split_position);
+        write_split_wave_field_rgba(&wave_field, config.color_scheme, config.color_scheme_b, split_column, &mut image.data);
+    } else {
+        write_wave_field_rgba(&wave_field, config.color_scheme, &mut image.data);
+    }
+    if config.show_boundary {
+        tint_absorbing_layer(&wave_field, config.absorbing_width(), &mut image.data);
+    }
+}
+
+/// Blends the pixels of a `pml_width`-cell absorbing layer toward red, with
+/// an opacity following the layer's damping coefficient.
+pub fn tint_absorbing_layer(wave_field: &WaveField, pml_width: usize, data: &mut [u8]) {
+    if pml_width == 0 {
+        return;
+    }
+    let row_bytes = wave_field.width * 4;
+    for (row, pixels) in data.chunks_exact_mut(row_bytes).take(wave_field.height).enumerate() {
+        let y = wave_field.height - 1 - row;
+        for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let damping = wave_field.pml_damping(x, y, pml_width);
+            if damping <= 0.0 {
+                continue;
+            }
+            let alpha = BOUNDARY_TINT_OPACITY * damping / PML_MAX_DAMPING;
+            for (channel, red) in pixel[..3].iter_mut().zip([255.0, 40.0, 40.0]) {
+                *channel = (*channel as f32 * (1.0 - alpha) + red * alpha).round() as u8;
+            }
+        }
+    }
+}
+
+/// First grid column on the right-hand side of a split at `position`
+/// (a fraction of the width).
+pub fn split_column(width: usize, position: f32) -> usize {
+    ((position.clamp(0.0, 1.0) * width as f32).round() as usize).min(width)
+}
+
+/// Writes the wave field as RGBA8 pixels, one per grid cell.
+///
+/// Grid row 0 is the bottom of the world, while image rows run top-down,
+/// so rows are written flipped.
+pub fn write_wave_field_rgba(wave_field: &WaveField, scheme: ColorScheme, data: &mut [u8]) {
+    write_split_wave_field_rgba(wave_field, scheme, scheme, wave_field.width, data);
+}
+
+/// Like [`write_wave_field_rgba`], but columns from `split_column` on are
+/// colored with the `right` scheme instead of `left`.
+pub fn write_split_wave_field_rgba(
+    wave_field: &WaveField,
+    left: ColorScheme,
+    right: ColorScheme,
+    split_column: usize,
+    data: &mut [u8],
+) {
+    let values_for =
+        |scheme: ColorScheme| if scheme == ColorScheme::Intensity { &wave_field.intensity } else { &wave_field.current };
+    let (left_values, right_values) = (values_for(left), values_for(right));
+    let row_bytes = wave_field.width * 4;
+    for (row, pixels) in data.chunks_exact_mut(row_bytes).take(wave_field.height).enumerate() {
+        let y = wave_field.height - 1 - row;
+        for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
             let idx = wave_field.idx(x, y);
-            let value = wave_field.current[idx];
-            let obstacle = wave_field.obstacle_map[idx];
-
-            let (r, g, b) = if obstacle == 0.0 {
-                (60, 60, 70)
-            } else {
-                match config.color_scheme {
-                    ColorScheme::DeepOcean => {
-                        let v = ((value + 1.0) * 0.5).clamp(0.0, 1.0);
-                        ((20.0 + v * 40.0) as u8, (40.0 + v * 80.0) as u8, (80.0 + v * 175.0) as u8)
-                    }
-                    ColorScheme::Scientific => {
-                        let v = ((value + 1.0) * 0.5).clamp(0.0, 1.0);
-                        if v < 0.5 {
-                            let t = v * 2.0;
-                            ((255.0 * (1.0 - t)) as u8, (255.0 * t) as u8, 0)
-                        } else {
-                            let t = (v - 0.5) * 2.0;
-                            (0, (255.0 * (1.0 - t)) as u8, (255.0 * t) as u8)
-                        }
-                    }
-                    ColorScheme::PhaseColor => {
-                        let hue = ((value.atan2(0.5) + PI) / (2.0 * PI) * 360.0) as u16;
-                        hsl_to_rgb(hue, 80, 50)
-                    }
-                    ColorScheme::Grayscale => {
-                        let v = ((value + 1.0) * 0.5 * 255.0).clamp(0.0, 255.0) as u8;
-                        (v, v, v)
-                    }
-                }
-            };
+            let (scheme, values) = if x < split_column { (left, left_values) } else { (right, right_values) };
+            let (r, g, b) = wave_pixel_color(values[idx], wave_field.obstacle_map[idx], scheme);
+            pixel.copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+}
 
-            let pixel_idx = idx * 4;
-            image.data[pixel_idx] = r;
-            image.data[pixel_idx + 1] = g;
-            image.data[pixel_idx + 2] = b;
-            image.data[pixel_idx + 3] = 255;
+/// Maps a wave amplitude (or intensity, for `ColorScheme::Intensity`) to a
+/// display color under the given scheme.
+pub fn wave_pixel_color(value: f32, obstacle: f32, scheme: ColorScheme) -> (u8, u8, u8) {
+    if obstacle == 0.0 {
+        return (60, 60, 70);
+    }
+
+    match scheme {
+        ColorScheme::DeepOcean => {
+            let v = ((value + 1.0) * 0.5).clamp(0.0, 1.0);
+            ((20.0 + v * 40.0) as u8, (40.0 + v * 80.0) as u8, (80.0 + v * 175.0) as u8)
+        }
+        ColorScheme::Scientific => rgb8(turbo(((value + 1.0) * 0.5).clamp(0.0, 1.0))),
+        ColorScheme::PhaseColor => {
+            let hue = ((value.atan2(0.5) + PI) / (2.0 * PI) * 360.0) as u16;
+            hsl_to_rgb(hue, 80, 50)
+        }
+        ColorScheme::Grayscale => {
+            let v = ((value + 1.0) * 0.5 * 255.0).clamp(0.0, 255.0) as u8;
+            (v, v, v)
+        }
+        ColorScheme::Intensity => {
+            // RMS amplitude reads more evenly than raw intensity.
+            rgb8(inferno((value.max(0.0).sqrt() * 2.0).min(1.0)))
         }
     }
 }
 
+fn rgb8(color: Color) -> (u8, u8, u8) {
+    let [r, g, b, _] = color.to_srgba().to_u8_array();
+    (r, g, b)
+}
+
 fn hsl_to_rgb(h: u16, s: u8, l: u8) -> (u8, u8, u8) {
     let h = h as f32 / 360.0;
     let s = s as f32 / 100.0;
@@ -472,21 +1133,30 @@ fn hsl_to_rgb(h: u16, s: u8, l: u8) -> (u8, u8, u8) {
     (((r + m) * 255.0) as u8, ((g + m) * 255.0) as u8, ((b + m) * 255.0) as u8)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_stats(
     mut stats: ResMut<SimulationStats>,
     time: Res<Time>,
     config: Res<RippleTankConfig>,
     wave_field: Res<WaveField>,
     probes: Query<&Probe>,
+    probe_transforms: Query<&Transform, With<Probe>>,
+    moving_sources: Query<(&Transform, &WaveSource, &MovingSource)>,
+    rulers: Query<(&Transform, &Ruler), Without<Screen>>,
+    mut recorder: ResMut<DataRecorder>,
 ) {
     stats.fps = 1.0 / time.delta_seconds();
     stats.simulation_time = config.accumulated_time;
 
+    stats.sample_interval = FDTD_STEP;
+
     let energy: f32 = wave_field.current.iter()
         .zip(wave_field.previous.iter())
         .map(|(c, p)| (c - p).powi(2) + c.powi(2))
         .sum();
     stats.wave_energy = energy;
+    stats.courant = courant_number(&config, &wave_field);
+    stats.solver_unstable = wave_field.clamped_cells >= UNSTABLE_CLAMPED_CELLS;
 
     let probe_vec: Vec<&Probe> = probes.iter().collect();
     if probe_vec.len() >= 2 {
@@ -503,15 +1173,195 @@ pub fn update_stats(
     } else {
         stats.probe_phase_diff = None;
     }
+
+    let fastest = moving_sources
+        .iter()
+        .filter(|(_, source, _)| source.enabled)
+        .map(|(_, _, moving)| moving.current_velocity().length())
+        .reduce(f32::max);
+    stats.source_speed = fastest;
+    stats.mach_number = fastest.map(|speed| speed / wave_speed_world(&config, stats.sample_interval));
+
+    stats.observed_frequency = None;
+    stats.shock_cone = false;
+    let probe_pos = probe_transforms.iter().next().map(|transform| transform.translation.truncate());
+    if let (Some(probe_pos), true) = (probe_pos, stats.sample_interval > 0.0) {
+        let wave_speed = wave_speed_world(&config, stats.sample_interval);
+        let nearest = moving_sources
+            .iter()
+            .filter(|(_, source, _)| source.enabled)
+            .map(|(transform, source, moving)| (transform.translation.truncate(), source, moving))
+            .min_by(|a, b| a.0.distance(probe_pos).total_cmp(&b.0.distance(probe_pos)));
+
+        if let Some((source_pos, source, moving)) = nearest {
+            match doppler_frequency(source.frequency, wave_speed, moving.current_velocity(), probe_pos - source_pos) {
+                Some(frequency) => stats.observed_frequency = Some(frequency),
+                None => stats.shock_cone = true,
+            }
+        }
+    }
+
+    // The clock going back (a reset or undo) starts a new recording.
+    if config.accumulated_time < recorder.time() {
+        recorder.clear();
+    }
+    recorder.set_time(config.accumulated_time);
+    recorder.record("wave energy", stats.wave_energy);
+    recorder.record("fps", stats.fps);
+    for probe in &probes {
+        if let Some(&value) = probe.history.last() {
+            recorder.record(&probe.label, value);
+        }
+    }
+    if let Some((transform, ruler)) = rulers.iter().next() {
+        let (start, end) = ruler.world_endpoints(transform.translation.truncate());
+        if let Some(wavelength) = wavelength_from_zero_crossings(&wave_field.zero_crossings(start, end)) {
+            recorder.record("wavelength", wavelength);
+        }
+    }
+}
+
+/// Adds a row to the [`Spectrogram`] every [`SPECTROGRAM_HOP`] of simulated
+/// time, from the selected probe or else the first one placed.
+pub fn update_spectrogram(
+    mut spectrogram: ResMut<Spectrogram>,
+    config: Res<RippleTankConfig>,
+    ui_state: Res<UIState>,
+    probes: Query<(Entity, &SceneObject, &Probe)>,
+) {
+    let selected = ui_state.selected_entity.filter(|&entity| probes.contains(entity));
+    let current = spectrogram.probe.filter(|&entity| probes.contains(entity));
+    let first = probes.iter().min_by_key(|(_, object, _)| object.id).map(|(entity, _, _)| entity);
+    let probe = selected.or(current).or(first);
+
+    // A new probe, or the clock going back (a reset), starts a new history.
+    if probe != spectrogram.probe || config.accumulated_time < spectrogram.last_row_time {
+        spectrogram.reset(probe);
+    }
+    let Some(probe) = probe else { return };
+    if config.accumulated_time - spectrogram.last_row_time < SPECTROGRAM_HOP {
+        return;
+    }
+
+    let Ok((_, _, probe)) = probes.get(probe) else { return };
+    if probe.history.len() >= MIN_SPECTRUM_SAMPLES {
+        spectrogram.push(magnitude_spectrum(&probe.history, SPECTRUM_SIZE), config.accumulated_time);
+    }
+}
+
+/// Samples the field along each poly probe every half cell.
+pub fn update_poly_probes(wave_field: Res<WaveField>, mut probes: Query<(&Transform, &mut PolyProbe)>) {
+    let spacing = wave_field.cell_size / 2.0;
+    for (transform, mut probe) in probes.iter_mut() {
+        let samples = probe.sample_points(transform.translation.truncate(), spacing);
+        probe.samples = samples.into_iter().map(|point| wave_field.sample(point)).collect();
+        probe.sample_spacing = spacing;
+    }
+}
+
+/// Samples each screen's intensity profile, finds its bright fringes, and
+/// predicts where d·sinθ = mλ puts them for the nearest double slit.
+pub fn update_screens(
+    wave_field: Res<WaveField>,
+    config: Res<RippleTankConfig>,
+    stats: Res<SimulationStats>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    sources: Query<(&Transform, &WaveSource)>,
+    mut screens: Query<(&Transform, &Ruler, &mut Screen)>,
+) {
+    for (transform, ruler, mut screen) in screens.iter_mut() {
+        let center = transform.translation.truncate();
+        let (start, end) = ruler.world_endpoints(center);
+        screen.profile = wave_field.intensity_profile(start, end);
+        screen.sample_spacing = wave_field.cell_size / 2.0;
+        screen.fringes = find_fringes(&screen.profile, screen.sample_spacing);
+
+        let slits = obstacles
+            .iter()
+            .filter(|(_, obstacle)| obstacle.obstacle_type == ObstacleType::DoubleSlit)
+            .map(|(transform, obstacle)| (transform.translation.truncate(), obstacle.slit_separation))
+            .min_by(|a, b| a.0.distance(center).total_cmp(&b.0.distance(center)));
+        screen.predicted = match slits {
+            Some((slits, separation)) if stats.sample_interval > 0.0 => nearest_enabled_source(&sources, slits)
+                .filter(|(_, source)| source.frequency > 0.0)
+                .map(|(_, source)| {
+                    let wavelength = wave_speed_world(&config, stats.sample_interval) / source.frequency;
+                    predicted_fringes(start, end, slits, separation, wavelength)
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+    }
+}
+
+/// Propagation speed in world units per simulated second.
+///
+/// The per-step Courant number is converted with the time a step
+/// represents, [`FDTD_STEP`].
+pub fn wave_speed_world(config: &RippleTankConfig, step_interval: f32) -> f32 {
+    config.wave_speed * 0.4 * GRID_SCALE / step_interval
+}
+
+/// Courant number of one substep in the fastest medium on the grid.
+///
+/// Substepping keeps open water under [`MAX_COURANT`], but a medium with
+/// refractive index below 1 speeds waves up past the limit.
+pub fn courant_number(config: &RippleTankConfig, wave_field: &WaveField) -> f32 {
+    let fastest_medium = wave_field.obstacle_map.iter().copied().fold(0.0, f32::max);
+    StepPlan::new(config, wave_field, 0.0, FDTD_STEP).c2.sqrt() * fastest_medium
+}
+
+/// Wavelength implied by zero-crossing positions along a line: crossings
+/// are half a wavelength apart. Needs at least two crossings.
+pub fn wavelength_from_zero_crossings(crossings: &[f32]) -> Option<f32> {
+    let (first, last) = (crossings.first()?, crossings.last()?);
+    (crossings.len() >= 2).then(|| 2.0 * (last - first) / (crossings.len() - 1) as f32)
+}
+
+/// Resonant frequency of the `(m, n)` mode of the reflecting tank.
+///
+/// With zero-slope walls the modes are `f = c/2 · √((m/W)² + (n/H)²)`.
+pub fn tank_mode_frequency(m: u32, n: u32, wave_speed: f32) -> f32 {
+    0.5 * wave_speed * ((m as f32 / TANK_WIDTH).powi(2) + (n as f32 / TANK_HEIGHT).powi(2)).sqrt()
+}
+
+/// Doppler-shifted frequency `f·c / (c − v·r̂)` heard at `to_observer` from
+/// a source moving with `velocity`.
+///
+/// Returns `None` when the source closes on the observer at or above the
+/// wave speed, where a shock cone forms instead.
+pub fn doppler_frequency(frequency: f32, wave_speed: f32, velocity: Vec2, to_observer: Vec2) -> Option<f32> {
+    let closing_speed = velocity.dot(to_observer.normalize_or_zero());
+    if closing_speed >= wave_speed {
+        return None;
+    }
+    Some(frequency * wave_speed / (wave_speed - closing_speed))
+}
+
+/// Half-angle of the Mach cone behind a source at `mach` times the wave
+/// speed, `asin(1/M)`. `None` at or below the wave speed.
+pub fn mach_angle(mach: f32) -> Option<f32> {
+    (mach > 1.0).then(|| (1.0 / mach).asin())
 }
 
+/// Frames the whole grid in the space between the panels.
+///
+/// Only refits when the camera, window size, or panel layout changes, so
+/// the user's pan and zoom are kept otherwise.
 pub fn fit_camera_to_viewport(
     windows: Query<&Window>,
-    mut camera_q: Query<(&mut OrthographicProjection, &mut Transform), With<Camera>>,
+    mut camera_q: Query<(Entity, &mut OrthographicProjection, &mut Transform, &mut Pan2dCamera), With<Camera>>,
     ui_state: Res<UIState>,
+    mut fitted_layout: Local<Option<(Entity, Vec2, bool)>>,
 ) {
     let Ok(window) = windows.get_single() else { return };
-    let Ok((mut projection, mut transform)) = camera_q.get_single_mut() else { return };
+    let Ok((entity, mut projection, mut transform, mut pan)) = camera_q.get_single_mut() else { return };
+
+    let layout = (entity, window.size(), ui_state.show_data_panel);
+    if *fitted_layout == Some(layout) {
+        return;
+    }
+    *fitted_layout = Some(layout);
 
     let available_w = window.width() - (super::TOOLBOX_PANEL_WIDTH + super::INSPECTOR_PANEL_WIDTH);
     let mut available_h = window.height() - super::TOP_BAR_HEIGHT;
@@ -525,8 +1375,8 @@ pub fn fit_camera_to_viewport(
     let available_w = available_w.max(100.0);
     let available_h = available_h.max(100.0);
 
-    let grid_w = super::GRID_WIDTH as f32 * super::GRID_SCALE;
-    let grid_h = super::GRID_HEIGHT as f32 * super::GRID_SCALE;
+    let grid_w = TANK_WIDTH;
+    let grid_h = TANK_HEIGHT;
 
     // Calculate scale to fit
     // We want: grid_dim * scale <= available_dim
@@ -561,6 +1411,8 @@ pub fn fit_camera_to_viewport(
     // Apply with some padding
     let padding = 1.1;
     projection.scale = target_scale * padding;
+    pan.min_scale = projection.scale * 0.05;
+    pan.max_scale = projection.scale * 2.0;
     
     // Center the camera in the available space
     // The viewport center is offset from the window center because of the left/right panels.
@@ -607,3 +1459,457 @@ pub fn fit_camera_to_viewport(
     transform.translation.x = -offset_x * projection.scale;
     transform.translation.y = offset_y * projection.scale;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::ripple_tank::{snell_refract, Reflecting, CFL_LIMIT, MAX_STEPS_PER_FRAME};
+    use std::time::Duration;
+
+    /// A tank world ready to run [`update_wave_field`]: the field, the
+    /// given config and a fixed timestep driven by a manual clock.
+    fn tank_world(width: usize, height: usize, config: RippleTankConfig) -> World {
+        let mut world = World::new();
+        world.insert_resource(WaveField::new(width, height));
+        world.insert_resource(config);
+        world.insert_resource(FixedTimestep::new(FDTD_STEP, MAX_STEPS_PER_FRAME));
+        world.insert_resource(Time::<()>::default());
+        world.init_resource::<TimeOfFlight>();
+        world
+    }
+
+    #[test]
+    fn test_pausing_freezes_source_phase_and_motion() {
+        let mut world = tank_world(64, 64, RippleTankConfig::default());
+        let source = world
+            .spawn((Transform::default(), WaveSource::default(), MovingSource::default()))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_wave_field);
+        // One and a half steps per frame, so every frame leaves a remainder.
+        let mut frame = |world: &mut World| {
+            world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP * 1.5));
+            schedule.run(world);
+        };
+        let snapshot = |world: &World| {
+            let phase = world.get::<WaveSource>(source).expect("source").clock_phase;
+            let position = world.get::<Transform>(source).expect("source").translation;
+            (phase, position, world.resource::<RippleTankConfig>().accumulated_time)
+        };
+
+        frame(&mut world);
+        let before = snapshot(&world);
+        assert!(before.0 > 0.0 && before.2 > 0.0, "the first frame should step the tank");
+
+        world.resource_mut::<RippleTankConfig>().paused = true;
+        for _ in 0..30 {
+            frame(&mut world);
+        }
+        assert_eq!(snapshot(&world), before);
+
+        // Resuming carries on from where the pause began.
+        world.resource_mut::<RippleTankConfig>().paused = false;
+        frame(&mut world);
+        let after = snapshot(&world);
+        assert!(after.2 > before.2 && after.2 <= before.2 + 2.0 * FDTD_STEP + 1e-6);
+        assert_ne!(after.1, before.1);
+    }
+
+    #[test]
+    fn test_fast_source_leaves_no_gaps_along_its_path() {
+        let mut world = tank_world(64, 64, RippleTankConfig::default());
+        // Five cells per step; start at a crest so every emission is nonzero.
+        let velocity = 5.0 * GRID_SCALE / FDTD_STEP;
+        world.spawn((
+            Transform::default(),
+            WaveSource { phase: PI / 2.0, ..default() },
+            MovingSource { velocity: Vec2::new(velocity, 0.0), ..default() },
+        ));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_wave_field);
+        world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP * 1.5));
+        schedule.run(&mut world);
+
+        let field = world.resource::<WaveField>();
+        for x in 32..37 {
+            assert!(field.current[field.idx(x, 32)].abs() > 0.1, "no emission at cell {x}");
+        }
+        assert_eq!(mach_angle(1.0), None);
+        assert!((mach_angle(2.0).expect("supersonic") - PI / 6.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_solo_and_mute_silence_sources() {
+        let crest = || WaveSource { phase: PI / 2.0, ..default() };
+        let (left, right) = (Transform::from_xyz(-40.0, 0.0, 0.0), Transform::from_xyz(40.0, 0.0, 0.0));
+        let emitting = |a: &WaveSource, b: &WaveSource| {
+            let mut field = WaveField::new(64, 64);
+            apply_wave_sources(&mut field, [(&left, a), (&right, b)], 100.0);
+            (field.sample(Vec2::new(-40.0, 0.0)) != 0.0, field.sample(Vec2::new(40.0, 0.0)) != 0.0)
+        };
+
+        assert_eq!(emitting(&crest(), &crest()), (true, true));
+        assert_eq!(emitting(&WaveSource { muted: true, ..crest() }, &crest()), (false, true));
+        assert_eq!(emitting(&WaveSource { solo: true, ..crest() }, &crest()), (true, false));
+        // A muted solo stays silent but still silences the rest.
+        assert_eq!(emitting(&WaveSource { solo: true, muted: true, ..crest() }, &crest()), (false, false));
+    }
+
+    #[test]
+    fn test_apodized_line_source_tapers_to_its_ends() {
+        let center = Transform::default();
+        let row = |apodization: Apodization| {
+            let mut field = WaveField::new(64, 64);
+            let source =
+                WaveSource { source_type: WaveSourceType::Line, phase: PI / 2.0, apodization, ..default() };
+            apply_wave_sources(&mut field, [(&center, &source)], 100.0);
+            let y = field.height / 2;
+            (field.current[field.idx(32, y)], field.current[field.idx(12, y)])
+        };
+
+        assert_eq!(row(Apodization::Uniform), (1.0, 1.0));
+        for apodization in [Apodization::Gaussian, Apodization::RaisedCosine] {
+            let (middle, end) = row(apodization);
+            assert!(middle > 0.99 && end < 0.1, "{apodization:?}: middle {middle}, end {end}");
+        }
+    }
+
+    #[test]
+    fn test_graded_index_lens_is_slowest_on_its_axis() {
+        let mut world = World::new();
+        world.insert_resource(WaveField::new(64, 64));
+        let lens = Obstacle {
+            obstacle_type: ObstacleType::GradedIndex,
+            width: 80.0,
+            height: 20.0,
+            refractive_index: 2.0,
+            index_gradient: 0.8,
+            ..default()
+        };
+        assert_eq!(lens.graded_focal_length(), Some(50.0));
+        world.spawn((Transform::default(), lens));
+        world.run_system_once(rasterize_obstacles);
+
+        // 20 cells each side of the axis at 2 units per cell.
+        let field = world.resource::<WaveField>();
+        let speed = |dx: i32| field.obstacle_map[field.idx((32 + dx) as usize, 32)];
+        assert!((speed(0) - 0.5).abs() < 1e-6);
+        assert!((speed(10) - 1.0 / 1.8).abs() < 1e-6);
+        assert!((speed(-20) - 1.0 / 1.2).abs() < 1e-6);
+        assert_eq!(speed(21), 1.0, "open water beyond the lens");
+    }
+
+    #[test]
+    fn test_dispersive_block_refracts_each_frequency_by_its_own_index() {
+        let mut world = tank_world(64, 64, RippleTankConfig::default());
+        world.init_resource::<DispersiveFields>();
+        let block = Obstacle {
+            obstacle_type: ObstacleType::RefractionBlock,
+            width: 40.0,
+            height: 40.0,
+            refractive_index: 1.2,
+            dispersion: 2.0,
+            ..default()
+        };
+        world.spawn((Transform::default(), block));
+        let slow = world.spawn((Transform::from_xyz(-30.0, -30.0, 0.0), WaveSource { frequency: 1.0, ..default() })).id();
+        let fast = world.spawn((Transform::from_xyz(30.0, -30.0, 0.0), WaveSource { frequency: 2.0, ..default() })).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((rasterize_obstacles, update_dispersive_fields, update_wave_field).chain());
+        for _ in 0..20 {
+            world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP));
+            schedule.run(&mut world);
+        }
+
+        // A ray entering the block's face at 30° bends by each band's index.
+        let fields = world.resource::<DispersiveFields>();
+        let refraction_angle = |source: Entity, expected_index: f32| {
+            let band = fields.bands.iter().find(|band| band.source == source).unwrap();
+            let index = 1.0 / band.field.obstacle_map[band.field.idx(32, 32)];
+            assert!((index - expected_index).abs() < 1e-5, "index {index}, expected {expected_index}");
+            let incident = Vec2::new(30f32.to_radians().sin(), -30f32.to_radians().cos());
+            snell_refract(incident, Vec2::Y, 1.0, index).unwrap().x.asin().to_degrees()
+        };
+        // n = 1.2 + 2/f²
+        let (slow_angle, fast_angle) = (refraction_angle(slow, 3.2), refraction_angle(fast, 1.7));
+        assert!(fast_angle - slow_angle > 5.0, "{slow_angle}° vs {fast_angle}°");
+
+        // Each band carries only its own source's waves, and the tank shows their sum.
+        let field = world.resource::<WaveField>();
+        assert!(fields.sums_to(field));
+        let source_cell = |entity: Entity| {
+            let cell = field.world_to_grid(world.get::<Transform>(entity).unwrap().translation.truncate());
+            field.idx(cell.x as usize, cell.y as usize)
+        };
+        let band = |source: Entity| &fields.bands.iter().find(|band| band.source == source).unwrap().field;
+        assert!(band(slow).current.iter().any(|&value| value != 0.0));
+        assert_eq!(band(fast).current[source_cell(slow)], 0.0);
+
+        // With one frequency left there is nothing to separate.
+        world.get_mut::<WaveSource>(fast).unwrap().frequency = 1.0;
+        schedule.run(&mut world);
+        assert!(world.resource::<DispersiveFields>().bands.is_empty());
+    }
+
+    #[test]
+    fn test_energy_flux_follows_a_travelling_pulse() {
+        // A Gaussian bump that moved one cell in +x over the last step.
+        let mut field = WaveField::new(40, 8);
+        for y in 0..8 {
+            for x in 0..40 {
+                let bump = |center: f32| (-((x as f32 - center) / 3.0).powi(2)).exp();
+                let i = field.idx(x, y);
+                field.previous[i] = bump(19.0);
+                field.current[i] = bump(20.0);
+            }
+        }
+        for x in [16, 18, 22, 24] {
+            let flux = field.energy_flux(x, 4);
+            assert!(flux.x > 0.0 && flux.y.abs() < 1e-6, "flux at x = {x} is {flux}");
+        }
+
+        std::mem::swap(&mut field.current, &mut field.previous);
+        assert!(field.energy_flux(18, 4).x < 0.0, "reversed in time, it flows back");
+    }
+
+    #[test]
+    fn test_poly_probe_samples_evenly_along_its_path() {
+        let probe = PolyProbe { points: vec![Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 4.0)], ..default() };
+        assert_eq!(probe.arc_length(), 7.0);
+        let points = probe.sample_points(Vec2::new(10.0, 0.0), 2.0);
+        assert_eq!(
+            points,
+            vec![Vec2::new(10.0, 0.0), Vec2::new(12.0, 0.0), Vec2::new(13.0, 1.0), Vec2::new(13.0, 3.0)],
+            "samples turn the corner without losing arc length"
+        );
+
+        let mut world = World::new();
+        let mut field = WaveField::new(64, 64);
+        field.current.fill(0.5);
+        world.insert_resource(field);
+        let entity = world.spawn((Transform::default(), probe)).id();
+        world.run_system_once(update_poly_probes);
+        let probe = world.get::<PolyProbe>(entity).expect("probe spawned");
+        assert_eq!(probe.sample_spacing, world.resource::<WaveField>().cell_size / 2.0);
+        assert!(probe.samples.len() > 4 && probe.samples.iter().all(|&value| value == 0.5));
+    }
+
+    #[test]
+    fn test_frequency_sweep_chirps_from_first_step() {
+        let linear = FrequencySweep { start: 1.0, end: 5.0, duration: 4.0, looping: false, started: Some(10.0), ..default() };
+        assert_eq!(linear.frequency_at(12.0), 3.0);
+        assert_eq!(linear.frequency_at(20.0), 5.0);
+        let looping = FrequencySweep { looping: true, ..linear };
+        assert_eq!(looping.frequency_at(15.0), 2.0);
+        let log = FrequencySweep { mode: SweepMode::Logarithmic, start: 1.0, end: 4.0, ..linear };
+        assert!((log.frequency_at(12.0) - 2.0).abs() < 1e-5, "halfway is one octave up");
+
+        let mut world = tank_world(64, 64, RippleTankConfig { accumulated_time: 3.0, ..default() });
+        let sweep = FrequencySweep { duration: 1.0, looping: false, ..default() };
+        let source = world.spawn((Transform::default(), WaveSource { sweep: Some(sweep), ..default() })).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_wave_field);
+        for _ in 0..31 {
+            world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP));
+            schedule.run(&mut world);
+        }
+        // The sweep began at the first step, t = 3, and is halfway at 3.5.
+        let source = world.get::<WaveSource>(source).expect("source");
+        assert_eq!(source.sweep.and_then(|sweep| sweep.started), Some(3.0));
+        assert!((source.frequency - 3.0).abs() < 0.01, "{}", source.frequency);
+    }
+
+    #[test]
+    fn test_time_of_flight_recovers_wave_speed() {
+        let mut world = tank_world(160, 64, RippleTankConfig { damping: 1.0, ..default() });
+        // One pulse every 300 steps, so only the first is in flight.
+        world.spawn((
+            Transform::from_xyz(-100.0, 0.0, 0.0),
+            WaveSource { waveform: Waveform::Pulse, frequency: 0.2, ..default() },
+        ));
+        let near = world.spawn((Transform::from_xyz(-40.0, 0.0, 0.0), Probe::new("A", Color::WHITE))).id();
+        let far = world.spawn((Transform::from_xyz(40.0, 0.0, 0.0), Probe::new("B", Color::WHITE))).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_wave_field);
+        for _ in 0..250 {
+            world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP * 1.01));
+            schedule.run(&mut world);
+        }
+
+        assert_eq!(world.resource::<TimeOfFlight>().emitted, Some(0.0));
+        let arrival = |entity| world.get::<Probe>(entity).expect("probe").arrival.expect("pulse arrived");
+        let (first, second) = (arrival(near), arrival(far));
+        assert!(first < second);
+        let speed = time_of_flight_speed(80.0, first, second).expect("distinct arrivals");
+        let expected = wave_speed_world(world.resource::<RippleTankConfig>(), FDTD_STEP);
+        assert!((speed / expected - 1.0).abs() < 0.15, "measured {speed}, expected {expected}");
+    }
+
+    #[test]
+    fn test_phased_array_phase_follows_steering_angle() {
+        // d = λ/4 at 30°: Δφ = (2π/λ)·(λ/4)·½ = π/4.
+        let step = steering_phase_step(25.0, 2.0, 200.0, PI / 6.0);
+        assert!((step - PI / 4.0).abs() < 1e-5);
+        assert_eq!(steering_phase_step(25.0, 2.0, 200.0, 0.0), 0.0);
+
+        let center = Transform::default();
+        let source = WaveSource {
+            source_type: WaveSourceType::PhasedArray { count: 3 },
+            phase: PI / 2.0,
+            steering_angle: PI / 6.0,
+            ..default()
+        };
+        let mut field = WaveField::new(64, 64);
+        let spacing = PHASED_ARRAY_SPACING * GRID_SCALE;
+        let wave_speed = 8.0 * spacing * source.frequency;
+        apply_wave_sources(&mut field, [(&center, &source)], wave_speed);
+        let y = field.height / 2;
+        let elements: Vec<f32> = [24, 32, 40].iter().map(|&x| field.current[field.idx(x, y)]).collect();
+        for (i, value) in elements.iter().enumerate() {
+            let expected = (PI / 2.0 - i as f32 * PI / 8.0).sin();
+            assert!((value - expected).abs() < 1e-5, "element {i}: {value} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn test_step_back_retraces_forward_steps() {
+        let mut world =
+            tank_world(48, 48, RippleTankConfig { damping: 1.0, boundary: Box::new(Reflecting), ..default() });
+        world.insert_resource(UIState::default());
+        world.spawn((Transform::from_xyz(10.0, -6.0, 0.0), WaveSource { phase: 0.3, ..default() }));
+
+        let mut forward = Schedule::default();
+        forward.add_systems(update_wave_field);
+        let mut backward = Schedule::default();
+        backward.add_systems(step_wave_field_backward);
+
+        // Run forward a few steps, then keep a copy of the field part way.
+        let mut run_steps = |world: &mut World, steps: u32| {
+            for _ in 0..steps {
+                world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP * 1.01));
+                forward.run(world);
+            }
+        };
+        run_steps(&mut world, 10);
+        let checkpoint = world.resource::<WaveField>().previous.clone();
+        run_steps(&mut world, 20);
+
+        world.resource_mut::<RippleTankConfig>().paused = true;
+        for _ in 0..20 {
+            world.resource_mut::<UIState>().step_back_requested = true;
+            backward.run(&mut world);
+        }
+
+        let field = world.resource::<WaveField>();
+        let error = field.previous.iter().zip(&checkpoint).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+        assert!(error < 1e-4, "field differs from the checkpoint by {error}");
+        let elapsed = world.resource::<RippleTankConfig>().accumulated_time;
+        assert!((elapsed - 10.0 * FDTD_STEP).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ruler_angle_snaps_and_folds_to_horizontal() {
+        let snapped = snap_angle(Vec2::from_angle(47f32.to_radians()) * 80.0, RULER_SNAP_DEGREES);
+        assert!((snapped.to_angle().to_degrees() - 45.0).abs() < 1e-4);
+        assert!((snapped.length() - 80.0).abs() < 1e-4);
+
+        // A line has no direction: both orderings read the same angle.
+        let ruler = Ruler { start: Vec2::new(10.0, 10.0), end: Vec2::new(-10.0, -10.0), snap_angle: true };
+        assert!((ruler.angle_degrees() - 45.0).abs() < 1e-4);
+        let flipped = Ruler { start: ruler.end, end: ruler.start, snap_angle: true };
+        assert!((flipped.angle_degrees() - 45.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_dragging_a_group_with_a_locked_member_follows_the_cursor() {
+        let mut world = World::new();
+        let mut spawn = |x: f32, locked: bool| {
+            world.spawn((Transform::from_xyz(x, 0.0, 0.0), SceneObject { id: 0, selected: false, locked })).id()
+        };
+        let selection = vec![spawn(-20.0, false), spawn(0.0, true), spawn(20.0, false)];
+        let unlocked = |entity: Entity| entity != selection[1];
+
+        // Grabbing the locked member hands the drag to an unlocked one.
+        let anchor = drag_anchor(selection[1], &selection, unlocked).unwrap();
+        assert_eq!(anchor, selection[0]);
+        assert_eq!(drag_anchor(selection[1], &selection[1..2], unlocked), None);
+
+        // Clicked at the origin, then held 10 units to the right for several frames.
+        let offset = Vec2::new(-20.0, 0.0);
+        for _ in 0..3 {
+            let selection = selection.clone();
+            world.run_system_once(move |mut objects: Query<(Entity, &mut Transform, &SceneObject)>| {
+                drag_selection(anchor, Vec2::new(10.0, 0.0) + offset, &selection, &mut objects);
+            });
+        }
+        let x = |entity: Entity| world.get::<Transform>(entity).unwrap().translation.x;
+        assert_eq!([x(selection[0]), x(selection[1]), x(selection[2])], [-10.0, 0.0, 30.0]);
+    }
+
+    #[test]
+    fn test_split_view_colors_each_side_with_its_own_scheme() {
+        let mut wave_field = WaveField::new(8, 2);
+        wave_field.current.fill(0.5);
+        let mut data = vec![0; 8 * 2 * 4];
+        write_split_wave_field_rgba(&wave_field, ColorScheme::Grayscale, ColorScheme::Scientific, split_column(8, 0.5), &mut data);
+
+        let pixel = |x: usize| -> (u8, u8, u8) { (data[x * 4], data[x * 4 + 1], data[x * 4 + 2]) };
+        assert_eq!(pixel(3), wave_pixel_color(0.5, 1.0, ColorScheme::Grayscale));
+        assert_eq!(pixel(4), wave_pixel_color(0.5, 1.0, ColorScheme::Scientific));
+        assert_ne!(pixel(3), pixel(4));
+    }
+
+    #[test]
+    fn test_boundary_tint_deepens_toward_the_edge() {
+        let wave_field = WaveField::new(16, 16);
+        let mut data = vec![0; 16 * 16 * 4];
+        write_wave_field_rgba(&wave_field, ColorScheme::Grayscale, &mut data);
+        let untinted = data.clone();
+        tint_absorbing_layer(&wave_field, 4, &mut data);
+
+        // Along the middle row: the outermost cell is reddest, the tint
+        // fades inward, and the interior is untouched.
+        let red = |x: usize| data[(8 * 16 + x) * 4] as i32 - data[(8 * 16 + x) * 4 + 1] as i32;
+        assert!(red(0) > red(1) && red(1) > red(2) && red(2) > red(3) && red(3) > 0);
+        assert_eq!(data[(8 * 16 + 8) * 4..(8 * 16 + 9) * 4], untinted[(8 * 16 + 8) * 4..(8 * 16 + 9) * 4]);
+    }
+
+    #[test]
+    fn test_fast_medium_past_the_cfl_limit_flags_the_solver_unstable() {
+        let run = |refractive_index: f32| {
+            let mut world = tank_world(64, 64, RippleTankConfig::default());
+            world.init_resource::<SimulationStats>();
+            world.init_resource::<DataRecorder>();
+            world.spawn((Transform::default(), WaveSource::default()));
+            let medium = Obstacle {
+                obstacle_type: ObstacleType::RefractionBlock,
+                width: 200.0,
+                height: 200.0,
+                refractive_index,
+                ..default()
+            };
+            world.spawn((Transform::default(), medium));
+
+            let mut schedule = Schedule::default();
+            schedule.add_systems((rasterize_obstacles, update_wave_field, update_stats).chain());
+            for _ in 0..60 {
+                world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(FDTD_STEP));
+                schedule.run(&mut world);
+            }
+            let stats = world.resource::<SimulationStats>();
+            (stats.courant, stats.solver_unstable)
+        };
+
+        let (courant, unstable) = run(1.5);
+        assert!(courant < CFL_LIMIT && !unstable, "glass is stable: {courant}");
+        // Waves twice as fast as in open water outrun the grid.
+        let (courant, unstable) = run(0.5);
+        assert!(courant > CFL_LIMIT && unstable, "n < 1 diverges: {courant}");
+    }
+}
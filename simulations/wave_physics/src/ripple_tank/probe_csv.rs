@@ -0,0 +1,77 @@
+//! CSV export of probe time series and recorded observables
+
+use ez_core::recording::{csv_field, DataRecorder};
+
+use super::{components::*, file_io::save_file};
+
+pub const PROBE_CSV_FILE_NAME: &str = "ripple_tank_probes.csv";
+pub const RECORDING_CSV_FILE_NAME: &str = "ripple_tank_recording.csv";
+
+// ══════════════════════════════════════════════════════════════════════════════
+// CSV Export
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Formats probe histories as CSV with a leading time column.
+///
+/// Histories end at the same instant (`end_time`), so shorter ones are
+/// padded with empty cells at the top to keep each row simultaneous.
+pub fn probe_csv<'a>(probes: impl IntoIterator<Item = &'a Probe>, sample_interval: f32, end_time: f32) -> String {
+    let probes: Vec<&Probe> = probes.into_iter().collect();
+    let rows = probes.iter().map(|probe| probe.history.len()).max().unwrap_or(0);
+
+    let mut csv = String::from("time");
+    for probe in &probes {
+        csv.push(',');
+        csv.push_str(&csv_field(&probe.label));
+    }
+    csv.push('\n');
+
+    for row in 0..rows {
+        let time = end_time - (rows - 1 - row) as f32 * sample_interval;
+        csv.push_str(&format!("{time:.6}"));
+        for probe in &probes {
+            csv.push(',');
+            let padding = rows - probe.history.len();
+            if let Some(value) = row.checked_sub(padding).map(|i| probe.history[i]) {
+                csv.push_str(&format!("{value}"));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Saves probe histories to a CSV file (a download in the browser).
+pub fn export_probe_csv<'a>(probes: impl IntoIterator<Item = &'a Probe>, sample_interval: f32, end_time: f32) {
+    let csv = probe_csv(probes, sample_interval, end_time);
+    save_file(PROBE_CSV_FILE_NAME, "text/csv", csv.as_bytes());
+}
+
+/// Saves every recorded channel to a CSV file (a download in the browser).
+pub fn export_recording_csv(recorder: &DataRecorder) {
+    save_file(RECORDING_CSV_FILE_NAME, "text/csv", recorder.to_csv().as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    #[test]
+    fn test_unequal_histories_align_to_latest_sample() {
+        let mut long = Probe::new("P1", Color::WHITE);
+        long.history = vec![1.0, 2.0, 3.0];
+        let mut short = Probe::new("a, \"b\"", Color::WHITE);
+        short.history = vec![5.0];
+
+        let csv = probe_csv([&long, &short], 0.5, 10.0);
+
+        assert_eq!(
+            csv,
+            "time,P1,\"a, \"\"b\"\"\"\n\
+             9.000000,1,\n\
+             9.500000,2,\n\
+             10.000000,3,5\n"
+        );
+    }
+}
@@ -1,8 +1,18 @@
 //! Resources for the Ripple Tank simulation
 
 use bevy::prelude::*;
+use ez_core::math::lerp;
+use rayon::prelude::*;
+use ez_core::parameters::ParameterValue;
+use ez_ui::panels::SimulationParameters;
+use std::collections::VecDeque;
 
-use super::{GRID_SCALE, GRID_WIDTH, GRID_HEIGHT};
+use super::boundary::{Absorbing, BoundaryCondition, Periodic, Reflecting};
+use super::components::RulerEnd;
+use super::{
+    AMPLITUDE_CLAMP, ARRIVAL_THRESHOLD, DEFAULT_PML_WIDTH, GRID_SCALE, GRID_WIDTH, GRID_HEIGHT, PML_MAX_DAMPING, SPECTROGRAM_ROWS, TANK_HEIGHT,
+    ZERO_CROSSING_THRESHOLD,
+};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Wave Field (FDTD Grid)
@@ -12,32 +22,76 @@ use super::{GRID_SCALE, GRID_WIDTH, GRID_HEIGHT};
 pub struct WaveField {
     pub current: Vec<f32>,
     pub previous: Vec<f32>,
+    /// Scratch buffer the next step is written into before the three
+    /// buffers rotate, so stepping never allocates.
+    next: Vec<f32>,
+    /// Running exponential average of `current²`.
+    pub intensity: Vec<f32>,
     pub obstacle_map: Vec<f32>,
+    /// Fraction of a wave's amplitude kept per cell it crosses: 1 in open
+    /// water, lower inside partial reflectors.
+    pub transmission: Vec<f32>,
     pub width: usize,
     pub height: usize,
+    /// Size of one cell in world units.
+    pub cell_size: f32,
+    /// Cells that hit [`AMPLITUDE_CLAMP`] in the latest step; more than a
+    /// handful means the solve is diverging.
+    pub clamped_cells: usize,
 }
 
 impl Default for WaveField {
     fn default() -> Self {
-        let size = GRID_WIDTH * GRID_HEIGHT;
+        Self::new(GRID_WIDTH, GRID_HEIGHT)
+    }
+}
+
+impl WaveField {
+    pub fn new(width: usize, height: usize) -> Self {
+        let size = width * height;
         Self {
             current: vec![0.0; size],
             previous: vec![0.0; size],
+            next: vec![0.0; size],
+            intensity: vec![0.0; size],
             obstacle_map: vec![1.0; size],
-            width: GRID_WIDTH,
-            height: GRID_HEIGHT,
+            transmission: vec![1.0; size],
+            width,
+            height,
+            cell_size: GRID_SCALE,
+            clamped_cells: 0,
         }
     }
-}
 
-impl WaveField {
+    /// Allocates a field covering the whole tank with `grid_size` cells
+    /// along its height.
+    pub fn with_grid_size(grid_size: usize) -> Self {
+        let width = grid_size * GRID_WIDTH / GRID_HEIGHT;
+        Self {
+            cell_size: TANK_HEIGHT / grid_size as f32,
+            ..Self::new(width, grid_size)
+        }
+    }
+
+    /// Converts a world position to (fractional) grid coordinates.
+    pub fn world_to_grid(&self, world_pos: Vec2) -> Vec2 {
+        world_pos / self.cell_size + Vec2::new(self.width as f32, self.height as f32) / 2.0
+    }
+
+    /// World position of the center of cell `(x, y)`.
+    pub fn cell_center(&self, x: usize, y: usize) -> Vec2 {
+        (Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - Vec2::new(self.width as f32, self.height as f32) / 2.0) * self.cell_size
+    }
+
     pub fn clear(&mut self) {
         self.current.fill(0.0);
         self.previous.fill(0.0);
+        self.intensity.fill(0.0);
     }
 
     pub fn clear_obstacles(&mut self) {
         self.obstacle_map.fill(1.0);
+        self.transmission.fill(1.0);
     }
 
     #[inline]
@@ -45,17 +99,267 @@ impl WaveField {
         y * self.width + x
     }
 
+    /// Damping coefficient of the absorbing layer at a cell.
+    ///
+    /// Zero in the interior, rising quadratically to `PML_MAX_DAMPING` at the
+    /// outermost cell of a `pml_width`-cell border.
+    pub fn pml_damping(&self, x: usize, y: usize, pml_width: usize) -> f32 {
+        let edge_distance = x.min(y).min(self.width - 1 - x).min(self.height - 1 - y);
+        if edge_distance >= pml_width {
+            return 0.0;
+        }
+        let depth = (pml_width - edge_distance) as f32 / pml_width as f32;
+        PML_MAX_DAMPING * depth * depth
+    }
+
+    /// Advances the field by one FDTD step, then lets `boundary` set the
+    /// border cells.
+    ///
+    /// Inside the absorbing border this solves the damped wave equation
+    /// `u_tt + σ u_t = c² ∇²u`, so outgoing waves decay smoothly instead of
+    /// reflecting off a hard edge. Rows are updated in parallel; each reads
+    /// only `current`, `previous` and the obstacle map, so the result is
+    /// identical to [`Self::step_serial`].
+    pub fn step(&mut self, c2: f32, damping: f32, pml_width: usize, boundary: &dyn BoundaryCondition) {
+        self.advance(c2, damping, pml_width, boundary, true);
+    }
+
+    /// Single-threaded version of [`Self::step`].
+    pub fn step_serial(&mut self, c2: f32, damping: f32, pml_width: usize, boundary: &dyn BoundaryCondition) {
+        self.advance(c2, damping, pml_width, boundary, false);
+    }
+
+    fn advance(&mut self, c2: f32, damping: f32, pml_width: usize, boundary: &dyn BoundaryCondition, parallel: bool) {
+        let width = self.width;
+        let height = self.height;
+
+        // The scratch buffer holds a stale step; the boundary overwrites
+        // the border once the interior is done.
+        let mut next = std::mem::take(&mut self.next);
+
+        let interior = &mut next[width..(height - 1) * width];
+        let update_row = |(row, cells): (usize, &mut [f32])| self.update_row(row + 1, cells, c2, damping, pml_width);
+        self.clamped_cells = if parallel {
+            interior.par_chunks_mut(width).enumerate().map(update_row).sum()
+        } else {
+            interior.chunks_mut(width).enumerate().map(update_row).sum()
+        };
+        boundary.apply(&mut next, width, height);
+
+        // previous ← current ← next, recycling the old previous as scratch.
+        self.next = std::mem::replace(&mut self.previous, std::mem::replace(&mut self.current, next));
+    }
+
+    /// Writes interior row `y` of the next step into `next_row`, returning
+    /// how many of its cells were clamped.
+    fn update_row(&self, y: usize, next_row: &mut [f32], c2: f32, damping: f32, pml_width: usize) -> usize {
+        let width = self.width;
+        let mut clamped = 0;
+        for (x, cell) in next_row.iter_mut().enumerate().take(width - 1).skip(1) {
+            let idx = y * width + x;
+            let obstacle = self.obstacle_map[idx];
+
+            if obstacle == 0.0 {
+                *cell = 0.0;
+                continue;
+            }
+
+            let laplacian = self.current[idx - 1]
+                + self.current[idx + 1]
+                + self.current[idx - width]
+                + self.current[idx + width]
+                - 4.0 * self.current[idx];
+
+            let effective_c2 = c2 * obstacle * obstacle;
+            let half_sigma = 0.5 * self.pml_damping(x, y, pml_width);
+            let value = (2.0 * self.current[idx] - (1.0 - half_sigma) * self.previous[idx]
+                + effective_c2 * laplacian)
+                / (1.0 + half_sigma);
+            // Attenuate by the share of a cell the wave crossed this step.
+            let transmission = self.transmission[idx];
+            let value = if transmission < 1.0 { value * transmission.powf(effective_c2.sqrt()) } else { value };
+            let value = damping * value;
+            if value.abs() >= AMPLITUDE_CLAMP {
+                clamped += 1;
+            }
+            *cell = value.clamp(-AMPLITUDE_CLAMP, AMPLITUDE_CLAMP);
+        }
+        clamped
+    }
+
+    /// Undoes one lossless [`Self::step`] (no damping, no absorbing layer).
+    ///
+    /// The leapfrog stencil is symmetric in time, so running it with
+    /// `current` and `previous` swapped recovers the level before
+    /// `previous`, and `boundary` then sets its border as the forward step
+    /// did. Cells overwritten after a step (sources) can't be recovered
+    /// this way and must be restored by the caller. Damping and partial
+    /// reflectors are not reversible, and values clamped by the forward
+    /// step are lost.
+    pub fn step_backward(&mut self, c2: f32, boundary: &dyn BoundaryCondition) {
+        self.swap_time_levels();
+        self.advance(c2, 1.0, 0, boundary, true);
+        self.swap_time_levels();
+    }
+
+    /// Exchanges `current` and `previous`, reversing the direction of time.
+    pub fn swap_time_levels(&mut self) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+    }
+
+    /// A still field of the same size and resolution with open water
+    /// everywhere.
+    pub fn empty_like(&self) -> Self {
+        Self {
+            cell_size: self.cell_size,
+            ..Self::new(self.width, self.height)
+        }
+    }
+
+    /// Sets both time levels to the sum of `parts`, which must share this
+    /// field's size.
+    pub fn superpose<'a>(&mut self, parts: impl IntoIterator<Item = &'a WaveField>) {
+        self.current.fill(0.0);
+        self.previous.fill(0.0);
+        self.clamped_cells = 0;
+        for part in parts {
+            for (sum, value) in self.current.iter_mut().zip(&part.current) {
+                *sum += value;
+            }
+            for (sum, value) in self.previous.iter_mut().zip(&part.previous) {
+                *sum += value;
+            }
+            self.clamped_cells += part.clamped_cells;
+        }
+    }
+
+    /// Blends the current field's squared amplitude into `intensity`.
+    ///
+    /// `alpha` is the weight of the newest sample; smaller values average
+    /// over more steps.
+    pub fn accumulate_intensity(&mut self, alpha: f32) {
+        for (intensity, value) in self.intensity.iter_mut().zip(&self.current) {
+            *intensity += alpha * (value * value - *intensity);
+        }
+    }
+
+    /// Distances along the line from `start` to `end` where the field
+    /// changes sign, sampled at half-cell spacing.
+    ///
+    /// Samples weaker than [`ZERO_CROSSING_THRESHOLD`] of the line's peak are
+    /// skipped so noise around a node doesn't count as extra crossings.
+    pub fn zero_crossings(&self, start: Vec2, end: Vec2) -> Vec<f32> {
+        let length = start.distance(end);
+        let step = self.cell_size / 2.0;
+        let count = (length / step) as usize + 1;
+        let samples: Vec<(f32, f32)> = (0..count)
+            .map(|i| {
+                let distance = i as f32 * step;
+                (distance, self.sample(start.lerp(end, distance / length.max(f32::EPSILON))))
+            })
+            .collect();
+
+        let peak = samples.iter().fold(0.0f32, |peak, (_, value)| peak.max(value.abs()));
+        if peak <= f32::EPSILON {
+            return Vec::new();
+        }
+        let threshold = peak * ZERO_CROSSING_THRESHOLD;
+
+        let mut crossings = Vec::new();
+        let mut last: Option<(f32, f32)> = None;
+        for (distance, value) in samples.into_iter().filter(|(_, value)| value.abs() > threshold) {
+            if let Some((last_distance, last_value)) = last {
+                if last_value.signum() != value.signum() {
+                    let t = last_value / (last_value - value);
+                    crossings.push(lerp(last_distance, distance, t));
+                }
+            }
+            last = Some((distance, value));
+        }
+        crossings
+    }
+
+    /// Time-averaged intensity sampled every half cell from `start` to `end`.
+    pub fn intensity_profile(&self, start: Vec2, end: Vec2) -> Vec<f32> {
+        let length = start.distance(end);
+        let step = self.cell_size / 2.0;
+        let count = (length / step) as usize + 1;
+        (0..count)
+            .map(|i| self.cell_value(&self.intensity, start.lerp(end, i as f32 * step / length.max(f32::EPSILON))))
+            .collect()
+    }
+
+    /// Energy flux at an interior cell, −∂u/∂t · ∇u per step and per cell;
+    /// it points the way the waves there are travelling.
+    pub fn energy_flux(&self, x: usize, y: usize) -> Vec2 {
+        let field = &self.current;
+        let gradient = Vec2::new(
+            field[self.idx(x + 1, y)] - field[self.idx(x - 1, y)],
+            field[self.idx(x, y + 1)] - field[self.idx(x, y - 1)],
+        ) / 2.0;
+        let rate = field[self.idx(x, y)] - self.previous[self.idx(x, y)];
+        -rate * gradient
+    }
+
     pub fn sample(&self, world_pos: Vec2) -> f32 {
-        let grid_x = ((world_pos.x / GRID_SCALE) + (self.width as f32 / 2.0)) as usize;
-        let grid_y = ((world_pos.y / GRID_SCALE) + (self.height as f32 / 2.0)) as usize;
+        self.cell_value(&self.current, world_pos)
+    }
+
+    /// Time-averaged intensity ⟨u²⟩ in the cell under a world position.
+    pub fn intensity_at(&self, world_pos: Vec2) -> f32 {
+        self.cell_value(&self.intensity, world_pos)
+    }
+
+    /// Obstacle map value under a world position: 0 in a wall, 1 in open
+    /// water, and 0 outside the tank.
+    pub fn obstacle_at(&self, world_pos: Vec2) -> f32 {
+        self.cell_value(&self.obstacle_map, world_pos)
+    }
+
+    fn cell_value(&self, values: &[f32], world_pos: Vec2) -> f32 {
+        let grid = self.world_to_grid(world_pos);
+        let (grid_x, grid_y) = (grid.x as usize, grid.y as usize);
         if grid_x < self.width && grid_y < self.height {
-            self.current[self.idx(grid_x, grid_y)]
+            values[self.idx(grid_x, grid_y)]
         } else {
             0.0
         }
     }
 }
 
+/// One enabled source's share of the field in [`DispersiveFields`].
+pub struct FrequencyBand {
+    pub source: Entity,
+    pub field: WaveField,
+}
+
+/// Per-source fields, used while a dispersive block meets sources of more
+/// than one frequency; empty otherwise.
+///
+/// A field holds one wave speed per cell, so each source gets a band of its
+/// own with dispersive blocks rasterized at its frequency. The solver is
+/// linear, so the [`WaveField`] everything else reads is the bands' sum.
+#[derive(Resource, Default)]
+pub struct DispersiveFields {
+    pub bands: Vec<FrequencyBand>,
+}
+
+impl DispersiveFields {
+    /// Whether `wave_field` is still the sum of the bands. Anything that
+    /// rewrites the field directly (a reset, the history scrubber, a new
+    /// grid size) breaks this.
+    pub fn sums_to(&self, wave_field: &WaveField) -> bool {
+        if self.bands.iter().any(|band| band.field.current.len() != wave_field.current.len()) {
+            return false;
+        }
+        (0..wave_field.current.len()).all(|idx| {
+            let current = self.bands.iter().fold(0.0, |sum, band| sum + band.field.current[idx]);
+            let previous = self.bands.iter().fold(0.0, |sum, band| sum + band.field.previous[idx]);
+            current == wave_field.current[idx] && previous == wave_field.previous[idx]
+        })
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // Configuration
 // ══════════════════════════════════════════════════════════════════════════════
@@ -64,10 +368,35 @@ impl WaveField {
 pub struct RippleTankConfig {
     pub wave_speed: f32,
     pub damping: f32,
+    /// What waves do at the tank's edges; see [`BoundaryMode`] for the
+    /// built-in choices.
+    pub boundary: Box<dyn BoundaryCondition>,
+    /// Width of the absorbing boundary layer in cells.
+    pub pml_width: usize,
     pub time_scale: f32,
     pub paused: bool,
     pub show_grid: bool,
+    /// Grid cells along the tank's height (see `GRID_SIZE_OPTIONS`).
+    pub grid_size: usize,
+    /// Draw the Snell's-law ray prediction through refraction blocks.
+    pub show_snell_overlay: bool,
+    /// Draw arrows along the field gradient, pointing uphill.
+    pub show_gradient: bool,
+    /// Draw arrows along the energy flux, the way the waves are travelling.
+    pub show_flux: bool,
+    /// Draw the wavefront circles expanding from each point source.
+    pub show_huygens: bool,
+    /// Tint the absorbing layer red, more strongly where it damps harder.
+    pub show_boundary: bool,
+    /// Keep every source at the same frequency so their phases stay locked.
+    pub lock_frequencies: bool,
     pub color_scheme: ColorScheme,
+    /// Show the field in two color schemes side by side.
+    pub split_view: bool,
+    /// Scheme for the right-hand side of the split view.
+    pub color_scheme_b: ColorScheme,
+    /// Where the split view divides, as a fraction of the tank's width.
+    pub split_position: f32,
     pub accumulated_time: f32,
 }
 
@@ -76,15 +405,121 @@ impl Default for RippleTankConfig {
         Self {
             wave_speed: 1.0,
             damping: 0.995,
+            boundary: Box::new(Absorbing),
+            pml_width: DEFAULT_PML_WIDTH,
             time_scale: 1.0,
             paused: false,
             show_grid: true,
+            grid_size: GRID_HEIGHT,
+            show_snell_overlay: false,
+            show_gradient: false,
+            show_flux: false,
+            show_huygens: false,
+            show_boundary: false,
+            lock_frequencies: false,
             color_scheme: ColorScheme::DeepOcean,
+            split_view: false,
+            color_scheme_b: ColorScheme::Scientific,
+            split_position: 0.5,
             accumulated_time: 0.0,
         }
     }
 }
 
+impl RippleTankConfig {
+    /// Current values of the settings that persist between sessions, keyed
+    /// by the simulation's parameter ids.
+    pub fn saved_parameters(&self) -> [(&'static str, ParameterValue); 6] {
+        [
+            ("wave_speed", ParameterValue::Float(self.wave_speed)),
+            ("damping", ParameterValue::Float(self.damping)),
+            ("pml_width", ParameterValue::Int(self.pml_width as i32)),
+            ("boundary_mode", ParameterValue::Enum(self.boundary_mode().unwrap_or_default() as usize)),
+            ("time_scale", ParameterValue::Float(self.time_scale)),
+            ("color_scheme", ParameterValue::Enum(self.color_scheme as usize)),
+        ]
+    }
+
+    /// The built-in boundary in use, or `None` for a custom one.
+    pub fn boundary_mode(&self) -> Option<BoundaryMode> {
+        BoundaryMode::ALL.into_iter().find(|mode| mode.label() == self.boundary.name())
+    }
+
+    /// Width of the absorbing layer in cells, or 0 when the boundary
+    /// doesn't absorb.
+    pub fn absorbing_width(&self) -> usize {
+        if self.boundary.absorbs() {
+            self.pml_width
+        } else {
+            0
+        }
+    }
+
+    /// Whether the FDTD update can be run backward: no damping and no
+    /// absorbing layer.
+    pub fn is_time_reversible(&self) -> bool {
+        self.damping >= 1.0 && self.absorbing_width() == 0
+    }
+
+    /// Applies persisted parameter values, ignoring any that are missing.
+    pub fn apply_parameters(&mut self, params: &SimulationParameters) {
+        if let Some(wave_speed) = params.get_float("wave_speed") {
+            self.wave_speed = wave_speed;
+        }
+        if let Some(damping) = params.get_float("damping") {
+            self.damping = damping;
+        }
+        if let Some(pml_width) = params.get_int("pml_width") {
+            self.pml_width = pml_width.max(0) as usize;
+        }
+        if let Some(mode) = params.get_enum("boundary_mode").and_then(|i| BoundaryMode::ALL.get(i)) {
+            self.boundary = mode.condition();
+        }
+        if let Some(time_scale) = params.get_float("time_scale") {
+            self.time_scale = time_scale;
+        }
+        if let Some(scheme) = params.get_enum("color_scheme").and_then(|i| ColorScheme::ALL.get(i)) {
+            self.color_scheme = *scheme;
+        }
+    }
+}
+
+/// How waves behave at the edges of the tank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryMode {
+    /// Outgoing waves fade in an absorbing layer, as in an open tank.
+    #[default]
+    Absorbing,
+    /// Hard walls (zero normal slope) reflect waves back, so a steady
+    /// source builds up standing-wave patterns.
+    Reflecting,
+    /// Opposite edges are joined, so waves leaving one side re-enter on the
+    /// other.
+    Periodic,
+}
+
+impl BoundaryMode {
+    /// Every mode, in the order of the `boundary_mode` parameter's options.
+    pub const ALL: [BoundaryMode; 3] = [Self::Absorbing, Self::Reflecting, Self::Periodic];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Absorbing => "Absorbing",
+            Self::Reflecting => "Reflecting",
+            Self::Periodic => "Periodic",
+        }
+    }
+
+    /// A new instance of the boundary condition.
+    pub fn condition(self) -> Box<dyn BoundaryCondition> {
+        match self {
+            Self::Absorbing => Box::new(Absorbing),
+            Self::Reflecting => Box::new(Reflecting),
+            Self::Periodic => Box::new(Periodic),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ColorScheme {
     #[default]
@@ -92,6 +527,14 @@ pub enum ColorScheme {
     Scientific,
     PhaseColor,
     Grayscale,
+    /// Time-averaged intensity ⟨u²⟩
+    Intensity,
+}
+
+impl ColorScheme {
+    /// Every scheme, in the order of the `color_scheme` parameter's options.
+    pub const ALL: [ColorScheme; 5] =
+        [Self::DeepOcean, Self::Scientific, Self::PhaseColor, Self::Grayscale, Self::Intensity];
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -101,10 +544,37 @@ pub enum ColorScheme {
 #[derive(Resource, Default)]
 pub struct UIState {
     pub selected_tool: ToolType,
+    /// Object shown in the inspector, the last one clicked.
     pub selected_entity: Option<Entity>,
+    /// Every selected object, `selected_entity` among them; they move
+    /// together. Shift+click adds or removes objects.
+    pub selection: Vec<Entity>,
     pub dragging: Option<Entity>,
     pub drag_offset: Vec2,
     pub show_data_panel: bool,
+    /// Show the overview inset in the corner of the viewport.
+    pub show_minimap: bool,
+    /// Clicks append waypoints to the selected moving source.
+    pub placing_waypoints: bool,
+    /// Poly probe whose vertices clicks are adding, until a right click.
+    pub placing_poly_probe: Option<Entity>,
+    /// Ruler endpoint being dragged, if any.
+    pub dragging_ruler_end: Option<(Entity, RulerEnd)>,
+    /// Step Back was pressed; cleared once the step is undone.
+    pub step_back_requested: bool,
+    /// Show the far-field diffraction panel for the selected slit.
+    pub show_far_field: bool,
+    /// Split the window to compare the tank with the same tank minus its
+    /// slits; see [`SlitComparison`](super::SlitComparison).
+    pub compare_without_slits: bool,
+}
+
+impl UIState {
+    /// Selects just `entity`, or nothing.
+    pub fn select(&mut self, entity: Option<Entity>) {
+        self.selected_entity = entity;
+        self.selection = entity.into_iter().collect();
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -119,8 +589,11 @@ pub enum ToolType {
     SingleSlit,
     DoubleSlit,
     RefractionBlock,
+    GradedIndexLens,
     Probe,
+    PolyProbe,
     Ruler,
+    Screen,
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -131,9 +604,241 @@ pub enum ToolType {
 pub struct SimulationStats {
     pub fps: f32,
     pub simulation_time: f32,
+    /// Simulated time between probe samples (one FDTD step).
+    pub sample_interval: f32,
     pub wave_energy: f32,
     pub probe_phase_diff: Option<f32>,
+    /// Doppler-shifted frequency of the nearest moving source at the first probe.
+    pub observed_frequency: Option<f32>,
+    /// The source is closing on the first probe at or above the wave speed.
+    pub shock_cone: bool,
+    /// Speed of the fastest enabled moving source, in world units per second.
+    pub source_speed: Option<f32>,
+    /// That source's speed as a multiple of the wave speed.
+    pub mach_number: Option<f32>,
+    /// Courant number c·Δt/Δx of each substep in the fastest medium; the
+    /// solve is stable below [`CFL_LIMIT`](super::CFL_LIMIT).
+    pub courant: f32,
+    /// Many cells are pinned at the amplitude clamp: the solve has blown up.
+    pub solver_unstable: bool,
+}
+
+/// Stopwatch for time-of-flight measurements: started by each pulse a
+/// source emits and stopped at each probe by [`Probe::arrival`].
+///
+/// [`Probe::arrival`]: super::components::Probe::arrival
+#[derive(Resource)]
+pub struct TimeOfFlight {
+    /// |u| a probe must reach for the pulse to count as arrived.
+    pub threshold: f32,
+    /// Simulation time the latest pulse began, if one has been emitted.
+    pub emitted: Option<f32>,
+    /// Whether a pulse was being emitted at the last step.
+    pulse_high: bool,
+}
+
+impl Default for TimeOfFlight {
+    fn default() -> Self {
+        Self { threshold: ARRIVAL_THRESHOLD, emitted: None, pulse_high: false }
+    }
+}
+
+impl TimeOfFlight {
+    /// Notes whether a pulse is being emitted at `time`. Returns `true` when
+    /// the stopwatch restarts, at the start of a new pulse or when the clock
+    /// has gone back past the last one; arrivals from before are then stale.
+    pub fn observe(&mut self, pulse_high: bool, time: f32) -> bool {
+        let onset = pulse_high && !self.pulse_high;
+        let rewound = self.emitted.is_some_and(|emitted| emitted > time);
+        self.pulse_high = pulse_high;
+        if onset {
+            self.emitted = Some(time);
+        } else if rewound {
+            self.emitted = None;
+        }
+        onset || rewound
+    }
+}
+
+/// Wave speed implied by a pulse reaching two probes `distance` apart at
+/// times `first` and `second`, or `None` if it reached both at once.
+pub fn time_of_flight_speed(distance: f32, first: f32, second: f32) -> Option<f32> {
+    let delta = (second - first).abs();
+    (delta > 0.0).then(|| distance / delta)
+}
+
+/// Recent magnitude spectra of one probe, oldest first.
+#[derive(Resource, Default)]
+pub struct Spectrogram {
+    /// Probe the spectra were taken from.
+    pub probe: Option<Entity>,
+    pub rows: VecDeque<Vec<f32>>,
+    /// Simulation time of the newest row.
+    pub last_row_time: f32,
+}
+
+impl Spectrogram {
+    /// Appends a spectrum, dropping the oldest beyond [`SPECTROGRAM_ROWS`].
+    pub fn push(&mut self, row: Vec<f32>, time: f32) {
+        if self.rows.len() == SPECTROGRAM_ROWS {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(row);
+        self.last_row_time = time;
+    }
+
+    /// Starts over on `probe`.
+    pub fn reset(&mut self, probe: Option<Entity>) {
+        self.probe = probe;
+        self.rows.clear();
+        self.last_row_time = 0.0;
+    }
+
+    /// Largest magnitude in the history, or 0 when empty.
+    pub fn peak(&self) -> f32 {
+        self.rows.iter().flatten().copied().fold(0.0, f32::max)
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct ObjectIdCounter(pub u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    /// Energy left in the interior after a pulse has reached the edges and
+    /// had time to travel back.
+    fn interior_energy_after_reflection(pml_width: usize) -> f32 {
+        let size = 96;
+        let mut field = WaveField::new(size, size);
+        let center = size as f32 / 2.0;
+        for y in 0..size {
+            for x in 0..size {
+                let r2 = (x as f32 - center).powi(2) + (y as f32 - center).powi(2);
+                let idx = field.idx(x, y);
+                field.current[idx] = (-r2 / 8.0).exp();
+                field.previous[idx] = field.current[idx];
+            }
+        }
+
+        // At 0.4 cells/step the pulse reaches the edge in ~120 steps.
+        for _ in 0..360 {
+            field.step(0.16, 1.0, pml_width, &Absorbing);
+        }
+
+        let margin = 16;
+        let mut energy = 0.0;
+        for y in margin..size - margin {
+            for x in margin..size - margin {
+                energy += field.current[field.idx(x, y)].powi(2);
+            }
+        }
+        energy
+    }
+
+    #[test]
+    fn test_zero_crossings_measure_wavelength() {
+        // A standing wave along x with a 40-unit wavelength (20 cells).
+        let mut field = WaveField::new(200, 20);
+        for y in 0..field.height {
+            for x in 0..field.width {
+                let idx = field.idx(x, y);
+                field.current[idx] = (2.0 * std::f32::consts::PI * (x as f32 + 0.5) / 20.0).cos();
+            }
+        }
+
+        let crossings = field.zero_crossings(Vec2::new(-150.0, 0.0), Vec2::new(150.0, 0.0));
+        let wavelength = crate::ripple_tank::wavelength_from_zero_crossings(&crossings).expect("several crossings");
+        assert_eq!(crossings.len(), 15);
+        assert!((wavelength - 40.0).abs() < 1.0, "measured {wavelength}");
+    }
+
+    #[test]
+    fn test_pml_reduces_reflections() {
+        let reflective = interior_energy_after_reflection(0);
+        let absorbing = interior_energy_after_reflection(16);
+        assert!(reflective > 0.0);
+        assert!(
+            absorbing < reflective * 0.05,
+            "reflected energy {absorbing} vs {reflective} without PML"
+        );
+    }
+
+    #[test]
+    fn test_partial_reflector_splits_a_pulse() {
+        // Energy either side of a 5-cell wall at x = 80 after a tone burst
+        // from the left.
+        fn split(transmission: f32) -> (f32, f32) {
+            let mut field = WaveField::new(160, 120);
+            for y in 0..120 {
+                for x in 80..85 {
+                    let idx = field.idx(x, y);
+                    field.transmission[idx] = transmission;
+                }
+            }
+            let source = field.idx(50, 60);
+            for n in 0..170 {
+                if n < 60 {
+                    let envelope = (PI * n as f32 / 60.0).sin().powi(2);
+                    field.current[source] = envelope * (0.17 * n as f32).sin();
+                }
+                field.step_serial(0.16, 1.0, 0, &Absorbing);
+            }
+            let energy = |columns: std::ops::Range<usize>| {
+                let cells = (0..120).flat_map(|y| columns.clone().map(move |x| (x, y)));
+                cells.map(|(x, y)| field.current[field.idx(x, y)].powi(2)).sum::<f32>()
+            };
+            (energy(0..80), energy(85..160))
+        }
+
+        // A 50% reflector sends back about half the energy and lets some through.
+        let (open_left, open_right) = split(1.0);
+        let (half_left, half_right) = split(0.5f32.powf(1.0 / 10.0));
+        let reflected = (half_left - open_left) / open_right;
+        assert!((reflected - 0.5).abs() < 0.15, "reflected {reflected}");
+        assert!(half_right > 0.1 * open_right && half_right < 0.5 * open_right);
+    }
+
+    #[test]
+    fn test_parallel_step_matches_serial() {
+        let (width, height) = (37, 23);
+        let mut parallel = WaveField::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let idx = parallel.idx(x, y);
+                parallel.current[idx] = ((x * 7 + y * 3) as f32 * 0.37).sin();
+                parallel.previous[idx] = ((x * 5 + y * 11) as f32 * 0.21).cos() * 0.5;
+                parallel.obstacle_map[idx] = if (x + y) % 9 == 0 { 0.0 } else if x > width / 2 { 0.7 } else { 1.0 };
+            }
+        }
+        let mut serial = WaveField::new(width, height);
+        serial.current.clone_from(&parallel.current);
+        serial.previous.clone_from(&parallel.previous);
+        serial.obstacle_map.clone_from(&parallel.obstacle_map);
+
+        for _ in 0..50 {
+            parallel.step(0.16, 0.999, 6, &Absorbing);
+            serial.step_serial(0.16, 0.999, 6, &Absorbing);
+        }
+
+        let bits = |values: &[f32]| values.iter().map(|v| v.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&parallel.current), bits(&serial.current));
+        assert_eq!(bits(&parallel.previous), bits(&serial.previous));
+    }
+
+    #[test]
+    fn test_spectrogram_keeps_only_recent_rows() {
+        let mut spectrogram = Spectrogram::default();
+        for i in 0..SPECTROGRAM_ROWS + 5 {
+            spectrogram.push(vec![i as f32], i as f32);
+        }
+        assert_eq!(spectrogram.rows.len(), SPECTROGRAM_ROWS);
+        assert_eq!(spectrogram.rows.front(), Some(&vec![5.0]));
+        assert_eq!(spectrogram.peak(), (SPECTROGRAM_ROWS + 4) as f32);
+
+        spectrogram.reset(None);
+        assert!(spectrogram.rows.is_empty());
+    }
+}
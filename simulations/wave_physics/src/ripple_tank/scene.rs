@@ -0,0 +1,469 @@
+//! Scene save/load as JSON
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{components::*, file_io::*, resources::*, spawn::*};
+
+pub const SCENE_FILE_NAME: &str = "ripple_tank_scene.json";
+const SCENE_VERSION: u32 = 1;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Scene File Format
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneFile {
+    pub version: u32,
+    pub objects: Vec<SavedObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedObject {
+    pub position: [f32; 3],
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<u32>,
+    #[serde(flatten)]
+    pub kind: SavedKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SavedKind {
+    Source(SavedSource),
+    Obstacle(SavedObstacle),
+    Probe(SavedProbe),
+    Ruler(SavedRuler),
+    PolyProbe(SavedPolyProbe),
+    ImageMask(SavedImageMask),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSource {
+    pub source_type: WaveSourceType,
+    pub frequency: f32,
+    pub amplitude: f32,
+    pub phase: f32,
+    pub enabled: bool,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub solo: bool,
+    pub waveform: Waveform,
+    #[serde(default)]
+    pub apodization: Apodization,
+    #[serde(default)]
+    pub steering_angle: f32,
+    /// Sweeps start over from their first frequency when loaded.
+    #[serde(default)]
+    pub sweep: Option<FrequencySweep>,
+    pub motion: Option<SavedMotion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMotion {
+    pub velocity: [f32; 2],
+    pub path: MovementPath,
+    #[serde(default)]
+    pub center: [f32; 2],
+    #[serde(default)]
+    pub radius: f32,
+    #[serde(default)]
+    pub angular_speed: f32,
+    #[serde(default)]
+    pub angle: f32,
+    #[serde(default)]
+    pub waypoints: Vec<[f32; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedObstacle {
+    pub obstacle_type: ObstacleType,
+    pub width: f32,
+    pub height: f32,
+    pub rotation: f32,
+    pub slit_width: f32,
+    pub slit_separation: f32,
+    pub refractive_index: f32,
+    #[serde(default)]
+    pub dispersion: f32,
+    #[serde(default = "hard_wall")]
+    pub reflectivity: f32,
+    #[serde(default)]
+    pub index_gradient: f32,
+}
+
+fn hard_wall() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedProbe {
+    pub label: String,
+    pub color: [f32; 4],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedRuler {
+    pub start: [f32; 2],
+    pub end: [f32; 2],
+    #[serde(default)]
+    pub snap_angle: bool,
+    /// The ruler is a fringe [`Screen`].
+    #[serde(default)]
+    pub screen: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPolyProbe {
+    pub points: Vec<[f32; 2]>,
+}
+
+/// An image mask, one string per pixel row from the top, `#` for a wall
+/// and `.` for an opening.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedImageMask {
+    pub width: f32,
+    pub rows: Vec<String>,
+}
+
+impl SavedImageMask {
+    fn from_mask(mask: &ImageMaskObstacle) -> Self {
+        let rows = mask
+            .walls
+            .chunks(mask.columns.max(1))
+            .map(|row| row.iter().map(|&wall| if wall { '#' } else { '.' }).collect())
+            .collect();
+        Self { width: mask.width, rows }
+    }
+
+    fn to_mask(&self) -> ImageMaskObstacle {
+        let columns = self.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let walls = self
+            .rows
+            .iter()
+            .flat_map(|row| row.chars().map(|cell| cell == '#').chain(std::iter::repeat(false)).take(columns))
+            .collect();
+        ImageMaskObstacle { columns, rows: self.rows.len(), walls, width: self.width }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Capture & Restore
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Components making up a saveable scene object.
+pub type SceneObjectData<'a> = (
+    &'a Transform,
+    &'a SceneObject,
+    Option<&'a SceneGroup>,
+    Option<&'a WaveSource>,
+    Option<&'a MovingSource>,
+    Option<&'a Obstacle>,
+    Option<&'a Probe>,
+    Option<&'a Ruler>,
+    Option<&'a Screen>,
+    Option<&'a PolyProbe>,
+    Option<&'a ImageMaskObstacle>,
+);
+
+/// Captures scene objects in creation order.
+pub fn capture_scene<'a>(objects: impl Iterator<Item = SceneObjectData<'a>>) -> SceneFile {
+    let mut objects: Vec<_> = objects.filter_map(capture_object).collect();
+    objects.sort_by_key(|(id, _)| *id);
+
+    SceneFile {
+        version: SCENE_VERSION,
+        objects: objects.into_iter().map(|(_, object)| object).collect(),
+    }
+}
+
+/// Captures one scene object along with its id, or `None` for entities
+/// that aren't saved.
+pub fn capture_object(
+    (transform, object, group, source, motion, obstacle, probe, ruler, screen, poly_probe, image_mask): SceneObjectData,
+) -> Option<(u32, SavedObject)> {
+    let kind = if let Some(source) = source {
+        SavedKind::Source(SavedSource {
+            source_type: source.source_type,
+            frequency: source.frequency,
+            amplitude: source.amplitude,
+            phase: source.phase,
+            enabled: source.enabled,
+            muted: source.muted,
+            solo: source.solo,
+            waveform: source.waveform,
+            apodization: source.apodization,
+            steering_angle: source.steering_angle,
+            sweep: source.sweep,
+            motion: motion.map(|motion| SavedMotion {
+                velocity: motion.velocity.to_array(),
+                path: motion.path,
+                center: motion.center.to_array(),
+                radius: motion.radius,
+                angular_speed: motion.angular_speed,
+                angle: motion.angle,
+                waypoints: motion.waypoints.iter().map(|waypoint| waypoint.to_array()).collect(),
+            }),
+        })
+    } else if let Some(obstacle) = obstacle {
+        SavedKind::Obstacle(SavedObstacle {
+            obstacle_type: obstacle.obstacle_type,
+            width: obstacle.width,
+            height: obstacle.height,
+            rotation: obstacle.rotation,
+            slit_width: obstacle.slit_width,
+            slit_separation: obstacle.slit_separation,
+            refractive_index: obstacle.refractive_index,
+            dispersion: obstacle.dispersion,
+            reflectivity: obstacle.reflectivity,
+            index_gradient: obstacle.index_gradient,
+        })
+    } else if let Some(probe) = probe {
+        SavedKind::Probe(SavedProbe {
+            label: probe.label.clone(),
+            color: probe.color.to_srgba().to_f32_array(),
+        })
+    } else if let Some(poly_probe) = poly_probe {
+        SavedKind::PolyProbe(SavedPolyProbe {
+            points: poly_probe.points.iter().map(|point| point.to_array()).collect(),
+        })
+    } else if let Some(mask) = image_mask {
+        SavedKind::ImageMask(SavedImageMask::from_mask(mask))
+    } else {
+        let ruler = ruler?;
+        SavedKind::Ruler(SavedRuler {
+            start: ruler.start.to_array(),
+            end: ruler.end.to_array(),
+            snap_angle: ruler.snap_angle,
+            screen: screen.is_some(),
+        })
+    };
+    Some((
+        object.id,
+        SavedObject {
+            position: transform.translation.to_array(),
+            locked: object.locked,
+            group: group.map(|group| group.0),
+            kind,
+        },
+    ))
+}
+
+/// Spawns every object in a scene with the regular spawn helpers, then
+/// overrides their defaults with the saved parameters.
+pub fn spawn_scene(commands: &mut Commands, object_id: &mut ObjectIdCounter, scene: &SceneFile) {
+    for object in &scene.objects {
+        spawn_object(commands, object_id, object);
+    }
+}
+
+/// Spawns one saved object under the next free id.
+pub fn spawn_object(commands: &mut Commands, object_id: &mut ObjectIdCounter, object: &SavedObject) -> Entity {
+    let pos = Vec2::new(object.position[0], object.position[1]);
+    let entity = match &object.kind {
+        SavedKind::Source(saved) => {
+            let entity = match saved.source_type {
+                WaveSourceType::Point => spawn_point_source(commands, object_id, pos),
+                WaveSourceType::Line => spawn_line_source(commands, object_id, pos),
+                WaveSourceType::PhasedArray { .. } => spawn_phased_array(commands, object_id, pos),
+                WaveSourceType::Moving => spawn_moving_source(commands, object_id, pos),
+            };
+            commands.entity(entity).insert(WaveSource {
+                source_type: saved.source_type,
+                frequency: saved.frequency,
+                amplitude: saved.amplitude,
+                phase: saved.phase,
+                enabled: saved.enabled,
+                muted: saved.muted,
+                solo: saved.solo,
+                waveform: saved.waveform,
+                apodization: saved.apodization,
+                steering_angle: saved.steering_angle,
+                sweep: saved.sweep,
+                ..default()
+            });
+            match &saved.motion {
+                Some(motion) => commands.entity(entity).insert(MovingSource {
+                    velocity: Vec2::from_array(motion.velocity),
+                    path: motion.path,
+                    center: Vec2::from_array(motion.center),
+                    radius: motion.radius,
+                    angular_speed: motion.angular_speed,
+                    angle: motion.angle,
+                    waypoints: motion.waypoints.iter().copied().map(Vec2::from_array).collect(),
+                    progress: 0.0,
+                }),
+                None => commands.entity(entity).remove::<MovingSource>(),
+            };
+            entity
+        }
+        SavedKind::Obstacle(saved) => {
+            let entity = match saved.obstacle_type {
+                ObstacleType::Reflector => spawn_reflector(commands, object_id, pos),
+                ObstacleType::SingleSlit => spawn_single_slit(commands, object_id, pos),
+                ObstacleType::DoubleSlit => spawn_double_slit(commands, object_id, pos),
+                ObstacleType::RefractionBlock => spawn_refraction_block(commands, object_id, pos),
+                ObstacleType::GradedIndex => spawn_graded_index_lens(commands, object_id, pos),
+            };
+            commands.entity(entity).insert(Obstacle {
+                obstacle_type: saved.obstacle_type,
+                width: saved.width,
+                height: saved.height,
+                rotation: saved.rotation,
+                slit_width: saved.slit_width,
+                slit_separation: saved.slit_separation,
+                refractive_index: saved.refractive_index,
+                dispersion: saved.dispersion,
+                reflectivity: saved.reflectivity,
+                index_gradient: saved.index_gradient,
+            });
+            entity
+        }
+        SavedKind::Probe(saved) => {
+            let entity = spawn_probe(commands, object_id, pos);
+            let color = Color::srgba(saved.color[0], saved.color[1], saved.color[2], saved.color[3]);
+            commands.entity(entity).insert((
+                Probe::new(&saved.label, color),
+                Sprite { color, custom_size: Some(Vec2::splat(10.0)), ..default() },
+            ));
+            entity
+        }
+        SavedKind::Ruler(saved) => {
+            let entity = if saved.screen {
+                spawn_screen(commands, object_id, pos)
+            } else {
+                spawn_ruler(commands, object_id, pos)
+            };
+            commands.entity(entity).insert(Ruler {
+                start: Vec2::from_array(saved.start),
+                end: Vec2::from_array(saved.end),
+                snap_angle: saved.snap_angle,
+            });
+            entity
+        }
+        SavedKind::ImageMask(saved) => spawn_image_mask(commands, object_id, pos, saved.to_mask()),
+        SavedKind::PolyProbe(saved) => {
+            let entity = spawn_poly_probe(commands, object_id, pos);
+            commands.entity(entity).insert(PolyProbe {
+                points: saved.points.iter().copied().map(Vec2::from_array).collect(),
+                ..default()
+            });
+            entity
+        }
+    };
+
+    commands.entity(entity).insert((
+        Transform::from_translation(Vec3::from_array(object.position)),
+        SceneObject { id: object_id.0, selected: false, locked: object.locked },
+    ));
+    if let Some(group) = object.group {
+        commands.entity(entity).insert(SceneGroup(group));
+    }
+    entity
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Save & Load
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Scene file contents waiting to be applied.
+#[derive(Resource, Default)]
+pub struct PendingSceneLoad(pub PendingFile);
+
+pub fn save_scene(scene: &SceneFile) {
+    match serde_json::to_string_pretty(scene) {
+        Ok(json) => save_file(SCENE_FILE_NAME, "application/json", json.as_bytes()),
+        Err(err) => error!("Failed to serialize scene: {}", err),
+    }
+}
+
+pub fn request_scene_load(pending: &PendingSceneLoad) {
+    open_file(SCENE_FILE_NAME, ".json,application/json", &pending.0);
+}
+
+/// Replaces the current scene once a loaded file arrives.
+pub fn apply_loaded_scene(
+    mut commands: Commands,
+    pending: Res<PendingSceneLoad>,
+    mut object_id: ResMut<ObjectIdCounter>,
+    mut ui_state: ResMut<UIState>,
+    mut wave_field: ResMut<WaveField>,
+    existing: Query<Entity, With<SceneObject>>,
+) {
+    let Some(bytes) = pending.0.take() else { return };
+    let scene: SceneFile = match serde_json::from_slice(&bytes) {
+        Ok(scene) => scene,
+        Err(err) => {
+            error!("Failed to parse scene file: {}", err);
+            return;
+        }
+    };
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    ui_state.select(None);
+    ui_state.dragging = None;
+    wave_field.clear();
+
+    spawn_scene(&mut commands, &mut object_id, &scene);
+    info!("Loaded scene with {} objects", scene.objects.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(world: &mut World) -> SceneFile {
+        let mut query = world.query::<SceneObjectData>();
+        capture_scene(query.iter(world))
+    }
+
+    #[test]
+    fn test_double_slit_and_probes_round_trip() {
+        let mut world = World::new();
+        let mut object_id = ObjectIdCounter::default();
+
+        let mut commands = world.commands();
+        let slit = spawn_double_slit(&mut commands, &mut object_id, Vec2::new(10.0, -20.0));
+        let upper = spawn_probe(&mut commands, &mut object_id, Vec2::new(120.0, 40.0));
+        let lower = spawn_probe(&mut commands, &mut object_id, Vec2::new(120.0, -40.0));
+        commands.entity(upper).insert(SceneGroup(1));
+        commands.entity(lower).insert(SceneGroup(1));
+        let mask = ImageMaskObstacle { columns: 3, rows: 2, walls: vec![true, false, true, false, true, false], width: 90.0 };
+        spawn_image_mask(&mut commands, &mut object_id, Vec2::new(-80.0, 0.0), mask);
+        world.flush();
+        world.get_mut::<SceneObject>(slit).expect("slit is a scene object").locked = true;
+
+        // Move away from the helper defaults so they can't mask a lost field.
+        let mut obstacle = world.get_mut::<Obstacle>(slit).expect("slit has an obstacle");
+        obstacle.slit_width = 14.0;
+        obstacle.slit_separation = 42.0;
+        obstacle.width = 160.0;
+
+        let saved = capture(&mut world);
+        assert_eq!(saved.objects.len(), 4);
+        let SavedKind::ImageMask(mask) = &saved.objects[3].kind else { panic!("mask saved last") };
+        assert_eq!(mask.rows, ["#.#", ".#."]);
+
+        let json = serde_json::to_string(&saved).expect("scene serializes");
+        let loaded: SceneFile = serde_json::from_str(&json).expect("scene deserializes");
+        assert_eq!(loaded, saved);
+
+        let existing: Vec<Entity> = world
+            .query_filtered::<Entity, With<SceneObject>>()
+            .iter(&world)
+            .collect();
+        for entity in existing {
+            world.despawn(entity);
+        }
+
+        let mut commands = world.commands();
+        spawn_scene(&mut commands, &mut object_id, &loaded);
+        world.flush();
+
+        assert_eq!(capture(&mut world), saved);
+    }
+}
@@ -0,0 +1,69 @@
+//! PNG snapshot export of the wave field
+
+use bevy::prelude::*;
+use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+use super::{physics::write_wave_field_rgba, resources::*};
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Snapshot Export
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Renders the wave field to RGBA8 pixels at full grid resolution.
+pub fn render_snapshot(wave_field: &WaveField, scheme: ColorScheme) -> Vec<u8> {
+    let mut data = vec![0; wave_field.width * wave_field.height * 4];
+    write_wave_field_rgba(wave_field, scheme, &mut data);
+    data
+}
+
+/// Encodes the wave field as a PNG image.
+pub fn encode_snapshot_png(wave_field: &WaveField, scheme: ColorScheme) -> Result<Vec<u8>, image::ImageError> {
+    let data = render_snapshot(wave_field, scheme);
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(
+        &data,
+        wave_field.width as u32,
+        wave_field.height as u32,
+        ExtendedColorType::Rgba8,
+    )?;
+    Ok(png)
+}
+
+/// Saves the wave field to a timestamped PNG in the working directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_snapshot(wave_field: &WaveField, scheme: ColorScheme) {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    let path = format!("ripple_tank_{timestamp}.png");
+    let data = render_snapshot(wave_field, scheme);
+
+    match image::save_buffer(
+        &path,
+        &data,
+        wave_field.width as u32,
+        wave_field.height as u32,
+        ExtendedColorType::Rgba8,
+    ) {
+        Ok(()) => info!("Saved snapshot to {}", path),
+        Err(err) => error!("Failed to save snapshot: {}", err),
+    }
+}
+
+/// Offers the wave field as a PNG download in the browser.
+#[cfg(target_arch = "wasm32")]
+pub fn export_snapshot(wave_field: &WaveField, scheme: ColorScheme) {
+    let png = match encode_snapshot_png(wave_field, scheme) {
+        Ok(png) => png,
+        Err(err) => {
+            error!("Failed to encode snapshot: {}", err);
+            return;
+        }
+    };
+    let timestamp = web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| (performance.time_origin() + performance.now()) as u64)
+        .unwrap_or_default();
+    super::file_io::save_file(&format!("ripple_tank_{timestamp}.png"), "image/png", &png);
+}
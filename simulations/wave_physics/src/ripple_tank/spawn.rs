@@ -1,202 +1,357 @@
 //! Spawn helper functions for scene objects
+//!
+//! Each helper returns the spawned entity so callers can override the
+//! default components, e.g. when loading a saved scene.
 
 use bevy::prelude::*;
+use ez_core::registry::SimulationEntity;
 
-use super::{components::*, resources::*};
+use super::{components::*, resources::*, SIMULATION_ID};
 
-pub fn spawn_point_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_point_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(1.0, 0.3, 0.3),
-                custom_size: Some(Vec2::splat(12.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(1.0, 0.3, 0.3),
+                    custom_size: Some(Vec2::splat(12.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        WaveSource { source_type: WaveSourceType::Point, ..default() },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            WaveSource { source_type: WaveSourceType::Point, ..default() },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_line_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_line_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(1.0, 0.5, 0.2),
-                custom_size: Some(Vec2::new(80.0, 8.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(1.0, 0.5, 0.2),
+                    custom_size: Some(Vec2::new(80.0, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        WaveSource { source_type: WaveSourceType::Line, ..default() },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            WaveSource { source_type: WaveSourceType::Line, ..default() },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_phased_array(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_phased_array(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.8, 0.2, 0.8),
-                custom_size: Some(Vec2::new(60.0, 12.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.8, 0.2, 0.8),
+                    custom_size: Some(Vec2::new(60.0, 12.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        WaveSource { source_type: WaveSourceType::PhasedArray { count: 5 }, ..default() },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            WaveSource { source_type: WaveSourceType::PhasedArray { count: 5 }, ..default() },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_moving_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_moving_source(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.2, 0.8, 0.4),
-                custom_size: Some(Vec2::new(16.0, 10.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.2, 0.8, 0.4),
+                    custom_size: Some(Vec2::new(16.0, 10.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        WaveSource { source_type: WaveSourceType::Moving, ..default() },
-        MovingSource { velocity: Vec2::new(50.0, 0.0), path: MovementPath::Linear },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            WaveSource { source_type: WaveSourceType::Moving, ..default() },
+            MovingSource::default(),
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_reflector(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_reflector(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.4, 0.4, 0.5),
-                custom_size: Some(Vec2::new(80.0, 8.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.4, 0.4, 0.5),
+                    custom_size: Some(Vec2::new(80.0, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        Obstacle { obstacle_type: ObstacleType::Reflector, width: 80.0, height: 8.0, ..default() },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            Obstacle { obstacle_type: ObstacleType::Reflector, width: 80.0, height: 8.0, ..default() },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_single_slit(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_single_slit(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.3, 0.3, 0.6),
-                custom_size: Some(Vec2::new(120.0, 8.0)),
-                ..default()
-            },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        Obstacle {
-            obstacle_type: ObstacleType::SingleSlit,
-            width: 120.0,
-            height: 8.0,
-            slit_width: 15.0,
-            ..default()
-        },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
-}
-
-pub fn spawn_double_slit(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.3, 0.3, 0.6),
+                    custom_size: Some(Vec2::new(120.0, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
+                ..default()
+            },
+            Obstacle {
+                obstacle_type: ObstacleType::SingleSlit,
+                width: 120.0,
+                height: 8.0,
+                slit_width: 15.0,
+                ..default()
+            },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+pub fn spawn_double_slit(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgb(0.3, 0.5, 0.6),
-                custom_size: Some(Vec2::new(120.0, 8.0)),
-                ..default()
-            },
-            transform: Transform::from_xyz(pos.x, pos.y, 1.0),
-            ..default()
-        },
-        Obstacle {
-            obstacle_type: ObstacleType::DoubleSlit,
-            width: 120.0,
-            height: 8.0,
-            slit_width: 10.0,
-            slit_separation: 30.0,
-            ..default()
-        },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
-}
-
-pub fn spawn_refraction_block(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.3, 0.5, 0.6),
+                    custom_size: Some(Vec2::new(120.0, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
+                ..default()
+            },
+            Obstacle {
+                obstacle_type: ObstacleType::DoubleSlit,
+                width: 120.0,
+                height: 8.0,
+                slit_width: 10.0,
+                slit_separation: 30.0,
+                ..default()
+            },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+pub fn spawn_refraction_block(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgba(0.3, 0.6, 0.8, 0.5),
-                custom_size: Some(Vec2::new(60.0, 60.0)),
-                ..default()
-            },
-            transform: Transform::from_xyz(pos.x, pos.y, 0.5),
-            ..default()
-        },
-        Obstacle {
-            obstacle_type: ObstacleType::RefractionBlock,
-            width: 60.0,
-            height: 60.0,
-            refractive_index: 1.5,
-            ..default()
-        },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
-}
-
-pub fn spawn_probe(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.3, 0.6, 0.8, 0.5),
+                    custom_size: Some(Vec2::new(60.0, 60.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 0.5),
+                ..default()
+            },
+            Obstacle {
+                obstacle_type: ObstacleType::RefractionBlock,
+                width: 60.0,
+                height: 60.0,
+                refractive_index: 1.5,
+                ..default()
+            },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+pub fn spawn_graded_index_lens(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    let color = if object_id.0 % 2 == 0 {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.5, 0.4, 0.9, 0.5),
+                    custom_size: Some(Vec2::new(120.0, 30.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 0.5),
+                ..default()
+            },
+            Obstacle {
+                obstacle_type: ObstacleType::GradedIndex,
+                width: 120.0,
+                height: 30.0,
+                refractive_index: 1.8,
+                index_gradient: 0.6,
+                ..default()
+            },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+/// Spreadsheet-style column name for a 1-based index: A…Z, AA…AZ, BA…
+pub fn column_label(index: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = index.max(1);
+    while n > 0 {
+        n -= 1;
+        letters.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    letters.iter().rev().map(|&letter| letter as char).collect()
+}
+
+/// Spawns an imported mask with a faint outline showing its extent; the
+/// walls themselves show up in the field once rasterized.
+pub fn spawn_image_mask(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2, mask: ImageMaskObstacle) -> Entity {
+    object_id.0 += 1;
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.4, 0.4, 0.5, 0.15),
+                    custom_size: Some(Vec2::new(mask.width, mask.height())),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 1.0),
+                ..default()
+            },
+            mask,
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+pub fn spawn_probe(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
+    object_id.0 += 1;
+    let color = if object_id.0.is_multiple_of(2) {
         Color::srgb(0.2, 0.6, 1.0)
     } else {
         Color::srgb(1.0, 0.4, 0.4)
     };
-    let label = format!("Probe {}", (object_id.0 as u8 + b'A' - 1) as char);
+    let label = format!("Probe {}", column_label(object_id.0));
 
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color,
-                custom_size: Some(Vec2::splat(10.0)),
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(10.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 2.0),
                 ..default()
             },
-            transform: Transform::from_xyz(pos.x, pos.y, 2.0),
-            ..default()
-        },
-        Probe::new(&label, color),
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+            Probe::new(&label, color),
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
 }
 
-pub fn spawn_ruler(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) {
+pub fn spawn_ruler(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
     object_id.0 += 1;
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::srgba(1.0, 1.0, 0.3, 0.8),
-                custom_size: Some(Vec2::new(100.0, 5.0)),
-                ..default()
-            },
-            transform: Transform::from_xyz(pos.x, pos.y, 2.0),
-            ..default()
-        },
-        Ruler { start: Vec2::new(-50.0, 0.0), end: Vec2::new(50.0, 0.0) },
-        SceneObject { id: object_id.0, selected: false, locked: false },
-    ));
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 1.0, 0.3, 0.8),
+                    custom_size: Some(Vec2::new(100.0, 5.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 2.0),
+                ..default()
+            },
+            Ruler { start: Vec2::new(-50.0, 0.0), end: Vec2::new(50.0, 0.0), snap_angle: false },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+/// Spawns a [`PolyProbe`] with its first vertex at `pos`; further clicks
+/// add the rest.
+pub fn spawn_poly_probe(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
+    object_id.0 += 1;
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(1.0, 0.4, 1.0),
+                    custom_size: Some(Vec2::splat(8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 2.0),
+                ..default()
+            },
+            PolyProbe { points: vec![Vec2::ZERO], ..default() },
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+/// Spawns a vertical detector screen, a [`Ruler`] that also records
+/// intensity, long enough to catch several double-slit fringes.
+pub fn spawn_screen(commands: &mut Commands, object_id: &mut ObjectIdCounter, pos: Vec2) -> Entity {
+    object_id.0 += 1;
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.9, 0.9, 1.0, 0.8),
+                    custom_size: Some(Vec2::new(300.0, 5.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(pos.x, pos.y, 2.0),
+                ..default()
+            },
+            Ruler { start: Vec2::new(0.0, -150.0), end: Vec2::new(0.0, 150.0), snap_angle: false },
+            Screen::default(),
+            SceneObject { id: object_id.0, selected: false, locked: false },
+            SimulationEntity(SIMULATION_ID),
+        ))
+        .id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_probe_labels_stay_distinct_past_z() {
+        let labels: Vec<String> = (1..=60).map(column_label).collect();
+        assert_eq!(labels.iter().collect::<HashSet<_>>().len(), 60);
+        assert!(labels.iter().all(|label| label.chars().all(|c| c.is_ascii_uppercase())));
+        assert_eq!((labels[0].as_str(), labels[25].as_str(), labels[26].as_str()), ("A", "Z", "AA"));
+        assert_eq!(labels[52], "BA");
+    }
 }
@@ -2,19 +2,37 @@
 
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
+use ez_core::math::magnitude_spectrum;
+use ez_core::parameters::ParameterValue;
+use ez_core::prelude::Simulation;
+use ez_core::quality::AdaptiveQuality;
+use ez_core::recording::DataRecorder;
+use ez_core::units::format_with_unit;
+use ez_renderer::materials::inferno;
+use ez_ui::panels::{render_parameter_panel, SimulationParameters};
+use ez_ui::widgets::{adaptive_quality_controls, data_recorder_panel, line_plot, Series};
+use ez_ui::shortcuts::{shortcuts_overlay, ShortcutsOverlay};
 use std::f32::consts::PI;
 
-use super::{components::*, resources::*};
+use super::{components::*, field_npy::export_field_npy, fraunhofer::*, history::FieldHistory, image_mask::*, probe_csv::{export_probe_csv, export_recording_csv}, resources::*, scene::*, undo::*};
 
 // ══════════════════════════════════════════════════════════════════════════════
 // Top Bar
 // ══════════════════════════════════════════════════════════════════════════════
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_top_bar_ui(
     mut contexts: EguiContexts,
     mut config: ResMut<RippleTankConfig>,
+    mut ui_state: ResMut<UIState>,
     mut wave_field: ResMut<WaveField>,
     stats: Res<SimulationStats>,
+    pending_scene: Res<PendingSceneLoad>,
+    pending_mask: Res<PendingMaskImport>,
+    scene_objects: Query<SceneObjectData>,
+    mut shortcuts: ResMut<ShortcutsOverlay>,
+    mut quality: ResMut<AdaptiveQuality>,
+    mut undo: ResMut<UndoHistory>,
 ) {
     egui::TopBottomPanel::top("top_bar").show(contexts.ctx_mut(), |ui| {
         ui.horizontal(|ui| {
@@ -24,6 +42,15 @@ pub fn render_top_bar_ui(
             if ui.button(if config.paused { "▶ Play" } else { "⏸ Pause" }).clicked() {
                 config.paused = !config.paused;
             }
+            let step_back = ui
+                .add_enabled(config.paused && config.is_time_reversible(), egui::Button::new("⏮ Step Back"))
+                .on_hover_text("Run the wave equation backward by one step")
+                .on_disabled_hover_text(
+                    "Pause first; needs damping 1.0 and no absorbing border, since losses can't be undone",
+                );
+            if step_back.clicked() {
+                ui_state.step_back_requested = true;
+            }
 
             ui.label("Speed:");
             ui.add(egui::Slider::new(&mut config.time_scale, 0.1..=2.0).show_value(false));
@@ -32,11 +59,59 @@ pub fn render_top_bar_ui(
                 wave_field.clear();
             }
 
+            if ui.button("📷 Snapshot").on_hover_text("Save the wave field as PNG (P)").clicked() {
+                super::snapshot::export_snapshot(&wave_field, config.color_scheme);
+            }
+            if ui.button("🔢 Export Field (.npy)").on_hover_text("Raw field values for numpy.load, shape (height, width)").clicked() {
+                export_field_npy(&wave_field);
+            }
+
+            ui.separator();
+            if ui.button("💾 Save Scene").clicked() {
+                save_scene(&capture_scene(scene_objects.iter()));
+            }
+            if ui.button("📂 Load Scene").clicked() {
+                request_scene_load(&pending_scene);
+            }
+            if ui
+                .button("🖼 Import Mask")
+                .on_hover_text("Turn the dark pixels of a PNG into walls, for apertures of any shape")
+                .clicked()
+            {
+                request_mask_import(&pending_mask);
+            }
+            if ui.add_enabled(!undo.undo.is_empty(), egui::Button::new("↶")).on_hover_text("Undo (Ctrl+Z)").clicked() {
+                undo.request = Some(UndoRequest::Undo);
+            }
+            if ui.add_enabled(!undo.redo.is_empty(), egui::Button::new("↷")).on_hover_text("Redo (Ctrl+Shift+Z)").clicked() {
+                undo.request = Some(UndoRequest::Redo);
+            }
+
             ui.separator();
             ui.label(format!("FPS: {:.0}", stats.fps));
+            ui.menu_button("⚡", |ui| adaptive_quality_controls(ui, &mut quality))
+                .response
+                .on_hover_text("Adaptive quality");
             ui.label(format!("t = {:.2}s", stats.simulation_time));
+            if stats.solver_unstable {
+                ui.colored_label(egui::Color32::from_rgb(255, 90, 90), "⚠ Solver unstable").on_hover_text(format!(
+                    "{} cells hit the amplitude clamp (CFL number {:.2}, stable below {:.2}).\n\
+                     Reduce the wave speed or raise any refractive index below 1, then Clear Waves.",
+                    wave_field.clamped_cells, stats.courant, super::CFL_LIMIT
+                ));
+            }
+
+            ui.separator();
+            if ui.selectable_label(ui_state.show_minimap, "🗺").on_hover_text("Minimap (M)").clicked() {
+                ui_state.show_minimap = !ui_state.show_minimap;
+            }
+            if ui.selectable_label(shortcuts.open, "⌨").on_hover_text("Keyboard shortcuts (F1)").clicked() {
+                shortcuts.open = !shortcuts.open;
+            }
         });
     });
+
+    shortcuts_overlay(contexts.ctx_mut(), shortcuts.open, super::SHORTCUTS);
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -83,6 +158,9 @@ pub fn render_toolbox_ui(mut contexts: EguiContexts, mut ui_state: ResMut<UIStat
         if ui.selectable_label(ui_state.selected_tool == ToolType::RefractionBlock, "  ▢ Refraction Block").clicked() {
             ui_state.selected_tool = ToolType::RefractionBlock;
         }
+        if ui.selectable_label(ui_state.selected_tool == ToolType::GradedIndexLens, "  ◐ GRIN Lens").clicked() {
+            ui_state.selected_tool = ToolType::GradedIndexLens;
+        }
 
         ui.separator();
         ui.label("📏 Measurement");
@@ -93,6 +171,16 @@ pub fn render_toolbox_ui(mut contexts: EguiContexts, mut ui_state: ResMut<UIStat
         if ui.selectable_label(ui_state.selected_tool == ToolType::Ruler, "  📐 Ruler").clicked() {
             ui_state.selected_tool = ToolType::Ruler;
         }
+        if ui
+            .selectable_label(ui_state.selected_tool == ToolType::PolyProbe, "  〰 Poly Probe")
+            .on_hover_text("Click to add vertices, right-click to finish")
+            .clicked()
+        {
+            ui_state.selected_tool = ToolType::PolyProbe;
+        }
+        if ui.selectable_label(ui_state.selected_tool == ToolType::Screen, "  ▮ Fringe Screen").clicked() {
+            ui_state.selected_tool = ToolType::Screen;
+        }
 
         ui.separator();
         ui.small("Click viewport to place");
@@ -103,29 +191,134 @@ pub fn render_toolbox_ui(mut contexts: EguiContexts, mut ui_state: ResMut<UIStat
 // Inspector (Right Panel)
 // ══════════════════════════════════════════════════════════════════════════════
 
+fn color_scheme_combo(ui: &mut egui::Ui, id: &str, scheme: &mut ColorScheme) {
+    egui::ComboBox::from_id_source(id).selected_text(format!("{:?}", scheme)).show_ui(ui, |ui| {
+        ui.selectable_value(scheme, ColorScheme::DeepOcean, "Deep Ocean");
+        ui.selectable_value(scheme, ColorScheme::Scientific, "Scientific");
+        ui.selectable_value(scheme, ColorScheme::PhaseColor, "Phase Color");
+        ui.selectable_value(scheme, ColorScheme::Grayscale, "Grayscale");
+        ui.selectable_value(scheme, ColorScheme::Intensity, "Intensity ⟨u²⟩");
+    });
+}
+
+/// Toggle and settings for a source's frequency sweep.
+fn render_sweep_controls(ui: &mut egui::Ui, source: &mut WaveSource, time: f32) {
+    let mut sweeping = source.sweep.is_some();
+    if ui.checkbox(&mut sweeping, "Sweep Frequency").on_hover_text("Chirp between two frequencies over time").changed() {
+        source.sweep = sweeping.then(|| FrequencySweep { start: source.frequency, ..default() });
+    }
+    let Some(sweep) = source.sweep.as_mut() else { return };
+
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut sweep.mode, SweepMode::Linear, "Linear");
+        ui.selectable_value(&mut sweep.mode, SweepMode::Logarithmic, "Log")
+            .on_hover_text("Equal time per octave");
+        ui.checkbox(&mut sweep.looping, "Loop");
+    });
+    ui.add(egui::Slider::new(&mut sweep.start, 0.5..=10.0).text("From (Hz)"));
+    ui.add(egui::Slider::new(&mut sweep.end, 0.5..=10.0).text("To (Hz)"));
+    ui.add(egui::Slider::new(&mut sweep.duration, 1.0..=60.0).text("Duration (s)"));
+    ui.horizontal(|ui| {
+        ui.add(egui::ProgressBar::new(sweep.progress(time)).desired_width(100.0).text(format!("{:.2} Hz", source.frequency)));
+        if ui.small_button("↺").on_hover_text("Restart the sweep").clicked() {
+            sweep.started = None;
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render_inspector_ui(
     mut contexts: EguiContexts,
-    ui_state: Res<UIState>,
+    mut ui_state: ResMut<UIState>,
     mut config: ResMut<RippleTankConfig>,
+    stats: Res<SimulationStats>,
     mut sources: Query<(&SceneObject, &mut WaveSource)>,
     mut obstacles: Query<(&SceneObject, &mut Obstacle), Without<WaveSource>>,
     mut moving: Query<(&SceneObject, &mut MovingSource)>,
+    mut placements: Query<(&SceneObject, &mut Transform)>,
+    mut rulers: Query<(&SceneObject, &mut Ruler, Has<Screen>)>,
+    mut poly_probes: Query<(&SceneObject, &mut PolyProbe)>,
+    mut masks: Query<(&SceneObject, &mut ImageMaskObstacle)>,
+    groups: Query<(Entity, &SceneGroup)>,
+    mut preset_params: Local<SimulationParameters>,
     mut commands: Commands,
 ) {
     egui::SidePanel::right("inspector").default_width(super::INSPECTOR_PANEL_WIDTH).show(contexts.ctx_mut(), |ui| {
         ui.heading("🔧 Inspector");
         ui.separator();
 
+        // Phases are compared against the lowest-numbered enabled source.
+        let reference = sources
+            .iter()
+            .filter(|(_, source)| source.enabled)
+            .min_by_key(|(obj, _)| obj.id)
+            .map(|(obj, source)| (obj.id, source.phase));
+        let any_solo = sources.iter().any(|(_, source)| source.solo);
+        let mut sync_phases = false;
+        let mut shared_frequency = None;
+
+        if ui_state.selection.len() > 1 {
+            render_selection_controls(ui, &ui_state.selection, &placements, &groups, &mut commands);
+            ui.separator();
+        }
+
         if let Some(entity) = ui_state.selected_entity {
             if let Ok((obj, mut source)) = sources.get_mut(entity) {
                 ui.label(format!("Wave Source #{}", obj.id));
                 ui.separator();
+                render_placement_controls(ui, &mut placements, entity, &mut commands);
 
-                ui.checkbox(&mut source.enabled, "Enabled");
-                ui.add(egui::Slider::new(&mut source.frequency, 0.5..=10.0).text("Frequency (Hz)"));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut source.enabled, "Enabled");
+                    ui.toggle_value(&mut source.solo, "🎧 Solo")
+                        .on_hover_text("Hear only the soloed sources");
+                    ui.toggle_value(&mut source.muted, "🔇 Mute")
+                        .on_hover_text("Silence this source");
+                });
+                if source.enabled && !source.muted && any_solo && !source.solo {
+                    ui.small("Silent: another source is soloed");
+                }
+                let frequency_changed = ui
+                    .add_enabled(source.sweep.is_none(), egui::Slider::new(&mut source.frequency, 0.5..=10.0).text("Frequency (Hz)"))
+                    .on_disabled_hover_text("Set by the sweep")
+                    .changed();
+                render_sweep_controls(ui, &mut source, config.accumulated_time);
                 ui.add(egui::Slider::new(&mut source.amplitude, 0.1..=2.0).text("Amplitude"));
                 ui.add(egui::Slider::new(&mut source.phase, 0.0..=2.0 * PI).text("Phase (rad)"));
 
+                match reference {
+                    Some((reference_id, reference_phase)) if reference_id != obj.id => {
+                        let relative = (source.phase - reference_phase).rem_euclid(2.0 * PI);
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Δφ vs #{}: {:.2} rad", reference_id, relative));
+                            if ui.small_button("0").on_hover_text("In phase with the reference").clicked() {
+                                source.phase = reference_phase;
+                            }
+                            if ui.small_button("π").on_hover_text("Opposite phase: destructive on the center line").clicked() {
+                                source.phase = (reference_phase + PI).rem_euclid(2.0 * PI);
+                            }
+                        });
+                    }
+                    Some(_) => {
+                        ui.small("Phase reference for the other sources");
+                    }
+                    None => {}
+                }
+
+                ui.horizontal(|ui| {
+                    sync_phases = ui
+                        .button("🔗 Sync Phases")
+                        .on_hover_text("Set every source's phase to 0 on the shared clock")
+                        .clicked();
+                    let lock_changed = ui
+                        .checkbox(&mut config.lock_frequencies, "Lock Frequencies")
+                        .on_hover_text("Give every source this source's frequency")
+                        .changed();
+                    if config.lock_frequencies && (lock_changed || frequency_changed) {
+                        shared_frequency = Some(source.frequency);
+                    }
+                });
+
                 ui.horizontal(|ui| {
                     ui.label("Waveform:");
                     egui::ComboBox::from_id_source("waveform")
@@ -137,11 +330,110 @@ pub fn render_inspector_ui(
                         });
                 });
 
+                if matches!(source.source_type, WaveSourceType::Line | WaveSourceType::PhasedArray { .. }) {
+                    ui.horizontal(|ui| {
+                        ui.label("Apodization:");
+                        egui::ComboBox::from_id_source("apodization")
+                            .selected_text(source.apodization.label())
+                            .show_ui(ui, |ui| {
+                                for apodization in Apodization::ALL {
+                                    ui.selectable_value(&mut source.apodization, apodization, apodization.label());
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Taper the amplitude toward the ends for a cleaner beam with less edge diffraction");
+                }
+
+                if let WaveSourceType::PhasedArray { .. } = source.source_type {
+                    let mut degrees = source.steering_angle.to_degrees();
+                    if ui.add(egui::Slider::new(&mut degrees, -80.0..=80.0).text("Steering (°)")).changed() {
+                        source.steering_angle = degrees.to_radians();
+                    }
+                    if stats.sample_interval > 0.0 {
+                        let spacing = super::PHASED_ARRAY_SPACING * super::GRID_SCALE;
+                        let wave_speed = super::wave_speed_world(&config, stats.sample_interval);
+                        let step = super::steering_phase_step(spacing, source.frequency, wave_speed, source.steering_angle);
+                        ui.small(format!("Δφ per element: {:+.2} rad", step));
+                        let wavelength = wave_speed / source.frequency;
+                        if spacing > wavelength / 2.0 {
+                            ui.small(format!("d = {:.0} > λ/2 = {:.0}: expect grating lobes", spacing, wavelength / 2.0));
+                        }
+                    }
+                }
+
                 if let Ok((_, mut mov)) = moving.get_mut(entity) {
                     ui.separator();
                     ui.label("Movement");
-                    ui.add(egui::Slider::new(&mut mov.velocity.x, -100.0..=100.0).text("Vx"));
-                    ui.add(egui::Slider::new(&mut mov.velocity.y, -100.0..=100.0).text("Vy"));
+
+                    let position = placements.get(entity).map_or(Vec2::ZERO, |(_, transform)| transform.translation.truncate());
+                    let previous_path = mov.path;
+                    ui.horizontal(|ui| {
+                        ui.label("Path:");
+                        egui::ComboBox::from_id_source("movement_path")
+                            .selected_text(format!("{:?}", mov.path))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut mov.path, MovementPath::Linear, "Linear");
+                                ui.selectable_value(&mut mov.path, MovementPath::Circular, "Circular");
+                                ui.selectable_value(&mut mov.path, MovementPath::Custom, "Custom");
+                            });
+                    });
+                    if mov.path == MovementPath::Circular && previous_path != MovementPath::Circular {
+                        mov.center_orbit_on(position);
+                    }
+                    if mov.path != MovementPath::Custom {
+                        ui_state.placing_waypoints = false;
+                    }
+
+                    match mov.path {
+                        MovementPath::Linear => {
+                            ui.add(egui::Slider::new(&mut mov.velocity.x, -100.0..=100.0).text("Vx"));
+                            ui.add(egui::Slider::new(&mut mov.velocity.y, -100.0..=100.0).text("Vy"));
+                        }
+                        MovementPath::Circular => {
+                            ui.horizontal(|ui| {
+                                ui.label("Center:");
+                                ui.add(egui::DragValue::new(&mut mov.center.x).prefix("x: "));
+                                ui.add(egui::DragValue::new(&mut mov.center.y).prefix("y: "));
+                            });
+                            ui.add(egui::Slider::new(&mut mov.radius, 10.0..=300.0).text("Radius"));
+                            ui.add(egui::Slider::new(&mut mov.angular_speed, -5.0..=5.0).text("Angular Speed (rad/s)"));
+                            if ui.button("Orbit Around Current Position").clicked() {
+                                mov.center = position;
+                            }
+                        }
+                        MovementPath::Custom => {
+                            let mut speed = mov.velocity.length();
+                            if ui.add(egui::Slider::new(&mut speed, 0.0..=150.0).text("Speed")).changed() {
+                                mov.velocity = mov.velocity.try_normalize().unwrap_or(Vec2::X) * speed;
+                            }
+
+                            ui.label(format!("Waypoints: {}", mov.waypoints.len()));
+                            let mut removed = None;
+                            for (i, waypoint) in mov.waypoints.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{}.", i + 1));
+                                    ui.add(egui::DragValue::new(&mut waypoint.x).prefix("x: "));
+                                    ui.add(egui::DragValue::new(&mut waypoint.y).prefix("y: "));
+                                    if ui.small_button("✕").clicked() {
+                                        removed = Some(i);
+                                    }
+                                });
+                            }
+                            if let Some(i) = removed {
+                                mov.waypoints.remove(i);
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.toggle_value(&mut ui_state.placing_waypoints, "📍 Place Waypoints")
+                                    .on_hover_text("Click in the tank to add waypoints, right-click to stop");
+                                if ui.button("Clear").clicked() {
+                                    mov.waypoints.clear();
+                                    mov.progress = 0.0;
+                                }
+                            });
+                        }
+                    }
                 }
 
                 ui.separator();
@@ -151,31 +443,135 @@ pub fn render_inspector_ui(
             } else if let Ok((obj, mut obstacle)) = obstacles.get_mut(entity) {
                 ui.label(format!("Obstacle #{}", obj.id));
                 ui.separator();
+                render_placement_controls(ui, &mut placements, entity, &mut commands);
 
                 match obstacle.obstacle_type {
                     ObstacleType::Reflector => {
                         ui.label("Type: Reflector");
                         ui.add(egui::Slider::new(&mut obstacle.width, 10.0..=200.0).text("Width"));
+                        ui.add(egui::Slider::new(&mut obstacle.reflectivity, 0.0..=1.0).text("Reflectivity"))
+                            .on_hover_text("1 is a hard wall; lower values let part of the wave through, like a beam splitter");
                     }
                     ObstacleType::SingleSlit => {
                         ui.label("Type: Single Slit");
                         ui.add(egui::Slider::new(&mut obstacle.width, 50.0..=200.0).text("Width"));
                         ui.add(egui::Slider::new(&mut obstacle.slit_width, 5.0..=50.0).text("Slit Width"));
+                        ui.toggle_value(&mut ui_state.show_far_field, "🔭 Far Field")
+                            .on_hover_text("Compare the FFT of the aperture with the simulated pattern");
                     }
                     ObstacleType::DoubleSlit => {
                         ui.label("Type: Double Slit");
                         ui.add(egui::Slider::new(&mut obstacle.width, 50.0..=200.0).text("Width"));
                         ui.add(egui::Slider::new(&mut obstacle.slit_width, 5.0..=30.0).text("Slit Width"));
                         ui.add(egui::Slider::new(&mut obstacle.slit_separation, 10.0..=80.0).text("Separation"));
+                        ui.toggle_value(&mut ui_state.show_far_field, "🔭 Far Field")
+                            .on_hover_text("Compare the FFT of the aperture with the simulated pattern");
                     }
                     ObstacleType::RefractionBlock => {
                         ui.label("Type: Refraction Block");
                         ui.add(egui::Slider::new(&mut obstacle.width, 20.0..=150.0).text("Width"));
                         ui.add(egui::Slider::new(&mut obstacle.height, 20.0..=150.0).text("Height"));
                         ui.add(egui::Slider::new(&mut obstacle.refractive_index, 1.0..=3.0).text("Refractive Index"));
+                        ui.add(egui::Slider::new(&mut obstacle.dispersion, 0.0..=8.0).text("Dispersion k (Hz²)"))
+                            .on_hover_text("n(f) = n₀ + k/f²: lower frequencies slow down and bend more");
+                        if obstacle.dispersion > 0.0 {
+                            let mut enabled: Vec<_> = sources
+                                .iter()
+                                .filter(|(_, source)| source.enabled)
+                                .map(|(obj, source)| (obj.id, source.frequency))
+                                .collect();
+                            enabled.sort_by_key(|(id, _)| *id);
+                            for (id, frequency) in enabled {
+                                ui.small(format!(
+                                    "n at #{} ({:.1} Hz): {:.2}",
+                                    id,
+                                    frequency,
+                                    obstacle.refractive_index_at(frequency)
+                                ));
+                            }
+                            ui.small("Each source's waves cross the block at their own index");
+                        }
+                        ui.checkbox(&mut config.show_snell_overlay, "Show Snell's Law Ray")
+                            .on_hover_text("Predicted path of a ray from the nearest source (red: total internal reflection)");
+                    }
+                    ObstacleType::GradedIndex => {
+                        ui.label("Type: GRIN Lens");
+                        ui.add(egui::Slider::new(&mut obstacle.width, 40.0..=300.0).text("Width"));
+                        ui.add(egui::Slider::new(&mut obstacle.height, 10.0..=100.0).text("Thickness"));
+                        ui.add(egui::Slider::new(&mut obstacle.refractive_index, 1.0..=3.0).text("Index on Axis"));
+                        let max_gradient = (obstacle.refractive_index - 1.0).max(0.0);
+                        ui.add(egui::Slider::new(&mut obstacle.index_gradient, 0.0..=max_gradient).text("Gradient Δn"))
+                            .on_hover_text("How much the index drops from the axis to the edges: n = n₀ − Δn·(2x/w)²");
+                        match obstacle.graded_focal_length() {
+                            Some(focal_length) => ui.small(format!("Focal length ≈ {:.0} units", focal_length)),
+                            None => ui.small("No gradient: a flat slab"),
+                        };
                     }
                 }
 
+                let mut degrees = obstacle.rotation.to_degrees();
+                if ui.add(egui::Slider::new(&mut degrees, -180.0..=180.0).text("Rotation (°)")).changed() {
+                    obstacle.rotation = degrees.to_radians();
+                }
+
+                ui.separator();
+                if ui.button("🗑 Delete").clicked() {
+                    commands.entity(entity).despawn();
+                }
+            } else if let Ok((obj, mut ruler, is_screen)) = rulers.get_mut(entity) {
+                ui.label(format!("{} #{}", if is_screen { "Fringe Screen" } else { "Ruler" }, obj.id));
+                ui.separator();
+                render_placement_controls(ui, &mut placements, entity, &mut commands);
+
+                ui.label(format!("Length: {:.1} units", ruler.length()));
+                ui.label(format!("Angle: {:+.1}° from horizontal", ruler.angle_degrees()));
+                ui.checkbox(&mut ruler.snap_angle, format!("Shift snaps to {}°", super::RULER_SNAP_DEGREES))
+                    .on_hover_text("Hold Shift while dragging an end to lock the angle to fixed steps");
+
+                ui.separator();
+                if ui.button("🗑 Delete").clicked() {
+                    commands.entity(entity).despawn();
+                }
+            } else if let Ok((obj, mut mask)) = masks.get_mut(entity) {
+                ui.label(format!("Image Mask #{}", obj.id));
+                ui.separator();
+                render_placement_controls(ui, &mut placements, entity, &mut commands);
+
+                ui.label(format!("{} × {} pixels", mask.columns, mask.rows));
+                let mut width = mask.width;
+                if ui.add(egui::Slider::new(&mut width, 20.0..=1000.0).text("Width")).changed() {
+                    mask.width = width;
+                }
+                ui.small(format!("Height follows the image: {:.0} units", mask.height()));
+                if ui.button("◑ Invert").on_hover_text("Swap walls and openings").clicked() {
+                    mask.invert();
+                }
+
+                ui.separator();
+                if ui.button("🗑 Delete").clicked() {
+                    commands.entity(entity).despawn();
+                }
+            } else if let Ok((obj, mut probe)) = poly_probes.get_mut(entity) {
+                ui.label(format!("Poly Probe #{}", obj.id));
+                ui.separator();
+                render_placement_controls(ui, &mut placements, entity, &mut commands);
+
+                ui.label(format!("Vertices: {}", probe.points.len()));
+                ui.label(format!("Arc length: {:.1} units", probe.arc_length()));
+                ui.horizontal(|ui| {
+                    let mut placing = ui_state.placing_poly_probe == Some(entity);
+                    if ui
+                        .toggle_value(&mut placing, "📍 Add Vertices")
+                        .on_hover_text("Click in the tank to extend the path, right-click to stop")
+                        .changed()
+                    {
+                        ui_state.placing_poly_probe = placing.then_some(entity);
+                    }
+                    if ui.button("Clear").on_hover_text("Keep only the first vertex").clicked() {
+                        probe.points.truncate(1);
+                    }
+                });
+
                 ui.separator();
                 if ui.button("🗑 Delete").clicked() {
                     commands.entity(entity).despawn();
@@ -185,23 +581,102 @@ pub fn render_inspector_ui(
             ui.label("Global Settings");
             ui.separator();
 
+            render_presets(ui, &mut config, &mut preset_params);
+
             ui.add(egui::Slider::new(&mut config.wave_speed, 0.1..=5.0).text("Wave Speed"));
+            let cfl_color = if stats.courant > super::CFL_LIMIT { egui::Color32::from_rgb(255, 90, 90) } else { ui.visuals().text_color() };
+            ui.colored_label(cfl_color, format!("CFL number: {:.2} (stable below {:.2})", stats.courant, super::CFL_LIMIT))
+                .on_hover_text("Cells a wave crosses per solver substep in the fastest medium");
             ui.add(egui::Slider::new(&mut config.damping, 0.9..=1.0).text("Damping"));
+            ui.horizontal(|ui| {
+                ui.label("Boundary:");
+                let current = config.boundary_mode();
+                egui::ComboBox::from_id_source("boundary_mode")
+                    .selected_text(config.boundary.name())
+                    .show_ui(ui, |ui| {
+                        for mode in BoundaryMode::ALL {
+                            if ui.selectable_label(current == Some(mode), mode.label()).clicked() {
+                                config.boundary = mode.condition();
+                            }
+                        }
+                    });
+            });
+            match config.boundary_mode() {
+                Some(BoundaryMode::Absorbing) => {
+                    ui.add(egui::Slider::new(&mut config.pml_width, 0..=64).text("Absorbing Border"));
+                }
+                Some(BoundaryMode::Reflecting) if stats.sample_interval > 0.0 => {
+                    let wave_speed = super::wave_speed_world(&config, stats.sample_interval);
+                    ui.small("Resonant modes (m, n):");
+                    for (m, n) in [(1, 0), (0, 1), (1, 1), (2, 1)] {
+                        ui.small(format!("  ({}, {}): {:.2} Hz", m, n, super::tank_mode_frequency(m, n, wave_speed)));
+                    }
+                }
+                Some(BoundaryMode::Periodic) => {
+                    ui.small("Waves leaving one edge re-enter at the opposite one");
+                }
+                _ => {}
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Resolution:");
+                egui::ComboBox::from_id_source("grid_size")
+                    .selected_text(format!("{} cells", config.grid_size))
+                    .show_ui(ui, |ui| {
+                        for size in super::GRID_SIZE_OPTIONS {
+                            let width = size * super::GRID_WIDTH / super::GRID_HEIGHT;
+                            ui.selectable_value(&mut config.grid_size, size, format!("{}×{}", width, size));
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Finer grids resolve short wavelengths but run slower; changing it clears the field");
 
             ui.separator();
+            if ui
+                .checkbox(&mut config.show_gradient, "Show Gradient Arrows")
+                .on_hover_text("Arrows point up the slope of the surface; waves travel across them")
+                .changed()
+            {
+                config.show_flux &= !config.show_gradient;
+            }
+            if ui
+                .checkbox(&mut config.show_flux, "Show Energy Flux")
+                .on_hover_text("Arrows along −∂u/∂t·∇u: the direction energy is flowing, outward from sources")
+                .changed()
+            {
+                config.show_gradient &= !config.show_flux;
+            }
+            ui.checkbox(&mut config.show_huygens, "Show Wavefronts")
+                .on_hover_text("Huygens construction: crests spreading from each point source at the wave speed");
+            ui.add_enabled(config.absorbing_width() > 0, egui::Checkbox::new(&mut config.show_boundary, "Show Absorbing Layer"))
+                .on_hover_text("Tint the damping border red, deeper where it absorbs harder; measurements there aren't free-space physics");
             ui.label("Color Scheme:");
-            egui::ComboBox::from_id_source("color_scheme")
-                .selected_text(format!("{:?}", config.color_scheme))
-                .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut config.color_scheme, ColorScheme::DeepOcean, "Deep Ocean");
-                    ui.selectable_value(&mut config.color_scheme, ColorScheme::Scientific, "Scientific");
-                    ui.selectable_value(&mut config.color_scheme, ColorScheme::PhaseColor, "Phase Color");
-                    ui.selectable_value(&mut config.color_scheme, ColorScheme::Grayscale, "Grayscale");
-                });
+            color_scheme_combo(ui, "color_scheme", &mut config.color_scheme);
+            ui.checkbox(&mut config.split_view, "Split View")
+                .on_hover_text("Compare two color schemes on the same field, left and right");
+            if config.split_view {
+                color_scheme_combo(ui, "color_scheme_b", &mut config.color_scheme_b);
+                ui.add(egui::Slider::new(&mut config.split_position, 0.0..=1.0).text("Divider"));
+            }
+            ui.checkbox(&mut ui_state.compare_without_slits, "Compare Without Slits")
+                .on_hover_text("Split the window: the tank as built on the left, the same sources with every slit removed on the right");
 
             ui.separator();
             ui.small("Select an object to edit");
         }
+
+        if sync_phases || shared_frequency.is_some() {
+            for (_, mut source) in sources.iter_mut() {
+                if let Some(frequency) = shared_frequency {
+                    source.frequency = frequency;
+                }
+                if sync_phases {
+                    source.phase = 0.0;
+                    source.reset_clock(config.accumulated_time);
+                }
+            }
+        }
     });
 }
 
@@ -209,12 +684,52 @@ pub fn render_inspector_ui(
 // Data Panel (Bottom)
 // ══════════════════════════════════════════════════════════════════════════════
 
+/// Timeline slider over the stored field snapshots; dragging it restores
+/// the nearest one and pauses the tank.
+fn render_history_scrubber(ui: &mut egui::Ui, history: &mut FieldHistory) {
+    ui.label("⏪ Timeline");
+    let Some(last) = history.snapshots.len().checked_sub(1) else {
+        ui.small("recording…");
+        return;
+    };
+
+    let mut index = history.restored.unwrap_or(last);
+    let times: Vec<f32> = history.snapshots.iter().map(|snapshot| snapshot.time).collect();
+    let response = ui
+        .add(egui::Slider::new(&mut index, 0..=last).show_value(false))
+        .on_hover_text(format!(
+            "{} snapshots every {:.2} s ({:.1} MB)",
+            history.snapshots.len(),
+            history.interval,
+            history.memory_bytes() as f32 / 1e6
+        ));
+    if response.changed() {
+        history.restore_request = Some(index);
+    }
+    ui.label(format!("t = {:.2}s", times[index]));
+    if history.restored.is_some() {
+        ui.small("(paused on snapshot; play to resume from here)");
+    }
+    ui.add(egui::DragValue::new(&mut history.interval).range(0.05..=2.0).speed(0.01).prefix("every ").suffix(" s"));
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render_data_panel_ui(
     mut contexts: EguiContexts,
     mut ui_state: ResMut<UIState>,
     stats: Res<SimulationStats>,
     probes: Query<&Probe>,
-    rulers: Query<(&Transform, &Ruler)>,
+    probe_objects: Query<(&SceneObject, &Transform, &Probe)>,
+    rulers: Query<(&Transform, &Ruler), Without<Screen>>,
+    screens: Query<(&SceneObject, &Screen)>,
+    poly_probes: Query<(&SceneObject, &PolyProbe)>,
+    wave_field: Res<WaveField>,
+    spectrogram: Res<Spectrogram>,
+    mut spectrogram_texture: Local<Option<egui::TextureHandle>>,
+    mut history: ResMut<FieldHistory>,
+    mut timer: ResMut<TimeOfFlight>,
+    config: Res<RippleTankConfig>,
+    mut recorder: ResMut<DataRecorder>,
 ) {
     egui::TopBottomPanel::bottom("data_panel")
         .default_height(super::DATA_PANEL_HEIGHT)
@@ -223,6 +738,24 @@ pub fn render_data_panel_ui(
             ui.horizontal(|ui| {
                 ui.heading("📊 Data Lab");
                 ui.checkbox(&mut ui_state.show_data_panel, "Expand");
+
+                let has_data = probe_objects.iter().any(|(_, _, probe)| !probe.history.is_empty());
+                if ui
+                    .add_enabled(has_data, egui::Button::new("📄 Export CSV"))
+                    .on_hover_text("Save every probe's history as CSV columns")
+                    .clicked()
+                {
+                    let mut ordered: Vec<_> = probe_objects.iter().collect();
+                    ordered.sort_by_key(|(object, _, _)| object.id);
+                    export_probe_csv(
+                        ordered.into_iter().map(|(_, _, probe)| probe),
+                        stats.sample_interval,
+                        stats.simulation_time,
+                    );
+                }
+
+                ui.separator();
+                render_history_scrubber(ui, &mut history);
             });
 
             if !ui_state.show_data_panel {
@@ -257,17 +790,8 @@ pub fn render_data_panel_ui(
                             ui.small(format!("[{:.2}, {:.2}]", min, max));
                         });
 
-                        // Simple ASCII waveform display
-                        let width = 40;
-                        let start = probe.history.len().saturating_sub(width);
-                        let wave: String = probe.history[start..].iter()
-                            .map(|&v| {
-                                let normalized = ((v + 2.0) / 4.0).clamp(0.0, 1.0);
-                                let idx = (normalized * 4.0) as usize;
-                                ['▁', '▂', '▄', '▆', '█'][idx.min(4)]
-                            })
-                            .collect();
-                        columns[0].monospace(wave);
+                        line_plot(&mut columns[0], &[Series::new(&probe.label, &probe.history).color(color)], None);
+                        render_spectrum(&mut columns[0], &probe.history, stats.sample_interval, color);
                     }
 
                     if let Some(phase_diff) = stats.probe_phase_diff {
@@ -280,18 +804,502 @@ pub fn render_data_panel_ui(
 
                 columns[1].label(format!("Wave Energy: {:.2}", stats.wave_energy));
 
+                if let (Some(speed), Some(mach)) = (stats.source_speed, stats.mach_number) {
+                    columns[1].label(format!("Moving source: {:.0} units/s (Mach {:.2})", speed, mach));
+                    if let Some(angle) = super::mach_angle(mach) {
+                        columns[1].small(format!("  Mach cone half-angle: {:.1}°", angle.to_degrees()));
+                    }
+                }
+
+                if stats.shock_cone {
+                    columns[1].colored_label(egui::Color32::from_rgb(255, 140, 60), "Doppler: supersonic source — shock cone");
+                } else if let Some(frequency) = stats.observed_frequency {
+                    columns[1].label(format!("Doppler (probe 1): {}", format_with_unit(frequency as f64, Some("Hz"))));
+                }
+
+                let mut timed: Vec<_> = probe_objects.iter().collect();
+                timed.sort_by_key(|(object, _, _)| object.id);
+                let wave_speed = super::wave_speed_world(&config, stats.sample_interval);
+                render_time_of_flight(&mut columns[1], &mut timer, &timed, wave_speed);
+
                 for (transform, ruler) in rulers.iter() {
-                    let length = (ruler.end - ruler.start).length();
                     let pos = transform.translation.truncate();
                     columns[1].label(format!(
-                        "Ruler at ({:.0}, {:.0}): {:.1} units",
-                        pos.x, pos.y, length
+                        "Ruler at ({:.0}, {:.0}): {:.1} units at {:+.1}°",
+                        pos.x,
+                        pos.y,
+                        ruler.length(),
+                        ruler.angle_degrees()
                     ));
+
+                    let (start, end) = ruler.world_endpoints(pos);
+                    let crossings = wave_field.zero_crossings(start, end);
+                    match super::wavelength_from_zero_crossings(&crossings) {
+                        Some(wavelength) => columns[1].small(format!(
+                            "  {} zero crossings → λ ≈ {:.1} units",
+                            crossings.len(),
+                            wavelength
+                        )),
+                        None => columns[1].small("  Drag the ends across at least two fringes"),
+                    };
                 }
 
                 if rulers.is_empty() {
                     columns[1].small("Add rulers to measure wavelength");
                 }
+
+                columns[1].collapsing("⏺ Recorder", |ui| {
+                    if data_recorder_panel(ui, &mut recorder) {
+                        export_recording_csv(&recorder);
+                    }
+                });
+
+                for (object, screen) in screens.iter() {
+                    render_screen(&mut columns[1], object.id, screen);
+                }
+
+                for (object, probe) in poly_probes.iter() {
+                    columns[1].label(format!("Poly Probe #{}: {:.1} units", object.id, probe.arc_length()));
+                    if probe.samples.len() < 2 {
+                        columns[1].small("  Click to add vertices");
+                        continue;
+                    }
+                    line_plot(&mut columns[1], &[Series::new("u", &probe.samples).color(egui::Color32::from_rgb(255, 100, 255))], None);
+                    columns[1].small(format!("  u vs arc length, 0 to {:.0} units", (probe.samples.len() - 1) as f32 * probe.sample_spacing));
+                }
             });
+
+            ui.separator();
+            let label = spectrogram.probe.and_then(|entity| probes.get(entity).ok()).map(|probe| probe.label.as_str());
+            ui.heading(format!("Spectrogram{}", label.map(|label| format!(" ({label})")).unwrap_or_default()));
+            render_spectrogram(ui, &spectrogram, spectrogram.is_changed(), &mut spectrogram_texture, stats.sample_interval);
         });
 }
+
+/// X/Y fields placing an object at an exact position, disabled while it's
+/// locked, and the lock toggle.
+fn render_placement_controls(
+    ui: &mut egui::Ui,
+    placements: &mut Query<(&SceneObject, &mut Transform)>,
+    entity: Entity,
+    commands: &mut Commands,
+) {
+    let Ok((object, mut transform)) = placements.get_mut(entity) else { return };
+    let mut locked = object.locked;
+    if ui
+        .toggle_value(&mut locked, "🔒 Locked")
+        .on_hover_text("Locked objects can't be moved, and clicks pass through them")
+        .changed()
+    {
+        commands.entity(entity).insert(SceneObject { locked, ..*object });
+    }
+    let mut position = transform.translation.truncate();
+    ui.add_enabled_ui(!object.locked, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Position:");
+            ui.add(egui::DragValue::new(&mut position.x).prefix("x ").speed(1.0).max_decimals(1));
+            ui.add(egui::DragValue::new(&mut position.y).prefix("y ").speed(1.0).max_decimals(1));
+        })
+        .response
+        .on_hover_text("Type a value, or drag; arrow keys nudge too");
+    });
+    if position != transform.translation.truncate() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Group, ungroup and lock buttons for a selection of several objects.
+fn render_selection_controls(
+    ui: &mut egui::Ui,
+    selection: &[Entity],
+    placements: &Query<(&SceneObject, &mut Transform)>,
+    groups: &Query<(Entity, &SceneGroup)>,
+    commands: &mut Commands,
+) {
+    ui.label(format!("{} objects selected", selection.len()));
+    ui.small("Shift+click adds or removes objects; dragging one moves them all");
+    let grouped: Vec<u32> = groups.iter_many(selection).map(|(_, group)| group.0).collect();
+    let one_group = grouped.len() == selection.len() && grouped.windows(2).all(|pair| pair[0] == pair[1]);
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!one_group, egui::Button::new("🔗 Group")).clicked() {
+            let id = groups.iter().map(|(_, group)| group.0 + 1).max().unwrap_or(0);
+            for &entity in selection {
+                commands.entity(entity).insert(SceneGroup(id));
+            }
+        }
+        if ui.add_enabled(!grouped.is_empty(), egui::Button::new("Ungroup")).clicked() {
+            for &entity in selection {
+                commands.entity(entity).remove::<SceneGroup>();
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        for (label, locked) in [("🔒 Lock All", true), ("🔓 Unlock All", false)] {
+            if ui.button(label).clicked() {
+                for &entity in selection {
+                    if let Ok((object, _)) = placements.get(entity) {
+                        commands.entity(entity).insert(SceneObject { locked, ..*object });
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Window comparing the selected slit's Fraunhofer pattern, |FFT|² of its
+/// aperture, with the simulated ⟨u²⟩ on an arc behind it.
+pub fn render_far_field_ui(
+    mut contexts: EguiContexts,
+    mut ui_state: ResMut<UIState>,
+    config: Res<RippleTankConfig>,
+    wave_field: Res<WaveField>,
+    obstacles: Query<(&Transform, &Obstacle)>,
+    sources: Query<(&Transform, &WaveSource)>,
+    mut radius: Local<Option<f32>>,
+) {
+    if !ui_state.show_far_field {
+        return;
+    }
+    let Some((transform, obstacle)) = ui_state.selected_entity.and_then(|entity| obstacles.get(entity).ok()) else {
+        return;
+    };
+    if !matches!(obstacle.obstacle_type, ObstacleType::SingleSlit | ObstacleType::DoubleSlit) {
+        return;
+    }
+    let radius = radius.get_or_insert(super::FAR_FIELD_RADIUS);
+
+    let mut open = true;
+    egui::Window::new("🔭 Far-Field Diffraction").open(&mut open).default_width(320.0).show(contexts.ctx_mut(), |ui| {
+        let position = transform.translation.truncate();
+        let Some((source_position, source)) = super::nearest_enabled_source(&sources, position) else {
+            ui.label("Add a source to light the slits");
+            return;
+        };
+        let wavelength = super::wave_speed_world(&config, super::FDTD_STEP) / source.frequency.max(f32::EPSILON);
+        let along = Vec2::from_angle(obstacle.rotation);
+        let across = along.perp();
+        let forward = if (source_position - position).dot(across) > 0.0 { -across } else { across };
+
+        let aperture = aperture_function(&wave_field, position, obstacle);
+        let spacing = wave_field.cell_size;
+        let far_field_distance = fraunhofer_distance(&aperture, spacing, wavelength);
+        ui.label(format!(
+            "λ = {:.1} units · D = {:.1} units · D²/λ = {:.0} units",
+            wavelength,
+            aperture_extent(&aperture, spacing),
+            far_field_distance
+        ));
+
+        ui.add(egui::Slider::new(radius, 20.0..=600.0).text("Arc radius"))
+            .on_hover_text("Distance behind the barrier at which the simulated pattern is sampled");
+        if *radius < far_field_distance {
+            ui.small("Inside D²/λ: the simulated pattern is still the near field (Fresnel)");
+        } else {
+            ui.small("Beyond D²/λ: the simulated pattern should match the far field");
+        }
+
+        let pattern = far_field(&aperture, spacing, wavelength);
+        let simulated = near_field_profile(&wave_field, position, forward, along, *radius, &pattern.sin_theta);
+        line_plot(
+            ui,
+            &[
+                Series::new("|FFT|² far field", &pattern.intensity).color(egui::Color32::from_rgb(255, 200, 80)),
+                Series::new("Simulated ⟨u²⟩", &simulated).color(egui::Color32::from_rgb(100, 180, 255)),
+            ],
+            Some(0.0..=1.0),
+        );
+        if let (Some(first), Some(last)) = (pattern.sin_theta.first(), pattern.sin_theta.last()) {
+            ui.small(format!("sinθ from {:+.2} to {:+.2}, each normalized to its peak", first, last));
+        }
+    });
+    ui_state.show_far_field = open;
+}
+
+/// Lists when the latest pulse reached each probe and, from the first two
+/// probes placed, the wave speed it implies next to the configured one.
+fn render_time_of_flight(ui: &mut egui::Ui, timer: &mut TimeOfFlight, probes: &[(&SceneObject, &Transform, &Probe)], wave_speed: f32) {
+    ui.horizontal(|ui| {
+        ui.label("Time of flight");
+        ui.add(egui::Slider::new(&mut timer.threshold, 0.005..=0.5).logarithmic(true).text("threshold"))
+            .on_hover_text("|u| at which the pulse counts as arrived");
+    });
+    let Some(emitted) = timer.emitted else {
+        ui.small("  Set a source's waveform to Pulse to time it between probes");
+        return;
+    };
+    for (_, _, probe) in probes {
+        ui.small(match probe.arrival {
+            Some(arrival) => format!("  {}: arrived {:.3} s after emission", probe.label, arrival - emitted),
+            None => format!("  {}: waiting for the pulse", probe.label),
+        });
+    }
+
+    let [(_, a_transform, a), (_, b_transform, b), ..] = probes else {
+        ui.small("  Place two probes to measure the wave speed");
+        return;
+    };
+    let (Some(first), Some(second)) = (a.arrival, b.arrival) else { return };
+    let distance = a_transform.translation.truncate().distance(b_transform.translation.truncate());
+    match time_of_flight_speed(distance, first, second) {
+        Some(speed) => {
+            ui.label(format!(
+                "  {} → {}: {:.1} units in {:.3} s = {:.0} units/s",
+                a.label,
+                b.label,
+                distance,
+                (second - first).abs(),
+                speed
+            ));
+            ui.small(format!("  wave_speed setting: {:.0} units/s ({:+.1}%)", wave_speed, (speed / wave_speed - 1.0) * 100.0));
+        }
+        None => {
+            ui.small("  The pulse reached both probes together; move one further from the source");
+        }
+    }
+}
+
+/// Plots a screen's intensity profile and lists its fringes next to the
+/// d·sinθ = mλ prediction, both measured from the central fringe.
+fn render_screen(ui: &mut egui::Ui, id: u32, screen: &Screen) {
+    ui.label(format!("Screen #{}: {} bright fringes", id, screen.fringes.len()));
+    line_plot(ui, &[Series::new("⟨u²⟩", &screen.profile).color(egui::Color32::from_rgb(230, 230, 255))], None);
+
+    let Some(central) = screen.fringes.iter().find(|fringe| fringe.order == 0) else {
+        ui.small("  No fringes yet; let the pattern build up");
+        return;
+    };
+    let predicted_central = screen.predicted.iter().find(|(m, _)| *m == 0).map(|(_, distance)| *distance);
+    for fringe in &screen.fringes {
+        let offset = fringe.distance - central.distance;
+        let predicted = predicted_central.and_then(|center| {
+            let (_, distance) = screen.predicted.iter().find(|(m, _)| *m == fringe.order)?;
+            Some(distance - center)
+        });
+        ui.small(match predicted {
+            Some(predicted) => format!("  m = {:+}: {:+.1} units (d·sinθ = mλ: {:+.1})", fringe.order, offset, predicted),
+            None => format!("  m = {:+}: {:+.1} units", fringe.order, offset),
+        });
+    }
+    if screen.predicted.is_empty() {
+        ui.small("  Add a double slit and a source for the d·sinθ = mλ prediction");
+    }
+}
+
+/// Draws the spectrogram as an image: frequency across, newest spectrum on top.
+fn render_spectrogram(
+    ui: &mut egui::Ui,
+    spectrogram: &Spectrogram,
+    changed: bool,
+    texture: &mut Option<egui::TextureHandle>,
+    sample_interval: f32,
+) {
+    let Some(bins) = spectrogram.rows.back().map(Vec::len) else {
+        ui.small("Place a probe (or select one) and let the tank run to build up a spectrogram");
+        return;
+    };
+
+    if changed || texture.is_none() {
+        let peak = spectrogram.peak().max(f32::EPSILON);
+        let mut image = egui::ColorImage::new([bins, spectrogram.rows.len()], egui::Color32::BLACK);
+        for (pixels, row) in image.pixels.chunks_exact_mut(bins).zip(spectrogram.rows.iter().rev()) {
+            for (pixel, &magnitude) in pixels.iter_mut().zip(row) {
+                let db = 20.0 * (magnitude / peak).max(f32::MIN_POSITIVE).log10();
+                let c = inferno(1.0 - db / super::SPECTROGRAM_FLOOR_DB).to_srgba();
+                *pixel = egui::Color32::from_rgb((c.red * 255.0) as u8, (c.green * 255.0) as u8, (c.blue * 255.0) as u8);
+            }
+        }
+        match texture {
+            Some(handle) => handle.set(image, egui::TextureOptions::NEAREST),
+            None => *texture = Some(ui.ctx().load_texture("spectrogram", image, egui::TextureOptions::NEAREST)),
+        }
+    }
+    let Some(handle) = texture.as_ref() else { return };
+
+    let size = egui::vec2(ui.available_width(), ui.available_height().clamp(60.0, 160.0));
+    ui.add(egui::Image::new((handle.id(), size)));
+    if sample_interval > 0.0 {
+        let nyquist = bins as f32 / (super::SPECTRUM_SIZE as f32 * sample_interval);
+        ui.small(format!(
+            "0 – {:.1} Hz across, last {:.0} s down (newest on top), {:.0} dB range",
+            nyquist,
+            spectrogram.rows.len() as f32 * super::SPECTROGRAM_HOP,
+            -super::SPECTROGRAM_FLOOR_DB,
+        ));
+    }
+}
+
+/// Draws the magnitude spectrum of a probe trace with its peak labeled.
+fn render_spectrum(ui: &mut egui::Ui, history: &[f32], sample_interval: f32, color: egui::Color32) {
+    if history.len() < super::MIN_SPECTRUM_SAMPLES || sample_interval <= 0.0 {
+        ui.small(format!("Spectrum: collecting… ({}/{})", history.len(), super::MIN_SPECTRUM_SAMPLES));
+        return;
+    }
+
+    let spectrum = magnitude_spectrum(history, super::SPECTRUM_SIZE);
+    let bin_hz = 1.0 / (super::SPECTRUM_SIZE as f32 * sample_interval);
+    let (peak_bin, peak) = spectrum
+        .iter()
+        .copied()
+        .enumerate()
+        .skip(1)
+        .fold((0, 0.0f32), |best, (bin, value)| if value > best.1 { (bin, value) } else { best });
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 40.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+    if peak > 0.0 {
+        let bar_width = rect.width() / spectrum.len() as f32;
+        for (bin, value) in spectrum.iter().enumerate() {
+            let height = value / peak * rect.height();
+            let x = rect.left() + bin as f32 * bar_width;
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(x, rect.bottom() - height), egui::pos2(x + bar_width.max(1.0), rect.bottom())),
+                0.0,
+                color,
+            );
+        }
+    }
+
+    ui.small(format!("Peak: {:.2} Hz (±{:.2})", peak_bin as f32 * bin_hz, bin_hz / 2.0));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Minimap
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Overview of the whole tank in the corner of the viewport: the field
+/// downsampled, a dot per scene object, and the camera's view as a frame.
+/// Clicking or dragging in it recenters the camera.
+#[allow(clippy::too_many_arguments)]
+pub fn render_minimap_ui(
+    mut contexts: EguiContexts,
+    ui_state: Res<UIState>,
+    config: Res<RippleTankConfig>,
+    wave_field: Res<WaveField>,
+    objects: Query<(&Transform, &Sprite), With<SceneObject>>,
+    mut camera: Query<(&mut Transform, &OrthographicProjection), Without<SceneObject>>,
+    mut texture: Local<Option<egui::TextureHandle>>,
+) {
+    if !ui_state.show_minimap {
+        return;
+    }
+    let Ok((mut camera_transform, projection)) = camera.get_single_mut() else { return };
+
+    let ctx = contexts.ctx_mut();
+    let image = minimap_image(&wave_field, config.color_scheme);
+    let handle = match texture.as_mut() {
+        Some(handle) => {
+            handle.set(image, egui::TextureOptions::LINEAR);
+            handle
+        }
+        None => texture.insert(ctx.load_texture("minimap", image, egui::TextureOptions::LINEAR)),
+    };
+
+    let tank_size = Vec2::new(wave_field.width as f32, wave_field.height as f32) * wave_field.cell_size;
+    let size = egui::vec2(super::MINIMAP_WIDTH, super::MINIMAP_WIDTH * tank_size.y / tank_size.x);
+    let corner = ctx.available_rect().right_bottom() - egui::vec2(8.0, 8.0);
+    egui::Area::new(egui::Id::new("minimap")).fixed_pos(corner - size).order(egui::Order::Foreground).show(ctx, |ui| {
+        let response = ui.add(egui::Image::new((handle.id(), size)).sense(egui::Sense::click_and_drag()));
+        let rect = response.rect;
+        let to_screen = |world: Vec2| {
+            let t = world / tank_size + 0.5;
+            egui::pos2(rect.left() + t.x * rect.width(), rect.bottom() - t.y * rect.height())
+        };
+
+        let painter = ui.painter_at(rect);
+        for (transform, sprite) in objects.iter() {
+            let [r, g, b, _] = sprite.color.to_srgba().to_u8_array();
+            let position = to_screen(transform.translation.truncate());
+            painter.circle(position, 2.5, egui::Color32::from_rgb(r, g, b), egui::Stroke::new(1.0, egui::Color32::BLACK));
+        }
+
+        let center = camera_transform.translation.truncate();
+        let view = egui::Rect::from_two_pos(
+            to_screen(center + projection.area.min),
+            to_screen(center + projection.area.max),
+        );
+        painter.rect_stroke(view, 0.0, egui::Stroke::new(1.5, egui::Color32::WHITE));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::from_gray(120)));
+
+        if let Some(pointer) = response.interact_pointer_pos().filter(|_| response.clicked() || response.dragged()) {
+            let t = (pointer - rect.left_bottom()) / rect.size();
+            let target = (Vec2::new(t.x, -t.y) - 0.5) * tank_size;
+            camera_transform.translation.x = target.x;
+            camera_transform.translation.y = target.y;
+        }
+    });
+}
+
+/// The field colored with `scheme`, one nearest-neighbor sample per
+/// minimap pixel.
+fn minimap_image(wave_field: &WaveField, scheme: ColorScheme) -> egui::ColorImage {
+    let width = super::MINIMAP_WIDTH as usize;
+    let height = (width * wave_field.height / wave_field.width).max(1);
+    let values = if scheme == ColorScheme::Intensity { &wave_field.intensity } else { &wave_field.current };
+
+    let mut image = egui::ColorImage::new([width, height], egui::Color32::BLACK);
+    for (row, pixels) in image.pixels.chunks_exact_mut(width).enumerate() {
+        let y = (height - 1 - row) * wave_field.height / height;
+        for (column, pixel) in pixels.iter_mut().enumerate() {
+            let idx = wave_field.idx(column * wave_field.width / width, y);
+            let (r, g, b) = super::wave_pixel_color(values[idx], wave_field.obstacle_map[idx], scheme);
+            *pixel = egui::Color32::from_rgb(r, g, b);
+        }
+    }
+    image
+}
+
+/// Preset picker for the global settings. Presets are applied through the
+/// same parameter ids the settings persist under; editing any of them
+/// afterwards shows the settings as custom again.
+fn render_presets(ui: &mut egui::Ui, config: &mut RippleTankConfig, params: &mut SimulationParameters) {
+    for (id, value) in config.saved_parameters().into_iter().chain([("paused", ParameterValue::Bool(config.paused))]) {
+        if params.values.get(id) != Some(&value) {
+            params.values.insert(id, value);
+            params.preset = None;
+        }
+    }
+
+    let before = params.values.clone();
+    render_parameter_panel(ui, &[], &super::RippleTankSimulation.presets(), params);
+    if params.values != before {
+        config.apply_parameters(params);
+        if let Some(paused) = params.get_bool("paused") {
+            config.paused = paused;
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Settings Persistence
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Restores saved settings the first time the tank runs, then saves them
+/// once a change has held still for [`super::SETTINGS_SAVE_DELAY`], so
+/// dragging a slider writes the file once rather than every frame.
+pub fn persist_settings(
+    mut config: ResMut<RippleTankConfig>,
+    time: Res<Time<Real>>,
+    mut saved: Local<Option<SimulationParameters>>,
+    mut last_change: Local<Option<f32>>,
+) {
+    let Some(params) = saved.as_mut() else {
+        let params = SimulationParameters::load(super::SIMULATION_ID, &super::RippleTankSimulation.parameters());
+        config.apply_parameters(&params);
+        *saved = Some(params);
+        return;
+    };
+
+    let now = time.elapsed_seconds();
+    for (id, value) in config.saved_parameters() {
+        if params.values.get(id) != Some(&value) {
+            params.values.insert(id, value);
+            *last_change = Some(now);
+        }
+    }
+    if last_change.is_some_and(|changed| now - changed >= super::SETTINGS_SAVE_DELAY) {
+        params.save(super::SIMULATION_ID);
+        *last_change = None;
+    }
+}
@@ -0,0 +1,230 @@
+//! Undo and redo for scene edits
+//!
+//! Rather than every tool and inspector widget reporting what it changed,
+//! [`update_undo_history`] compares the scene with the last state it saw
+//! whenever the user isn't mid-gesture, and records the difference as one
+//! edit. Each change keeps the whole [`SavedObject`] on either side of it,
+//! so undoing a delete respawns the object with every saved parameter.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+
+use super::{components::*, resources::*, scene::*, UNDO_DEPTH};
+
+/// One object's state before and after an edit: spawned objects have no
+/// `before`, deleted ones no `after`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectChange {
+    pub id: u32,
+    pub before: Option<SavedObject>,
+    pub after: Option<SavedObject>,
+}
+
+impl ObjectChange {
+    /// The change that takes the object back again.
+    pub fn inverse(&self) -> Self {
+        Self { id: self.id, before: self.after.clone(), after: self.before.clone() }
+    }
+}
+
+/// Every object a single user action changed.
+pub type SceneEdit = Vec<ObjectChange>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoRequest {
+    Undo,
+    Redo,
+}
+
+/// Recorded scene edits, newest last, keeping at most [`UNDO_DEPTH`].
+#[derive(Resource, Default)]
+pub struct UndoHistory {
+    pub undo: VecDeque<SceneEdit>,
+    pub redo: Vec<SceneEdit>,
+    /// Step asked for by a shortcut or button, taken by [`update_undo_history`].
+    pub request: Option<UndoRequest>,
+    /// Scene as last seen, by object id; `None` until the first look, so the
+    /// objects already there don't count as an edit.
+    baseline: Option<BTreeMap<u32, SavedObject>>,
+}
+
+impl UndoHistory {
+    /// Compares `scene` with the last state seen and records any difference
+    /// as one edit, which clears the redo stack.
+    pub fn record(&mut self, scene: BTreeMap<u32, SavedObject>) {
+        let Some(baseline) = self.baseline.replace(scene) else { return };
+        let scene = self.baseline.as_ref().expect("just replaced");
+
+        let mut edit: SceneEdit = baseline
+            .iter()
+            .filter(|(id, before)| scene.get(id).is_none_or(|after| !same_edit_state(before, after)))
+            .map(|(&id, before)| ObjectChange { id, before: Some(before.clone()), after: scene.get(&id).cloned() })
+            .collect();
+        edit.extend(
+            scene
+                .iter()
+                .filter(|(id, _)| !baseline.contains_key(id))
+                .map(|(&id, after)| ObjectChange { id, before: None, after: Some(after.clone()) }),
+        );
+        if edit.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        if self.undo.len() >= UNDO_DEPTH {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(edit);
+    }
+
+    /// Moves the newest edit to the other stack, returning the changes that
+    /// revert it (undo) or reapply it (redo).
+    pub fn step(&mut self, request: UndoRequest) -> Option<SceneEdit> {
+        let changes = match request {
+            UndoRequest::Undo => {
+                let edit = self.undo.pop_back()?;
+                let changes = edit.iter().map(ObjectChange::inverse).collect();
+                self.redo.push(edit);
+                changes
+            }
+            UndoRequest::Redo => {
+                let edit = self.redo.pop()?;
+                let changes = edit.clone();
+                self.undo.push_back(edit);
+                changes
+            }
+        };
+
+        // The scene is about to match, so the step isn't itself an edit.
+        if let Some(baseline) = &mut self.baseline {
+            for change in &changes {
+                match &change.after {
+                    Some(after) => baseline.insert(change.id, after.clone()),
+                    None => baseline.remove(&change.id),
+                };
+            }
+        }
+        Some(changes)
+    }
+}
+
+/// Whether two states of an object differ only in what the simulation
+/// itself changes: where a moving source has travelled to and when a
+/// sweep began.
+fn same_edit_state(a: &SavedObject, b: &SavedObject) -> bool {
+    fn settled(object: &SavedObject) -> SavedObject {
+        let mut object = object.clone();
+        if let SavedKind::Source(source) = &mut object.kind {
+            if let Some(sweep) = &mut source.sweep {
+                sweep.started = None;
+            }
+            if let Some(motion) = &mut source.motion {
+                motion.angle = 0.0;
+                object.position = [0.0, 0.0, object.position[2]];
+            }
+        }
+        object
+    }
+    settled(a) == settled(b)
+}
+
+/// Applies an undo or redo that was asked for, or else records the scene
+/// once the user has let go of the mouse and left any text field, so a
+/// drag or a slider adjustment becomes one edit.
+///
+/// Changed objects are despawned and respawned from their saved state
+/// under their old id; probe histories and other measurements on them
+/// start over.
+#[allow(clippy::too_many_arguments)]
+pub fn update_undo_history(
+    mut history: ResMut<UndoHistory>,
+    mut commands: Commands,
+    mut object_id: ResMut<ObjectIdCounter>,
+    mut ui_state: ResMut<UIState>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut contexts: EguiContexts,
+    scene_objects: Query<SceneObjectData>,
+    entities: Query<(Entity, &SceneObject)>,
+) {
+    if let Some(request) = history.request.take() {
+        let Some(changes) = history.step(request) else { return };
+        ui_state.dragging = None;
+        ui_state.dragging_ruler_end = None;
+        ui_state.placing_poly_probe = None;
+
+        for change in &changes {
+            let (mut was_selected, mut was_in_selection) = (false, false);
+            for (entity, _) in entities.iter().filter(|(_, object)| object.id == change.id) {
+                was_selected |= ui_state.selected_entity == Some(entity);
+                was_in_selection |= ui_state.selection.contains(&entity);
+                ui_state.selection.retain(|&selected| selected != entity);
+                commands.entity(entity).despawn_recursive();
+            }
+            let respawned = change.after.as_ref().map(|after| {
+                let entity = spawn_object(&mut commands, &mut object_id, after);
+                commands.entity(entity).insert(SceneObject { id: change.id, selected: false, locked: after.locked });
+                entity
+            });
+            if was_selected {
+                ui_state.selected_entity = respawned;
+            }
+            if was_in_selection {
+                ui_state.selection.extend(respawned);
+            }
+        }
+        return;
+    }
+
+    let ctx = contexts.ctx_mut();
+    if mouse_button.pressed(MouseButton::Left) || ctx.is_using_pointer() || ctx.wants_keyboard_input() {
+        return;
+    }
+    history.record(scene_objects.iter().filter_map(capture_object).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(x: f32) -> SavedObject {
+        SavedObject {
+            position: [x, 0.0, 2.0],
+            locked: false,
+            group: None,
+            kind: SavedKind::Probe(SavedProbe { label: "P".into(), color: [1.0; 4] }),
+        }
+    }
+
+    #[test]
+    fn test_undo_reverts_spawn_move_and_delete() {
+        let mut history = UndoHistory::default();
+        history.record(BTreeMap::from([(0, probe(0.0))]));
+        assert!(history.undo.is_empty(), "the starting scene isn't an edit");
+
+        history.record(BTreeMap::from([(0, probe(0.0)), (1, probe(5.0))]));
+        history.record(BTreeMap::from([(0, probe(0.0)), (1, probe(9.0))]));
+        history.record(BTreeMap::from([(1, probe(9.0))]));
+        history.record(BTreeMap::from([(1, probe(9.0))]));
+        assert_eq!(history.undo.len(), 3);
+
+        // Undoing the delete respawns the whole object.
+        let changes = history.step(UndoRequest::Undo).expect("a delete to undo");
+        assert_eq!(changes, vec![ObjectChange { id: 0, before: None, after: Some(probe(0.0)) }]);
+        let changes = history.step(UndoRequest::Undo).expect("a move to undo");
+        assert_eq!(changes[0].after, Some(probe(5.0)));
+
+        // Stepping leaves nothing new to record; a fresh edit drops the redo.
+        history.record(BTreeMap::from([(0, probe(0.0)), (1, probe(5.0))]));
+        assert_eq!((history.undo.len(), history.redo.len()), (1, 2));
+        assert_eq!(history.step(UndoRequest::Redo).expect("a move to redo")[0].after, Some(probe(9.0)));
+        history.record(BTreeMap::from([(0, probe(3.0)), (1, probe(9.0))]));
+        assert!(history.redo.is_empty());
+
+        for x in 0..UNDO_DEPTH * 2 {
+            history.record(BTreeMap::from([(0, probe(x as f32))]));
+        }
+        assert_eq!(history.undo.len(), UNDO_DEPTH);
+    }
+}